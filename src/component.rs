@@ -0,0 +1,227 @@
+use crate::properties::cal_adress::CalAdress;
+use crate::properties::status::Status;
+use crate::properties::Property;
+use crate::valarm::VAlarm;
+use crate::vevent::VEvent;
+use crate::vfreebusy::VFreeBusy;
+use crate::vjournal::VJournal;
+use crate::vtodo::VTodo;
+
+#[cfg(test)]
+use chrono::{FixedOffset, TimeZone};
+
+/// The components that the crate can read.
+pub const SUPPORTED_COMPONENTS: &[&str] = &[
+    "VCALENDAR",
+    "VTODO",
+    "VEVENT",
+    "VJOURNAL",
+    "VALARM",
+    "VTIMEZONE",
+    "VFREEBUSY",
+];
+
+/// The type of a component, without its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentKind {
+    Todo,
+    Event,
+    Journal,
+    FreeBusy,
+}
+
+/// A property whose value differs between two versions of a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldChange {
+    Added(Property),
+    Removed(Property),
+    Changed(Property),
+}
+
+impl FieldChange {
+    /// Compares the values of a property, None or an empty list meaning it is not set.
+    pub fn compare<T: PartialEq + ?Sized>(
+        property: Property,
+        before: Option<&T>,
+        after: Option<&T>,
+    ) -> Option<FieldChange> {
+        match (before, after) {
+            (None, Some(_)) => Some(FieldChange::Added(property)),
+            (Some(_), None) => Some(FieldChange::Removed(property)),
+            (Some(before), Some(after)) if before != after => Some(FieldChange::Changed(property)),
+            _ => None,
+        }
+    }
+
+    /// Compares the values of a property that can be repeated.
+    pub fn compare_list<T: PartialEq>(
+        property: Property,
+        before: &[T],
+        after: &[T],
+    ) -> Option<FieldChange> {
+        FieldChange::compare(
+            property,
+            Some(before).filter(|before| !before.is_empty()),
+            Some(after).filter(|after| !after.is_empty()),
+        )
+    }
+
+    /// Returns the property that changed.
+    pub fn property(&self) -> Property {
+        match self {
+            FieldChange::Added(property)
+            | FieldChange::Removed(property)
+            | FieldChange::Changed(property) => *property,
+        }
+    }
+}
+
+/// A reference to one of the components held by a calendar.
+#[derive(Debug, Clone, Copy)]
+pub enum Component<'a> {
+    Todo(&'a VTodo),
+    Event(&'a VEvent),
+    Journal(&'a VJournal),
+    FreeBusy(&'a VFreeBusy),
+}
+
+impl<'a> Component<'a> {
+    /// Returns the type of the component.
+    pub fn kind(&self) -> ComponentKind {
+        match self {
+            Component::Todo(_) => ComponentKind::Todo,
+            Component::Event(_) => ComponentKind::Event,
+            Component::Journal(_) => ComponentKind::Journal,
+            Component::FreeBusy(_) => ComponentKind::FreeBusy,
+        }
+    }
+
+    /// Returns a label to display the component, its SUMMARY or its UID if it has none.
+    pub fn title(&self) -> &'a str {
+        match self {
+            Component::Todo(vtodo) => vtodo.summary.as_deref().unwrap_or(&vtodo.uid),
+            Component::Event(vevent) => vevent.summary.as_deref().unwrap_or(&vevent.uid),
+            Component::Journal(vjournal) => vjournal.summary.as_deref().unwrap_or(&vjournal.uid),
+            // VFREEBUSY has no SUMMARY
+            Component::FreeBusy(vfreebusy) => &vfreebusy.uid,
+        }
+    }
+
+    /// Returns the UID of the component.
+    pub fn uid(&self) -> Option<&'a str> {
+        match self {
+            Component::Todo(vtodo) => Some(&vtodo.uid),
+            Component::Event(vevent) => Some(&vevent.uid),
+            Component::Journal(vjournal) => Some(&vjournal.uid),
+            Component::FreeBusy(vfreebusy) => Some(&vfreebusy.uid),
+        }
+    }
+
+    /// Returns true if the component overrides an occurrence of a recurring component.
+    pub fn has_recurrence_id(&self) -> bool {
+        match self {
+            Component::Todo(vtodo) => vtodo.recurrence_id.is_some(),
+            Component::Event(vevent) => vevent.recurrence_id.is_some(),
+            Component::Journal(vjournal) => vjournal.recurrence_id.is_some(),
+            Component::FreeBusy(_) => false,
+        }
+    }
+
+    /// Returns the STATUS of the component, VFREEBUSY has none.
+    pub fn status(&self) -> Option<&'a Status> {
+        match self {
+            Component::Todo(vtodo) => vtodo.status.as_ref(),
+            Component::Event(vevent) => vevent.status.as_ref(),
+            Component::Journal(vjournal) => vjournal.status.as_ref(),
+            Component::FreeBusy(_) => None,
+        }
+    }
+
+    /// Returns the organizer of the component, if it has one.
+    pub fn organizer(&self) -> Option<&'a CalAdress> {
+        match self {
+            Component::Todo(vtodo) => vtodo.organizer.as_ref(),
+            Component::Event(vevent) => vevent.organizer.as_ref(),
+            Component::Journal(vjournal) => vjournal.organizer.as_ref(),
+            Component::FreeBusy(vfreebusy) => vfreebusy.organizer.as_ref(),
+        }
+    }
+
+    /// Returns a stable hash of the content of the component, usable as an HTTP ETag.
+    /// DTSTAMP is left out as it changes every time the component is exported.
+    pub fn etag(&self) -> String {
+        let content = match self {
+            Component::Todo(vtodo) => vtodo.write(),
+            Component::Event(vevent) => vevent.write(),
+            Component::Journal(vjournal) => vjournal.write(),
+            Component::FreeBusy(vfreebusy) => vfreebusy.write(),
+        };
+
+        // FNV-1a, unlike the std hashers it does not change between Rust versions
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for line in content.replace("\r\n ", "").lines() {
+            if line.starts_with("DTSTAMP:") || line.starts_with("DTSTAMP;") {
+                continue;
+            }
+            for byte in line.bytes().chain(std::iter::once(b'\n')) {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        format!("{hash:016x}")
+    }
+
+    /// Returns the alarms nested in the component, VJOURNAL and VFREEBUSY cannot have any.
+    pub fn alarms(&self) -> &'a [VAlarm] {
+        match self {
+            Component::Todo(vtodo) => &vtodo.alarms,
+            Component::Event(vevent) => &vevent.alarms,
+            Component::Journal(_) | Component::FreeBusy(_) => &[],
+        }
+    }
+}
+
+#[test]
+fn title() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    assert_eq!(
+        Component::Todo(&vtodo).title(),
+        "20070514T103211Z-123404@example.com"
+    );
+
+    vtodo.summary = Some("Submit Revised Internet-Draft".to_string());
+    assert_eq!(
+        Component::Todo(&vtodo).title(),
+        "Submit Revised Internet-Draft"
+    );
+}
+
+#[test]
+fn supported_components() {
+    assert!(!SUPPORTED_COMPONENTS.is_empty());
+    assert!(SUPPORTED_COMPONENTS.contains(&"VTODO"));
+}
+
+#[test]
+fn etag() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.summary = Some("Submit Revised Internet-Draft".to_string());
+
+    // Only the DTSTAMP differs
+    let mut other = vtodo.clone();
+    other.dtstamp = dtstamp + chrono::Duration::days(1);
+    let etag = Component::Todo(&vtodo).etag();
+    assert_eq!(etag, Component::Todo(&other).etag());
+    assert_eq!(etag.len(), 16);
+
+    other.summary = Some("Submit Final Internet-Draft".to_string());
+    assert_ne!(etag, Component::Todo(&other).etag());
+}
@@ -1,4 +1,4 @@
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, io};
 
 /// This is the list of all possible errors linked to the database.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -29,12 +29,147 @@ pub enum ICSError {
     NotICSFile,
     /// The file reader has failed reading the file
     ReadError,
+    /// The input has more lines or longer lines than allowed by the parse options
+    InputTooLarge,
+    /// The input is not encoded in UTF-8, the string gives the detected encoding and how to fix it
+    UnsupportedEncoding(String),
+    /// The TZID is neither defined by a VTIMEZONE of the calendar nor a known time zone name
+    UnknownTimezone(String),
 }
 
 impl Error for ICSError {}
 
 impl fmt::Display for ICSError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "")
+        match self {
+            ICSError::MissingNecessaryProperty(property) => {
+                write!(f, "missing required property: {property}")
+            }
+            ICSError::DuplicateUniqueProperty(property) => {
+                write!(f, "property must not occur more than once: {property}")
+            }
+            ICSError::BeginWithoutEnd => write!(f, "component has a BEGIN without its END"),
+            ICSError::NoBegin => write!(f, "input does not start with BEGIN:VCALENDAR"),
+            ICSError::UnableToParseProperty(property) => {
+                write!(f, "unable to parse property: {property}")
+            }
+            ICSError::UknownProperty(property) => write!(f, "unknown property: {property}"),
+            ICSError::UnexpectedProperty(property) => {
+                write!(f, "property not expected in this component: {property}")
+            }
+            ICSError::PropertyConditionNotRespected(property) => {
+                write!(f, "invalid property value: {property}")
+            }
+            ICSError::InvalidBeginLine(line) => write!(f, "invalid BEGIN line: {line}"),
+            ICSError::UnknownComponent(component) => write!(f, "unknown component: {component}"),
+            ICSError::UnexpectedComponent(component) => {
+                write!(f, "component not expected here: {component}")
+            }
+            ICSError::NotICSFile => write!(f, "file does not have the ics extension"),
+            ICSError::ReadError => write!(f, "unable to read the input"),
+            ICSError::InputTooLarge => write!(f, "input exceeds the size limits"),
+            ICSError::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported encoding: {encoding}")
+            }
+            ICSError::UnknownTimezone(tzid) => write!(f, "unknown time zone: {tzid}"),
+        }
     }
 }
+
+/// A problem found while reading a calendar, along with the line it was found on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Number of the line, counted from 1, or 0 when the problem concerns the whole file
+    pub line: usize,
+    pub error: ICSError,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl From<io::Error> for ICSError {
+    /// Recovers the error given to the reader, such as a size limit, any other is a read error.
+    fn from(error: io::Error) -> Self {
+        match error
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<ICSError>())
+        {
+            Some(ics_error) => ics_error.clone(),
+            None => ICSError::ReadError,
+        }
+    }
+}
+
+#[test]
+fn display() {
+    let cases = [
+        (
+            ICSError::MissingNecessaryProperty("UID".to_string()),
+            "missing required property: UID",
+        ),
+        (
+            ICSError::DuplicateUniqueProperty("SUMMARY:Second".to_string()),
+            "property must not occur more than once: SUMMARY:Second",
+        ),
+        (
+            ICSError::BeginWithoutEnd,
+            "component has a BEGIN without its END",
+        ),
+        (
+            ICSError::NoBegin,
+            "input does not start with BEGIN:VCALENDAR",
+        ),
+        (
+            ICSError::UnableToParseProperty("GEO".to_string()),
+            "unable to parse property: GEO",
+        ),
+        (
+            ICSError::UknownProperty("X-FOO".to_string()),
+            "unknown property: X-FOO",
+        ),
+        (
+            ICSError::UnexpectedProperty("DUE:20070313T123432Z".to_string()),
+            "property not expected in this component: DUE:20070313T123432Z",
+        ),
+        (
+            ICSError::PropertyConditionNotRespected("PRIORITY must be 0..=9".to_string()),
+            "invalid property value: PRIORITY must be 0..=9",
+        ),
+        (
+            ICSError::InvalidBeginLine("BEGIN".to_string()),
+            "invalid BEGIN line: BEGIN",
+        ),
+        (
+            ICSError::UnknownComponent("VFOO".to_string()),
+            "unknown component: VFOO",
+        ),
+        (
+            ICSError::UnexpectedComponent("VEVENT".to_string()),
+            "component not expected here: VEVENT",
+        ),
+        (ICSError::NotICSFile, "file does not have the ics extension"),
+        (ICSError::ReadError, "unable to read the input"),
+        (ICSError::InputTooLarge, "input exceeds the size limits"),
+        (
+            ICSError::UnsupportedEncoding("UTF-16LE input".to_string()),
+            "unsupported encoding: UTF-16LE input",
+        ),
+        (
+            ICSError::UnknownTimezone("Mars/Olympus_Mons".to_string()),
+            "unknown time zone: Mars/Olympus_Mons",
+        ),
+    ];
+    for (error, message) in cases {
+        assert_eq!(error.to_string(), message);
+    }
+
+    // The message is kept when the error is boxed
+    let boxed: Box<dyn Error> = Box::new(ICSError::NoBegin);
+    assert_eq!(
+        boxed.to_string(),
+        "input does not start with BEGIN:VCALENDAR"
+    );
+}
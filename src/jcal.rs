@@ -0,0 +1,744 @@
+/*
+jCal, RFC 7265, gives the content of a calendar as JSON arrays:
+
+  component  = [name, [property, ...], [component, ...]]
+
+  property   = [name, {parameter: value, ...}, type, value, ...]
+
+The names are in lowercase, the type is the one the VALUE parameter would give,
+and the times are written as in ISO 8601, 2007-05-14T10:32:11Z.
+*/
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, Utc};
+use serde_json::{json, Map, Value};
+
+use crate::component::Component;
+use crate::properties::attachment::Attachment;
+use crate::properties::cal_adress::CalAdress;
+use crate::properties::date_time_value::DateTimeValue;
+use crate::properties::free_busy::FreeBusy;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::period::Period;
+use crate::properties::recur::Recur;
+use crate::properties::related_to::RelatedTo;
+use crate::properties::trigger::Trigger;
+use crate::properties::x_prop::XProp;
+use crate::properties::{Parameters, Property};
+use crate::utils;
+use crate::valarm::VAlarm;
+use crate::vcalendar::VCalendar;
+use crate::vevent::VEvent;
+use crate::vfreebusy::VFreeBusy;
+use crate::vjournal::VJournal;
+use crate::vtimezone::{Observance, VTimezone};
+use crate::vtodo::VTodo;
+
+/// The properties of a component in the jCal form, along with the parameters they were read with.
+struct Properties<'a> {
+    parameters: &'a [(Property, Parameters)],
+    properties: Vec<Value>,
+}
+
+impl<'a> Properties<'a> {
+    fn new(parameters: &'a [(Property, Parameters)]) -> Properties<'a> {
+        Properties {
+            parameters,
+            properties: Vec::new(),
+        }
+    }
+
+    /// Adds a property with the given parameters, those read with it are not looked up.
+    fn push_with(
+        &mut self,
+        name: &str,
+        parameters: Option<&Parameters>,
+        value_type: &str,
+        values: Vec<Value>,
+    ) {
+        let mut property = vec![
+            Value::from(name.to_lowercase()),
+            Value::Object(parameters_object(parameters)),
+            Value::from(value_type),
+        ];
+        property.extend(values);
+        self.properties.push(Value::Array(property));
+    }
+
+    fn push(&mut self, property: Property, value_type: &str, values: Vec<Value>) {
+        let parameters = utils::find_parameters(self.parameters, property);
+        self.push_with(property.get_identier(), parameters, value_type, values);
+    }
+
+    fn text(&mut self, property: Property, value: &str) {
+        self.push(property, "text", vec![Value::from(value)]);
+    }
+
+    fn integer(&mut self, property: Property, value: usize) {
+        self.push(property, "integer", vec![Value::from(value)]);
+    }
+
+    fn duration(&mut self, property: Property, value: &Duration) {
+        self.push(
+            property,
+            "duration",
+            vec![Value::from(utils::format_duration(value))],
+        );
+    }
+
+    fn uri(&mut self, property: Property, value: &impl ToString) {
+        self.push(property, "uri", vec![Value::from(value.to_string())]);
+    }
+
+    fn date_time(&mut self, property: Property, value: &DateTime<FixedOffset>) {
+        let parameters = utils::find_parameters(self.parameters, property);
+        let (value_type, value) = date_time(value, parameters);
+        self.push_with(
+            property.get_identier(),
+            parameters,
+            value_type,
+            vec![Value::from(value)],
+        );
+    }
+
+    fn cal_address(&mut self, property: Property, value: &CalAdress) {
+        self.push_with(
+            property.get_identier(),
+            Some(&value.parameters()),
+            "cal-address",
+            vec![Value::from(value.address.to_string())],
+        );
+    }
+
+    fn geo(&mut self, (lat, long): (f32, f32)) {
+        // Going through the text keeps the decimals that were read
+        let float = |value: f32| Value::from(value.to_string().parse::<f64>().unwrap_or_default());
+        self.push(
+            Property::Geo,
+            "float",
+            vec![json!([float(lat), float(long)])],
+        );
+    }
+
+    fn recur(&mut self, value: &Recur) {
+        self.push(Property::RRule, "recur", vec![recur(value)]);
+    }
+
+    fn attachments(&mut self, attachments: &[Attachment]) {
+        for (index, attachment) in attachments.iter().enumerate() {
+            let parameters = utils::find_nth_parameters(self.parameters, Property::Attach, index);
+            let value_type = match attachment {
+                Attachment::Uri(_) => "uri",
+                Attachment::Binary(_) => "binary",
+            };
+            self.push_with(
+                "ATTACH",
+                Some(&attachment.parameters(parameters)),
+                value_type,
+                vec![Value::from(attachment.to_string())],
+            );
+        }
+    }
+
+    fn related_to(&mut self, related_to: &[RelatedTo]) {
+        for related_to in related_to {
+            self.push_with(
+                "RELATED-TO",
+                Some(&related_to.parameters()),
+                "text",
+                vec![Value::from(related_to.value.clone())],
+            );
+        }
+    }
+
+    /// Adds the non-standard and unknown registered properties, whose type is unknown.
+    fn extensions(&mut self, x_properties: &[XProp], iana_properties: &[IanaProp]) {
+        let properties = x_properties
+            .iter()
+            .map(|x_prop| (&x_prop.name, &x_prop.parameters, &x_prop.value))
+            .chain(
+                iana_properties
+                    .iter()
+                    .map(|iana_prop| (&iana_prop.name, &iana_prop.parameters, &iana_prop.value)),
+            );
+        for (name, parameters, value) in properties {
+            let value_type = parameters
+                .get("VALUE")
+                .map(str::to_lowercase)
+                .unwrap_or_else(|| "unknown".to_string());
+            self.push_with(
+                name,
+                Some(parameters),
+                &value_type,
+                vec![Value::from(value.clone())],
+            );
+        }
+    }
+
+    fn into_value(self) -> Value {
+        Value::Array(self.properties)
+    }
+}
+
+/// Writes the parameters as a JSON object, VALUE is left out as it is given by the type.
+fn parameters_object(parameters: Option<&Parameters>) -> Map<String, Value> {
+    parameters
+        .map(|parameters| {
+            parameters
+                .canonical()
+                .into_iter()
+                .filter(|(name, _)| !name.eq_ignore_ascii_case("VALUE"))
+                .map(|(name, value)| (name.to_lowercase(), Value::from(value.replace('"', ""))))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn component(name: &str, properties: Properties, components: Vec<Value>) -> Value {
+    json!([name, properties.into_value(), components])
+}
+
+/// Returns the type and the ISO 8601 form of a time, keeping the form it was read in.
+fn date_time(
+    value: &DateTime<FixedOffset>,
+    parameters: Option<&Parameters>,
+) -> (&'static str, String) {
+    match DateTimeValue::new(value, parameters) {
+        DateTimeValue::Date(date) => ("date", date.format("%Y-%m-%d").to_string()),
+        DateTimeValue::Utc(date_time) => ("date-time", utc(&date_time)),
+        DateTimeValue::Local(date_time) | DateTimeValue::Zoned(_, date_time) => {
+            ("date-time", local(&date_time))
+        }
+    }
+}
+
+fn utc(value: &DateTime<Utc>) -> String {
+    value.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn local(value: &NaiveDateTime) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+fn utc_offset(value: &FixedOffset) -> String {
+    let seconds = value.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+    let mut output = format!("{sign}{:02}:{:02}", seconds / 3600, seconds / 60 % 60);
+    if seconds % 60 != 0 {
+        output.push_str(&format!(":{:02}", seconds % 60));
+    }
+    output
+}
+
+fn period(value: &Period) -> Value {
+    let start = utc(&value.start().with_timezone(&Utc));
+    Value::from(match value {
+        Period::Explicit(_, end) => format!("{start}/{}", utc(&end.with_timezone(&Utc))),
+        Period::Start(_, duration) => format!("{start}/{}", utils::format_duration(duration)),
+    })
+}
+
+/// FREQ=WEEKLY;COUNT=5;BYDAY=MO,WE is written {"freq":"WEEKLY","count":5,"byday":["MO","WE"]}.
+fn recur(value: &Recur) -> Value {
+    let mut rule = Map::new();
+    for part in value.to_string().split(';') {
+        let Some((name, values)) = part.split_once('=') else {
+            continue;
+        };
+        let name = name.to_lowercase();
+        let mut values: Vec<Value> = values
+            .split(',')
+            .map(|single| match name.as_str() {
+                "until" => Value::from(utc(&value.until.unwrap_or_default().with_timezone(&Utc))),
+                "freq" | "wkst" | "byday" => Value::from(single),
+                _ => single
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .unwrap_or_else(|_| Value::from(single)),
+            })
+            .collect();
+        // A part with several values is given as an array
+        let values = if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        };
+        rule.insert(name, values);
+    }
+    Value::Object(rule)
+}
+
+fn valarm(valarm: &VAlarm) -> Value {
+    let mut properties = Properties::new(&valarm.parameters);
+    properties.text(Property::Action, &String::from(valarm.action.clone()));
+    match &valarm.trigger {
+        Trigger::Relative(duration, _) => properties.push_with(
+            "TRIGGER",
+            Some(&valarm.trigger.parameters()),
+            "duration",
+            vec![Value::from(utils::format_duration(duration))],
+        ),
+        Trigger::Absolute(date_time) => properties.push_with(
+            "TRIGGER",
+            Some(&valarm.trigger.parameters()),
+            "date-time",
+            vec![Value::from(utc(&date_time.with_timezone(&Utc)))],
+        ),
+    }
+    if let Some(duration) = &valarm.duration {
+        properties.duration(Property::Duration, duration);
+    }
+    if let Some(repeat) = valarm.repeat {
+        properties.integer(Property::Repeat, repeat);
+    }
+    if let Some(description) = &valarm.description {
+        properties.text(Property::Description, description);
+    }
+    if let Some(summary) = &valarm.summary {
+        properties.text(Property::Summary, summary);
+    }
+    properties.attachments(&valarm.attach);
+    for attendee in &valarm.attendee {
+        properties.cal_address(Property::Attendee, attendee);
+    }
+    properties.extensions(&valarm.x_properties, &valarm.iana_properties);
+    component("valarm", properties, Vec::new())
+}
+
+fn vtodo(vtodo: &VTodo) -> Value {
+    let mut properties = Properties::new(&vtodo.parameters);
+    properties.date_time(Property::DTStamp, &vtodo.dtstamp);
+    properties.text(Property::UID, &vtodo.uid);
+    if let Some(class) = &vtodo.class {
+        properties.text(Property::Class, &String::from(class.clone()));
+    }
+    if let Some(completed) = &vtodo.completed {
+        properties.date_time(Property::Completed, completed);
+    }
+    if let Some(created) = &vtodo.created {
+        properties.date_time(Property::Created, created);
+    }
+    if let Some(description) = &vtodo.description {
+        properties.text(Property::Description, description);
+    }
+    if let Some(dtstart) = &vtodo.dtstart {
+        properties.date_time(Property::DTStart, dtstart);
+    }
+    if let Some(geo) = vtodo.geo {
+        properties.geo(geo);
+    }
+    if let Some(last_modified) = &vtodo.last_modified {
+        properties.date_time(Property::LastModified, last_modified);
+    }
+    if let Some(location) = &vtodo.location {
+        properties.text(Property::Location, location);
+    }
+    if let Some(percent) = vtodo.percent {
+        properties.integer(Property::PercentComplete, percent);
+    }
+    if let Some(priority) = vtodo.priority {
+        properties.integer(Property::Priority, priority);
+    }
+    if let Some(recurrence_id) = &vtodo.recurrence_id {
+        properties.date_time(Property::RecurrenceID, recurrence_id);
+    }
+    if let Some(sequence) = vtodo.sequence {
+        properties.integer(Property::Sequence, sequence);
+    }
+    if let Some(status) = &vtodo.status {
+        properties.text(Property::Status, &String::from(status.clone()));
+    }
+    if let Some(summary) = &vtodo.summary {
+        properties.text(Property::Summary, summary);
+    }
+    if let Some(due) = &vtodo.due {
+        properties.date_time(Property::Due, due);
+    }
+    if let Some(duration) = &vtodo.duration {
+        properties.duration(Property::Duration, duration);
+    }
+    if let Some(rrule) = &vtodo.rrule {
+        properties.recur(rrule);
+    }
+    if let Some(url) = &vtodo.url {
+        properties.uri(Property::URL, url);
+    }
+    if let Some(organizer) = &vtodo.organizer {
+        properties.cal_address(Property::Organizer, organizer);
+    }
+    properties.attachments(&vtodo.attach);
+    for attendee in &vtodo.attendee {
+        properties.cal_address(Property::Attendee, attendee);
+    }
+    if !vtodo.categories.is_empty() {
+        properties.push(
+            Property::Categories,
+            "text",
+            vtodo
+                .categories
+                .iter()
+                .map(|category| Value::from(category.as_str()))
+                .collect(),
+        );
+    }
+    for comment in &vtodo.comment {
+        properties.text(Property::Comment, comment);
+    }
+    for contact in &vtodo.contact {
+        properties.text(Property::Contact, contact);
+    }
+    for exdate in &vtodo.exdate {
+        properties.date_time(Property::ExDate, exdate);
+    }
+    properties.related_to(&vtodo.related_to);
+    for resources in &vtodo.resources {
+        properties.text(Property::Resources, resources);
+    }
+    for rdate in &vtodo.rdate {
+        properties.date_time(Property::RDate, rdate);
+    }
+    properties.extensions(&vtodo.x_properties, &vtodo.iana_properties);
+    component(
+        "vtodo",
+        properties,
+        vtodo.alarms.iter().map(valarm).collect(),
+    )
+}
+
+fn vevent(vevent: &VEvent) -> Value {
+    let mut properties = Properties::new(&vevent.parameters);
+    properties.date_time(Property::DTStamp, &vevent.dtstamp);
+    properties.text(Property::UID, &vevent.uid);
+    if let Some(dtstart) = &vevent.dtstart {
+        properties.date_time(Property::DTStart, dtstart);
+    }
+    if let Some(class) = &vevent.class {
+        properties.text(Property::Class, &String::from(class.clone()));
+    }
+    if let Some(created) = &vevent.created {
+        properties.date_time(Property::Created, created);
+    }
+    if let Some(description) = &vevent.description {
+        properties.text(Property::Description, description);
+    }
+    if let Some(geo) = vevent.geo {
+        properties.geo(geo);
+    }
+    if let Some(last_modified) = &vevent.last_modified {
+        properties.date_time(Property::LastModified, last_modified);
+    }
+    if let Some(location) = &vevent.location {
+        properties.text(Property::Location, location);
+    }
+    if let Some(organizer) = &vevent.organizer {
+        properties.cal_address(Property::Organizer, organizer);
+    }
+    if let Some(priority) = vevent.priority {
+        properties.integer(Property::Priority, priority);
+    }
+    if let Some(sequence) = vevent.sequence {
+        properties.integer(Property::Sequence, sequence);
+    }
+    if let Some(status) = &vevent.status {
+        properties.text(Property::Status, &String::from(status.clone()));
+    }
+    if let Some(summary) = &vevent.summary {
+        properties.text(Property::Summary, summary);
+    }
+    if let Some(transp) = &vevent.transp {
+        properties.text(Property::Transp, &String::from(transp.clone()));
+    }
+    if let Some(url) = &vevent.url {
+        properties.uri(Property::URL, url);
+    }
+    if let Some(recurrence_id) = &vevent.recurrence_id {
+        properties.date_time(Property::RecurrenceID, recurrence_id);
+    }
+    if let Some(rrule) = &vevent.rrule {
+        properties.recur(rrule);
+    }
+    if let Some(dtend) = &vevent.dtend {
+        properties.date_time(Property::DTEnd, dtend);
+    }
+    if let Some(duration) = &vevent.duration {
+        properties.duration(Property::Duration, duration);
+    }
+    properties.attachments(&vevent.attach);
+    for attendee in &vevent.attendee {
+        properties.cal_address(Property::Attendee, attendee);
+    }
+    if !vevent.categories.is_empty() {
+        properties.push(
+            Property::Categories,
+            "text",
+            vevent
+                .categories
+                .iter()
+                .map(|category| Value::from(category.as_str()))
+                .collect(),
+        );
+    }
+    for comment in &vevent.comment {
+        properties.text(Property::Comment, comment);
+    }
+    for contact in &vevent.contact {
+        properties.text(Property::Contact, contact);
+    }
+    for exdate in &vevent.exdate {
+        properties.date_time(Property::ExDate, exdate);
+    }
+    properties.related_to(&vevent.related_to);
+    for resources in &vevent.resources {
+        properties.text(Property::Resources, resources);
+    }
+    for rdate in &vevent.rdate {
+        properties.date_time(Property::RDate, rdate);
+    }
+    properties.extensions(&vevent.x_properties, &vevent.iana_properties);
+    component(
+        "vevent",
+        properties,
+        vevent.alarms.iter().map(valarm).collect(),
+    )
+}
+
+fn vjournal(vjournal: &VJournal) -> Value {
+    let mut properties = Properties::new(&vjournal.parameters);
+    properties.date_time(Property::DTStamp, &vjournal.dtstamp);
+    properties.text(Property::UID, &vjournal.uid);
+    if let Some(class) = &vjournal.class {
+        properties.text(Property::Class, &String::from(class.clone()));
+    }
+    if let Some(created) = &vjournal.created {
+        properties.date_time(Property::Created, created);
+    }
+    if let Some(dtstart) = &vjournal.dtstart {
+        properties.date_time(Property::DTStart, dtstart);
+    }
+    if let Some(last_modified) = &vjournal.last_modified {
+        properties.date_time(Property::LastModified, last_modified);
+    }
+    if let Some(organizer) = &vjournal.organizer {
+        properties.cal_address(Property::Organizer, organizer);
+    }
+    if let Some(recurrence_id) = &vjournal.recurrence_id {
+        properties.date_time(Property::RecurrenceID, recurrence_id);
+    }
+    if let Some(sequence) = vjournal.sequence {
+        properties.integer(Property::Sequence, sequence);
+    }
+    if let Some(status) = &vjournal.status {
+        properties.text(Property::Status, &String::from(status.clone()));
+    }
+    if let Some(summary) = &vjournal.summary {
+        properties.text(Property::Summary, summary);
+    }
+    if let Some(url) = &vjournal.url {
+        properties.uri(Property::URL, url);
+    }
+    if let Some(rrule) = &vjournal.rrule {
+        properties.recur(rrule);
+    }
+    properties.attachments(&vjournal.attach);
+    for attendee in &vjournal.attendee {
+        properties.cal_address(Property::Attendee, attendee);
+    }
+    if !vjournal.categories.is_empty() {
+        properties.push(
+            Property::Categories,
+            "text",
+            vjournal
+                .categories
+                .iter()
+                .map(|category| Value::from(category.as_str()))
+                .collect(),
+        );
+    }
+    for comment in &vjournal.comment {
+        properties.text(Property::Comment, comment);
+    }
+    for contact in &vjournal.contact {
+        properties.text(Property::Contact, contact);
+    }
+    for description in &vjournal.description {
+        properties.text(Property::Description, description);
+    }
+    for exdate in &vjournal.exdate {
+        properties.date_time(Property::ExDate, exdate);
+    }
+    properties.related_to(&vjournal.related_to);
+    for rdate in &vjournal.rdate {
+        properties.date_time(Property::RDate, rdate);
+    }
+    properties.extensions(&vjournal.x_properties, &vjournal.iana_properties);
+    component("vjournal", properties, Vec::new())
+}
+
+fn vfreebusy(vfreebusy: &VFreeBusy) -> Value {
+    let mut properties = Properties::new(&vfreebusy.parameters);
+    properties.date_time(Property::DTStamp, &vfreebusy.dtstamp);
+    properties.text(Property::UID, &vfreebusy.uid);
+    if let Some(contact) = &vfreebusy.contact {
+        properties.text(Property::Contact, contact);
+    }
+    if let Some(dtstart) = &vfreebusy.dtstart {
+        properties.date_time(Property::DTStart, dtstart);
+    }
+    if let Some(dtend) = &vfreebusy.dtend {
+        properties.date_time(Property::DTEnd, dtend);
+    }
+    if let Some(organizer) = &vfreebusy.organizer {
+        properties.cal_address(Property::Organizer, organizer);
+    }
+    if let Some(url) = &vfreebusy.url {
+        properties.uri(Property::URL, url);
+    }
+    for attendee in &vfreebusy.attendee {
+        properties.cal_address(Property::Attendee, attendee);
+    }
+    for comment in &vfreebusy.comment {
+        properties.text(Property::Comment, comment);
+    }
+    for freebusy in &vfreebusy.freebusy {
+        free_busy(&mut properties, freebusy);
+    }
+    properties.extensions(&vfreebusy.x_properties, &vfreebusy.iana_properties);
+    component("vfreebusy", properties, Vec::new())
+}
+
+fn free_busy(properties: &mut Properties, freebusy: &FreeBusy) {
+    properties.push_with(
+        "FREEBUSY",
+        Some(&freebusy.parameters()),
+        "period",
+        freebusy.periods.iter().map(period).collect(),
+    );
+}
+
+fn observance(name: &str, observance: &Observance) -> Value {
+    let mut properties = Properties::new(&observance.parameters);
+    properties.push(
+        Property::DTStart,
+        "date-time",
+        vec![Value::from(local(&observance.dtstart))],
+    );
+    properties.push(
+        Property::TZOffsetFrom,
+        "utc-offset",
+        vec![Value::from(utc_offset(&observance.tzoffsetfrom))],
+    );
+    properties.push(
+        Property::TZOffsetTo,
+        "utc-offset",
+        vec![Value::from(utc_offset(&observance.tzoffsetto))],
+    );
+    if let Some(rrule) = &observance.rrule {
+        properties.recur(rrule);
+    }
+    for comment in &observance.comment {
+        properties.text(Property::Comment, comment);
+    }
+    for rdate in &observance.rdate {
+        properties.push(
+            Property::RDate,
+            "date-time",
+            vec![Value::from(local(rdate))],
+        );
+    }
+    for (index, tzname) in observance.tzname.iter().enumerate() {
+        let parameters =
+            utils::find_nth_parameters(&observance.parameters, Property::TZName, index);
+        properties.push_with(
+            "TZNAME",
+            parameters,
+            "text",
+            vec![Value::from(tzname.clone())],
+        );
+    }
+    properties.extensions(&observance.x_properties, &observance.iana_properties);
+    component(name, properties, Vec::new())
+}
+
+fn vtimezone(vtimezone: &VTimezone) -> Value {
+    let mut properties = Properties::new(&vtimezone.parameters);
+    properties.text(Property::TZID, &vtimezone.tzid);
+    if let Some(last_modified) = &vtimezone.last_modified {
+        properties.date_time(Property::LastModified, last_modified);
+    }
+    if let Some(tzurl) = &vtimezone.tzurl {
+        properties.uri(Property::TZURL, tzurl);
+    }
+    properties.extensions(&vtimezone.x_properties, &vtimezone.iana_properties);
+    let observances = vtimezone
+        .standard
+        .iter()
+        .map(|standard| observance("standard", standard))
+        .chain(
+            vtimezone
+                .daylight
+                .iter()
+                .map(|daylight| observance("daylight", daylight)),
+        )
+        .collect();
+    component("vtimezone", properties, observances)
+}
+
+/// Returns the calendar in the jCal form, its time zones coming before the other components.
+pub(crate) fn vcalendar(vcalendar: &VCalendar) -> Value {
+    let mut properties = Properties::new(&[]);
+    properties.text(Property::ProdID, vcalendar.prodid());
+    properties.text(Property::Version, vcalendar.version());
+    if let Some(calscale) = vcalendar.calscale() {
+        properties.text(Property::CalScale, calscale);
+    }
+    if let Some(method) = vcalendar.method() {
+        properties.text(Property::Method, method);
+    }
+    properties.extensions(vcalendar.x_properties(), vcalendar.iana_properties());
+
+    let components = vcalendar
+        .timezones()
+        .iter()
+        .map(vtimezone)
+        .chain(
+            vcalendar
+                .components()
+                .into_iter()
+                .map(|component| match component {
+                    Component::Todo(todo) => vtodo(todo),
+                    Component::Event(event) => vevent(event),
+                    Component::Journal(journal) => vjournal(journal),
+                    Component::FreeBusy(freebusy) => vfreebusy(freebusy),
+                }),
+        )
+        .collect();
+    component("vcalendar", properties, components)
+}
+
+#[test]
+fn utc_offsets() {
+    assert_eq!(
+        utc_offset(&FixedOffset::west_opt(5 * 3600).unwrap()),
+        "-05:00"
+    );
+    assert_eq!(
+        utc_offset(&FixedOffset::east_opt(3600 + 30 * 60).unwrap()),
+        "+01:30"
+    );
+}
+
+#[test]
+fn recur_parts() {
+    let rule: Recur = "FREQ=WEEKLY;INTERVAL=2;UNTIL=19971224T000000Z;BYDAY=MO,-1FR"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        recur(&rule),
+        json!({
+            "freq": "WEEKLY",
+            "interval": 2,
+            "until": "1997-12-24T00:00:00Z",
+            "byday": ["MO", "-1FR"]
+        })
+    );
+}
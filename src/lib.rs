@@ -1,8 +1,14 @@
+pub mod component;
 pub mod ics_error;
+#[cfg(feature = "jcal")]
+mod jcal;
+pub mod parse_options;
 pub mod properties;
 mod utils;
 pub mod valarm;
 pub mod vcalendar;
 pub mod vevent;
+pub mod vfreebusy;
 pub mod vjournal;
+pub mod vtimezone;
 pub mod vtodo;
@@ -0,0 +1,63 @@
+use crate::properties::Property;
+
+/// Options changing the way ics files are read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Maximum number of octets on a single line, line break excluded.
+    pub max_line_len: usize,
+    /// Maximum number of lines in the file.
+    pub max_lines: usize,
+    /// Keeps the original line of each property so that it is written back exactly as it was read.
+    /// The kept line wins over the field: to write a changed value, remove the entries of its
+    /// property from the `raw_properties` of the component first.
+    pub preserve_raw: bool,
+    /// Refuses the values that do not follow the RFC 5545, even if their meaning is clear,
+    /// and the registered properties the crate does not know.
+    pub strict: bool,
+    /// Other names of properties, looked up when the name is not a known identifier.
+    pub aliases: Vec<(String, Property)>,
+    /// Reads the A, B and C priorities of some task applications as 1, 5 and 9.
+    pub letter_priorities: bool,
+    /// Swaps the GEO values whose latitude and longitude were given in the wrong order,
+    /// the component tells each swap in its warnings, which the calendar gathers.
+    pub swap_geo: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            max_line_len: usize::MAX,
+            max_lines: usize::MAX,
+            preserve_raw: false,
+            strict: true,
+            aliases: Vec::new(),
+            letter_priorities: false,
+            swap_geo: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Options accepting some common deviations from the RFC 5545 found in broken files.
+    /// The unknown properties are kept instead of refused, a missing VERSION or END:VCALENDAR
+    /// only gives a warning and some malformed values are read when their meaning is clear.
+    pub fn lenient() -> Self {
+        ParseOptions {
+            strict: false,
+            ..Default::default()
+        }
+    }
+
+    /// Options reading the legacy property names of vCalendar 1.0 as their modern equivalent,
+    /// along with the letter priorities of some task applications.
+    pub fn compatibility() -> Self {
+        ParseOptions {
+            aliases: vec![
+                ("DCREATED".to_string(), Property::Created),
+                ("DCOMPLETED".to_string(), Property::Completed),
+            ],
+            letter_priorities: true,
+            ..Default::default()
+        }
+    }
+}
@@ -1,18 +1,35 @@
-use std::{ops::Add, str::FromStr};
+use std::{num::IntErrorKind, ops::Add, str::FromStr};
 
-#[cfg(test)]
-use chrono::TimeZone;
 use chrono::{DateTime, Duration, FixedOffset};
+#[cfg(test)]
+use chrono::{TimeZone, Timelike};
+#[cfg(test)]
+use trigger::Related;
 
-use crate::ics_error::ICSError;
+use crate::{ics_error::ICSError, parse_options::ParseOptions, utils};
 
-use self::{action::Action, class::Class, status::Status};
+use self::{
+    action::Action, attachment::Attachment, cal_adress::CalAdress, class::Class,
+    free_busy::FreeBusy, iana_prop::IanaProp, recur::Recur, status::Status, transp::Transp,
+    trigger::Trigger, uri::Uri, utc_offset::parse_utc_offset, x_prop::XProp,
+};
 
 pub mod action;
+pub mod attachment;
 pub mod cal_adress;
 pub mod class;
+pub mod date_time_value;
+pub mod free_busy;
+pub mod iana_prop;
+pub mod period;
+pub mod recur;
+pub mod related_to;
 pub mod status;
+pub mod transp;
+pub mod trigger;
 pub mod uri;
+pub mod utc_offset;
+pub mod x_prop;
 
 const PROPERTY_IDENTIFIER: &[&str] = &[
     // Time properties
@@ -60,8 +77,24 @@ const PROPERTY_IDENTIFIER: &[&str] = &[
     "GEO",
     "CLASS",
     "TRIGGER",
+    // Recurrence rule
+    "RRULE",
+    // Event properties
+    "DTEND",
+    "TRANSP",
+    // Time zone properties
+    "TZID",
+    "TZNAME",
+    "TZOFFSETFROM",
+    "TZOFFSETTO",
+    "TZURL",
+    // Free busy time
+    "FREEBUSY",
 ];
 
+/// The properties that the crate can read, every known identifier is read.
+pub const SUPPORTED_PROPERTIES: &[&str] = PROPERTY_IDENTIFIER;
+
 // This was yoinked here : https://stackoverflow.com/questions/28028854/how-do-i-match-enum-values-with-an-integer
 macro_rules! back_to_enum {
     ($(#[$meta:meta])* $vis:vis enum $name:ident {
@@ -86,7 +119,7 @@ macro_rules! back_to_enum {
 }
 
 back_to_enum! {
-#[derive(Debug,PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Property {
     // Time properties
     DTStamp,
@@ -141,14 +174,50 @@ pub enum Property {
     Geo,
     Class,
     Trigger,
+
+    // Recurrence rule
+    RRule,
+
+    // Event properties
+    DTEnd,
+    Transp,
+
+    // Time zone properties
+    TZID,
+    TZName,
+    TZOffsetFrom,
+    TZOffsetTo,
+    TZURL,
+
+    // Free busy time
+    FreeBusy,
+
+    // Any property whose name starts with X-
+    XProp,
+    // A registered property that is not known, only read when the parse is not strict
+    IanaProp,
 }
 }
 
 impl Property {
     pub fn get_property_from_identifier(identifier: &str) -> Option<Property> {
+        Property::get_property_from_identifier_with_aliases(identifier, &[])
+    }
+
+    /// Finds the property of an identifier, the aliases are used if it is not a known identifier.
+    pub fn get_property_from_identifier_with_aliases(
+        identifier: &str,
+        aliases: &[(String, Property)],
+    ) -> Option<Property> {
         let index = PROPERTY_IDENTIFIER.iter().position(|&r| r == identifier);
 
-        index.map(|index| Property::try_from(index).unwrap())
+        match index {
+            Some(index) => Some(Property::try_from(index).unwrap()),
+            None => aliases
+                .iter()
+                .find(|(alias, _)| alias == identifier)
+                .map(|(_, property)| *property),
+        }
     }
 
     pub fn get_identier<'a>(self) -> &'a str {
@@ -156,23 +225,66 @@ impl Property {
     }
 
     pub fn parse_property(line: String) -> Result<(Property, ParserResult), ICSError> {
-        // This line has the parameters on one side and the values on the other.
-        let splitted_line = match line.split_once(':') {
-            Some(l) => l,
-            None => return Err(ICSError::UnableToParseProperty(line)),
-        };
-        let mut parameters = splitted_line.0.split(';');
+        let (property, _, result) = Property::parse_property_with_parameters(line)?;
+        Ok((property, result))
+    }
 
-        let property_name = parameters.next().unwrap();
-        // println!("{}",var);
-        let property = Property::get_property_from_identifier(property_name);
+    /// Parses a property line and also returns the parameters that were given with it.
+    pub fn parse_property_with_parameters(
+        line: String,
+    ) -> Result<(Property, Parameters, ParserResult), ICSError> {
+        Property::parse_property_with_options(line, &ParseOptions::default())
+    }
 
+    /// Parses a property line with the given options and returns the parameters that were given with it.
+    pub fn parse_property_with_options(
+        line: String,
+        options: &ParseOptions,
+    ) -> Result<(Property, Parameters, ParserResult), ICSError> {
+        let (property_name, mut parameters, value) = split_property(&line)?;
+        let splitted_line = (property_name, value);
+        let property =
+            Property::get_property_from_identifier_with_aliases(property_name, &options.aliases);
+
+        // A non-standard property is kept whatever its name
+        let property = match property {
+            None if XProp::is_x_prop(property_name) => Some(Property::XProp),
+            None if !options.strict && utils::is_iana_token(property_name) => {
+                Some(Property::IanaProp)
+            }
+            property => property,
+        };
         if property.is_none() {
             return Err(ICSError::UknownProperty(property_name.to_string()));
         }
 
         let property = property.unwrap();
 
+        // Only text values can be empty, the other types of value always have something to parse
+        let is_text = matches!(
+            property,
+            Property::UID
+                | Property::Description
+                | Property::Location
+                | Property::Summary
+                | Property::Comment
+                | Property::Contact
+                | Property::RelatedTo
+                | Property::Resources
+                | Property::Categories
+                | Property::ProdID
+                | Property::Version
+                | Property::CalScale
+                | Property::Method
+                | Property::TZID
+                | Property::TZName
+                | Property::XProp
+                | Property::IanaProp
+        );
+        if splitted_line.1.is_empty() && !is_text {
+            return Err(ICSError::UnableToParseProperty(property_name.to_string()));
+        }
+
         let result: ParserResult = match property {
             // Time identifier
             Property::DTStamp
@@ -183,45 +295,63 @@ impl Property {
             | Property::RecurrenceID
             | Property::ExDate
             | Property::RDate
-            | Property::Due => {
-                // This is needed as parse_from_str wants timezone information.
-                let mut temp_string = splitted_line.1.to_string();
+            | Property::Due
+            | Property::DTEnd => {
+                let mut is_date = false;
+                let mut is_local = false;
 
                 // Deal with all the parameters possible for time values
-                let mut parameter = parameters.next();
-                while parameter.is_some() {
-                    // Split the parameter string
-                    let (param_name, param_value) = match parameter.unwrap().split_once('=') {
-                        Some(val) => (val.0, val.1),
-                        None => {
-                            return Err(ICSError::PropertyConditionNotRespected(
-                                property_name.to_string(),
-                            ))
-                        }
-                    };
-
+                for (param_name, param_value) in parameters.iter() {
                     // Match the parameter with different possibilities
                     match param_name {
-                        "VALUE" => {
-                            match param_value {
-                                // If it is a date, lets add some 0 time to parse it properly
-                                "DATE" => temp_string.push_str("T000000Z"),
-                                "DATE-TIME" => {}
-                                _ => {
-                                    return Err(ICSError::PropertyConditionNotRespected(
-                                        property_name.to_string(),
-                                    ))
-                                }
+                        "VALUE" => match param_value {
+                            "DATE" => is_date = true,
+                            "DATE-TIME" => {}
+                            _ => {
+                                return Err(ICSError::PropertyConditionNotRespected(
+                                    property_name.to_string(),
+                                ))
                             }
-                        }
-                        _ => {
-                            return Err(ICSError::PropertyConditionNotRespected(
-                                property_name.to_string(),
-                            ))
-                        }
+                        },
+                        // A time with a TZID is a local time of that zone and cannot be UTC.
+                        // The local time is kept as is here, the calendar resolves the zone once read.
+                        "TZID" => is_local = true,
+                        // Other parameters do not change the value, they are kept for writing
+                        _ => {}
                     }
+                }
+
+                // COMPLETED, CREATED, DTSTAMP and LAST-MODIFIED must be date-times in UTC
+                if matches!(
+                    property,
+                    Property::Completed
+                        | Property::Created
+                        | Property::DTStamp
+                        | Property::LastModified
+                ) && options.strict
+                    && !splitted_line.1.ends_with('Z')
+                {
+                    return Err(ICSError::PropertyConditionNotRespected(
+                        property_name.to_string(),
+                    ));
+                }
 
-                    parameter = parameters.next();
+                // This is needed as parse_from_str wants timezone information.
+                let mut temp_string = splitted_line.1.to_string();
+                if is_date {
+                    // If it is a date, lets add some 0 time to parse it properly
+                    temp_string.push_str("T000000Z");
+                } else if is_local {
+                    if temp_string.ends_with('Z') {
+                        return Err(ICSError::PropertyConditionNotRespected(
+                            property_name.to_string(),
+                        ));
+                    }
+                    temp_string.push('Z');
+                } else if !temp_string.ends_with('Z') {
+                    // A floating time is the same local time in every zone, it is kept as is.
+                    parameters.set_floating(true);
+                    temp_string.push('Z');
                 }
 
                 temp_string.push_str("+0000");
@@ -231,6 +361,20 @@ impl Property {
                         Err(_) => {
                             match DateTime::parse_from_str(temp_string.as_str(), "%Y%m%dT%H%MZ%z") {
                                 Ok(value) => value,
+                                // Some broken files only give the hour, the minutes are then 0
+                                Err(_) if !options.strict => {
+                                    match DateTime::parse_from_str(
+                                        temp_string.replacen('Z', "00Z", 1).as_str(),
+                                        "%Y%m%dT%H%MZ%z",
+                                    ) {
+                                        Ok(value) => value,
+                                        Err(_) => {
+                                            return Err(ICSError::PropertyConditionNotRespected(
+                                                property_name.to_string(),
+                                            ))
+                                        }
+                                    }
+                                }
                                 Err(_) => {
                                     return Err(ICSError::PropertyConditionNotRespected(
                                         property_name.to_string(),
@@ -242,161 +386,145 @@ impl Property {
                 ParserResult::DateTime(date_time)
             }
             // Duration property
-            Property::Duration => {
-                // Because the duration cannot include months or years
-                // it's analog to a duration in time
-                let mut temp_string = String::from(splitted_line.1);
-                // Create are 0 duration before adding more to it.
-                let mut duration: Duration = Duration::days(0);
-
-                let mut factor: i64 = 1;
-
-                // Try to the negative
-                let split = temp_string.split_once('P');
-                // verify that the start of the string is correct
-                match split {
-                    Some(vec) => {
-                        match (!vec.0.is_empty(), vec.0.starts_with('-')) {
-                            // We are negative
-                            (true, true) => factor = -1,
-                            // We are starting with the wrong character
-                            (true, false) => {
-                                return Err(ICSError::PropertyConditionNotRespected(
-                                    property_name.to_string(),
-                                ))
-                            }
-                            (_, _) => {}
-                        }
-                        temp_string = vec.1.to_string();
-                    }
-                    None => {
-                        return Err(ICSError::PropertyConditionNotRespected(
-                            property_name.to_string(),
-                        ))
-                    }
-                }
-
-                // Try to find week
-                let split = temp_string.split_once('W');
-                // Add it if it's there
-                if let Some(vec) = split {
-                    duration = duration.add(Duration::weeks(
-                        factor
-                            * <i32 as Into<i64>>::into(vec.0.to_string().parse::<i32>().unwrap()),
-                    ));
-                    temp_string = vec.1.to_string();
-                }
-
-                // Try to find days
-                let split = temp_string.split_once('D');
-                // Add it if it's there
-                if let Some(vec) = split {
-                    duration = duration.add(Duration::days(
-                        factor
-                            * <i32 as Into<i64>>::into(vec.0.to_string().parse::<i32>().unwrap()),
-                    ));
-                    temp_string = vec.1.to_string();
+            Property::Duration => match parse_duration_with_options(splitted_line.1, options) {
+                Ok(duration) => ParserResult::Duration(duration),
+                Err(_) => {
+                    return Err(ICSError::PropertyConditionNotRespected(
+                        property_name.to_string(),
+                    ))
                 }
+            },
 
-                // Try to find A time
-                let split = temp_string.split_once('T');
-                // Add it if it's there
-                if let Some(vec) = split {
-                    temp_string = vec.1.to_string();
-
-                    // Try to find hours
-                    let split = temp_string.split_once('H');
-                    // Add it if it's there
-                    if let Some(vec) = split {
-                        duration = duration.add(Duration::hours(
-                            factor
-                                * <i32 as Into<i64>>::into(
-                                    vec.0.to_string().parse::<i32>().unwrap(),
-                                ),
-                        ));
-                        temp_string = vec.1.to_string();
-                    }
-
-                    // Try to find minutes
-                    let split = temp_string.split_once('M');
-                    // Add it if it's there
-                    if let Some(vec) = split {
-                        duration = duration.add(Duration::minutes(
-                            factor
-                                * <i32 as Into<i64>>::into(
-                                    vec.0.to_string().parse::<i32>().unwrap(),
-                                ),
-                        ));
-                        temp_string = vec.1.to_string();
-                    }
-
-                    // Try to find seconds
-                    let split = temp_string.split_once('S');
-                    // Add it if it's there
-                    if let Some(vec) = split {
-                        duration = duration.add(Duration::seconds(
-                            factor
-                                * <i32 as Into<i64>>::into(
-                                    vec.0.to_string().parse::<i32>().unwrap(),
-                                ),
-                        ));
-                        temp_string = vec.1.to_string();
-                    }
-                }
+            Property::RRule => ParserResult::Recur(Recur::from_str(splitted_line.1)?),
 
-                // Verify that the string is completely eaten
-                if !temp_string.is_empty() {
+            // A trigger is either a date-time or a duration relative to the component
+            Property::Trigger => match Trigger::new(splitted_line.1, &parameters) {
+                Ok(trigger) => ParserResult::Trigger(trigger),
+                Err(_) => {
                     return Err(ICSError::PropertyConditionNotRespected(
                         property_name.to_string(),
-                    ));
+                    ))
                 }
-
-                ParserResult::Duration(duration)
-            }
+            },
             // String identifier
             // We might want to add a specific validator for UID
             Property::UID
-            | Property::Trigger
-            | Property::Description
-            | Property::Location
-            | Property::Summary
-            | Property::Comment
             | Property::RelatedTo
             | Property::Resources
             | Property::ProdID
             | Property::Version
             | Property::CalScale
-            | Property::Method => ParserResult::String(String::from(splitted_line.1)),
+            | Property::Method
+            | Property::TZID => ParserResult::String(String::from(splitted_line.1)),
+
+            // Free text, where line breaks and separators are escaped
+            Property::Description
+            | Property::Location
+            | Property::Summary
+            | Property::Comment
+            | Property::Contact
+            | Property::TZName => {
+                let text = utils::unescape_text(splitted_line.1);
+                if options.strict {
+                    utils::validate_text(property, &text)?;
+                }
+                ParserResult::String(text)
+            }
 
+            // An escaped comma belongs to the category
             Property::Categories => {
-                let mut vec: Vec<String> = Vec::new();
-                let mut categories = splitted_line.1.split(',');
-                let mut category = categories.next();
-                while category.is_some() {
-                    vec.push(category.unwrap().to_string());
-                    category = categories.next();
+                let categories = utils::split_text_list(splitted_line.1);
+                if options.strict {
+                    for category in &categories {
+                        utils::validate_text(property, category)?;
+                    }
                 }
-                ParserResult::Strings(vec)
+                ParserResult::Strings(categories)
             }
 
-            Property::Organizer | Property::Attendee | Property::Contact => todo!(),
+            Property::Organizer | Property::Attendee => ParserResult::CalAdress(CalAdress::new(
+                splitted_line.1,
+                &parameters,
+                options.strict,
+            )?),
 
             Property::PercentComplete
             | Property::Repeat
             | Property::Priority
-            | Property::Sequence => match splitted_line.1.to_string().parse() {
-                Ok(integer) => ParserResult::Integer(integer),
-                Err(_) => return Err(ICSError::UnableToParseProperty(property_name.to_string())),
-            },
+            | Property::Sequence => {
+                // Parsed as a signed integer to be able to explain why a negative value is refused.
+                let integer: i64 = match splitted_line.1.parse() {
+                    Ok(integer) => integer,
+                    Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+                        return Err(ICSError::PropertyConditionNotRespected(format!(
+                            "{property_name} is too large"
+                        )))
+                    }
+                    // Letter scale of some task applications, A being the highest
+                    Err(_) if property == Property::Priority && options.letter_priorities => {
+                        match splitted_line.1 {
+                            "A" => 1,
+                            "B" => 5,
+                            "C" => 9,
+                            _ => {
+                                return Err(ICSError::UnableToParseProperty(
+                                    property_name.to_string(),
+                                ))
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        return Err(ICSError::UnableToParseProperty(property_name.to_string()))
+                    }
+                };
+
+                // A lenient parse brings a percentage back in its range
+                let integer = match property {
+                    Property::PercentComplete if !options.strict => integer.clamp(0, 100),
+                    _ => integer,
+                };
+
+                ParserResult::Integer(validate_integer(property, integer)?)
+            }
+
+            // The values of enumerated properties are case insensitive
+            Property::Status => {
+                ParserResult::Status(Status::from_str(&splitted_line.1.to_uppercase())?)
+            }
+
+            Property::Action => {
+                ParserResult::Action(Action::from_str(&splitted_line.1.to_uppercase())?)
+            }
+
+            Property::Attach => {
+                ParserResult::Attachment(Attachment::new(splitted_line.1, &parameters)?)
+            }
 
-            Property::Status => ParserResult::Status(Status::from_str(splitted_line.1)?),
+            Property::URL | Property::TZURL => ParserResult::Uri(Uri::from_str(splitted_line.1)?),
 
-            Property::Action => ParserResult::Action(Action::from_str(splitted_line.1)?),
+            Property::FreeBusy => match FreeBusy::new(splitted_line.1, &parameters) {
+                Ok(free_busy) => ParserResult::FreeBusy(free_busy),
+                Err(_) => {
+                    return Err(ICSError::PropertyConditionNotRespected(
+                        property_name.to_string(),
+                    ))
+                }
+            },
 
-            Property::URL | Property::Attach => todo!(),
+            Property::TZOffsetFrom | Property::TZOffsetTo => {
+                match parse_utc_offset(splitted_line.1) {
+                    Ok(offset) => ParserResult::UtcOffset(offset),
+                    Err(_) => {
+                        return Err(ICSError::PropertyConditionNotRespected(
+                            property_name.to_string(),
+                        ))
+                    }
+                }
+            }
 
             Property::Geo => {
-                // Get the two floats
+                // Get the two floats, scientific notations such as 3.7e1 are accepted
+                // as they are still valid floats and go through the same range check.
                 let (lat, long) = match splitted_line.1.split_once(';') {
                     Some(values) => values,
                     None => return Err(ICSError::UnableToParseProperty(property_name.to_string())),
@@ -414,7 +542,11 @@ impl Property {
                     }
                 };
 
-                if !(-90. ..=90.).contains(&float_lat) {
+                // A latitude that only fits as a longitude is swapped by the component
+                let swapped = options.swap_geo
+                    && (-180. ..=180.).contains(&float_lat)
+                    && (-90. ..=90.).contains(&float_long);
+                if !(-90. ..=90.).contains(&float_lat) && !swapped {
                     return Err(ICSError::PropertyConditionNotRespected(
                         property_name.to_string(),
                     ));
@@ -429,11 +561,284 @@ impl Property {
                 ParserResult::Geo(float_lat, float_long)
             }
 
-            Property::Class => ParserResult::Class(Class::from_str(splitted_line.1)?),
+            Property::Class => {
+                ParserResult::Class(Class::from_str(&splitted_line.1.to_uppercase())?)
+            }
+
+            Property::Transp => {
+                ParserResult::Transp(Transp::from_str(&splitted_line.1.to_uppercase())?)
+            }
+
+            // The value is not known, it is kept as text
+            Property::XProp => ParserResult::XProp(XProp::parse(&line)?),
+            Property::IanaProp => ParserResult::IanaProp(IanaProp::parse(&line)?),
         };
 
-        Ok((property, result))
+        Ok((property, parameters, result))
+    }
+}
+
+/// Splits a line on a separator, ignoring the separators inside a quoted parameter value.
+fn split_outside_quotes(line: &str, separator: char) -> Vec<&str> {
+    let mut parts: Vec<&str> = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (index, character) in line.char_indices() {
+        if character == '"' {
+            quoted = !quoted;
+        } else if character == separator && !quoted {
+            parts.push(&line[start..index]);
+            start = index + 1;
+        }
+    }
+    parts.push(&line[start..]);
+    parts
+}
+
+/// Splits a property line into its name, its parameters and its value.
+pub(crate) fn split_property(line: &str) -> Result<(&str, Parameters, &str), ICSError> {
+    // This line has the parameters on one side and the values on the other.
+    // A quoted parameter value can contain a colon or a semicolon.
+    let (name, value) = match split_outside_quotes(line, ':').split_first() {
+        Some((name, [_, ..])) => (*name, &line[name.len() + 1..]),
+        _ => return Err(ICSError::UnableToParseProperty(line.to_string())),
+    };
+    let mut raw_parameters = split_outside_quotes(name, ';').into_iter();
+
+    let property_name = raw_parameters.next().unwrap();
+    let mut parameters = Parameters::new();
+    for parameter in raw_parameters {
+        match parameter.split_once('=') {
+            Some((param_name, param_value)) => {
+                parameters.insert(param_name.to_string(), param_value.to_string())
+            }
+            None => {
+                return Err(ICSError::PropertyConditionNotRespected(
+                    property_name.to_string(),
+                ))
+            }
+        }
+    }
+    Ok((property_name, parameters, value))
+}
+
+/// The parameters given to a property, kept in the order they were read.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Parameters {
+    parameters: Vec<(String, String)>,
+    // The time was given without Z nor TZID, this is not written as a parameter
+    floating: bool,
+}
+
+impl Parameters {
+    pub fn new() -> Parameters {
+        Parameters {
+            parameters: Vec::new(),
+            floating: false,
+        }
+    }
+
+    /// Returns true if the time of the property is a floating time, without Z nor TZID.
+    pub fn is_floating(&self) -> bool {
+        self.floating
+    }
+
+    /// Marks the time of the property as a floating time, it is then written without Z.
+    pub fn set_floating(&mut self, floating: bool) {
+        self.floating = floating;
+    }
+
+    /// Returns the value of the first parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(param_name, _)| param_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn insert(&mut self, name: String, value: String) {
+        self.parameters.push((name, value));
+    }
+
+    /// Removes every parameter with the given name.
+    pub fn remove(&mut self, name: &str) {
+        self.parameters.retain(|(param_name, _)| param_name != name);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Returns the parameters in the alphabetical order of their names, as they are written.
+    /// The values of a repeated parameter keep the order they were given in.
+    pub fn canonical(&self) -> Vec<(&str, &str)> {
+        let mut parameters: Vec<(&str, &str)> = self.iter().collect();
+        parameters.sort_by_key(|(name, _)| *name);
+        parameters
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Verifies an integer is in the range allowed for the property, PRIORITY is 0..=9,
+/// PERCENT-COMPLETE 0..=100 and the other counts are not negative.
+pub fn validate_integer(property: Property, value: i64) -> Result<usize, ICSError> {
+    let property_name = property.get_identier();
+    let range = match property {
+        Property::Priority => 0..=9,
+        Property::PercentComplete => 0..=100,
+        _ => 0..=i64::MAX,
+    };
+    if !range.contains(&value) {
+        let message = match property {
+            Property::Priority | Property::PercentComplete => format!(
+                "{property_name} must be {}..={}",
+                range.start(),
+                range.end()
+            ),
+            _ => format!("{property_name} must not be negative"),
+        };
+        return Err(ICSError::PropertyConditionNotRespected(message));
+    }
+
+    // Only fails on the platforms where usize is smaller than 64 bits
+    usize::try_from(value).map_err(|_| {
+        ICSError::PropertyConditionNotRespected(format!("{property_name} is too large"))
+    })
+}
+
+/// Writes a duration in the ISO 8601 format used by DURATION, such as P15DT5H20S or -PT15M.
+pub fn duration_to_iso(value: Duration) -> String {
+    utils::format_duration(&value)
+}
+
+/// Reads a duration written in the ISO 8601 format used by DURATION, the reverse of duration_to_iso.
+pub fn duration_from_iso(value: &str) -> Result<Duration, ICSError> {
+    parse_duration(value)
+}
+
+/// Parses a duration value, as used by DURATION, a relative TRIGGER or the end of a period.
+/// The designators are upper case only and every number must fit in a u32.
+pub fn parse_duration(value: &str) -> Result<Duration, ICSError> {
+    parse_duration_with_options(value, &ParseOptions::default())
+}
+
+/// Parses a duration value with the given options, a lenient parse accepts weeks mixed with
+/// other designators, such as P1W2D, and adds them up.
+pub fn parse_duration_with_options(
+    value: &str,
+    options: &ParseOptions,
+) -> Result<Duration, ICSError> {
+    let error = || ICSError::PropertyConditionNotRespected("DURATION".to_string());
+    // Only digits are allowed before a designator, a sign or a lower case letter is refused
+    let parse_number = |number: &str| -> Result<i64, ICSError> {
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+            return Err(error());
+        }
+        match number.parse::<u32>() {
+            Ok(number) => Ok(number.into()),
+            Err(_) => Err(error()),
+        }
+    };
+
+    // Because the duration cannot include months or years
+    // it's analog to a duration in time
+    let mut temp_string = String::from(value);
+
+    // Create are 0 duration before adding more to it.
+    let mut duration: Duration = Duration::days(0);
+
+    let mut factor: i64 = 1;
+
+    // Try to the negative
+    let split = temp_string.split_once('P');
+    // verify that the start of the string is correct
+    match split {
+        Some(vec) => {
+            match (!vec.0.is_empty(), vec.0 == "-", vec.0 == "+") {
+                // We are negative
+                (true, true, _) => factor = -1,
+                // Explicitly positive
+                (true, _, true) => {}
+                // We are starting with the wrong character
+                (true, false, false) => return Err(error()),
+                (_, _, _) => {}
+            }
+            temp_string = vec.1.to_string();
+        }
+        None => return Err(error()),
+    }
+
+    // At least one of the weeks, days or time must follow the designator
+    if temp_string.is_empty() {
+        return Err(error());
+    }
+
+    // Try to find week
+    let split = temp_string.split_once('W');
+    // Add it if it's there
+    if let Some(vec) = split {
+        duration = duration.add(Duration::weeks(factor * parse_number(vec.0)?));
+        temp_string = vec.1.to_string();
+        // A duration in weeks cannot be mixed with the other designators
+        if options.strict && !temp_string.is_empty() {
+            return Err(error());
+        }
+    }
+
+    // Try to find days
+    let split = temp_string.split_once('D');
+    // Add it if it's there
+    if let Some(vec) = split {
+        duration = duration.add(Duration::days(factor * parse_number(vec.0)?));
+        temp_string = vec.1.to_string();
+    }
+
+    // Try to find A time
+    let split = temp_string.split_once('T');
+    // Add it if it's there
+    if let Some(vec) = split {
+        // The time designator is the first thing left and is followed by a time
+        if !vec.0.is_empty() || vec.1.is_empty() {
+            return Err(error());
+        }
+        temp_string = vec.1.to_string();
+
+        // Try to find hours
+        let split = temp_string.split_once('H');
+        // Add it if it's there
+        if let Some(vec) = split {
+            duration = duration.add(Duration::hours(factor * parse_number(vec.0)?));
+            temp_string = vec.1.to_string();
+        }
+
+        // Try to find minutes
+        let split = temp_string.split_once('M');
+        // Add it if it's there
+        if let Some(vec) = split {
+            duration = duration.add(Duration::minutes(factor * parse_number(vec.0)?));
+            temp_string = vec.1.to_string();
+        }
+
+        // Try to find seconds
+        let split = temp_string.split_once('S');
+        // Add it if it's there
+        if let Some(vec) = split {
+            duration = duration.add(Duration::seconds(factor * parse_number(vec.0)?));
+            temp_string = vec.1.to_string();
+        }
     }
+
+    // Verify that the string is completely eaten
+    if !temp_string.is_empty() {
+        return Err(error());
+    }
+
+    Ok(duration)
 }
 
 #[derive(Debug, PartialEq)]
@@ -447,6 +852,16 @@ pub enum ParserResult {
     Action(Action),
     Class(Class),
     Geo(f32, f32),
+    Uri(Uri),
+    Attachment(Attachment),
+    CalAdress(CalAdress),
+    Trigger(Trigger),
+    Recur(Recur),
+    Transp(Transp),
+    UtcOffset(FixedOffset),
+    FreeBusy(FreeBusy),
+    XProp(XProp),
+    IanaProp(IanaProp),
 }
 
 impl From<ParserResult> for DateTime<FixedOffset> {
@@ -521,49 +936,166 @@ impl From<ParserResult> for Vec<String> {
     }
 }
 
-impl From<ParserResult> for Action {
+impl From<ParserResult> for Uri {
     fn from(result: ParserResult) -> Self {
         match result {
-            ParserResult::Action(val) => val,
+            ParserResult::Uri(val) => val,
             _ => panic!("Not casting the right result"),
         }
     }
 }
 
-#[test]
-fn all_properties_properly_recognised() {
-    // Date/Datetime
-    let expected_date = FixedOffset::east_opt(0)
-        .unwrap()
-        .with_ymd_and_hms(2007, 3, 13, 12, 34, 32)
-        .unwrap();
-
-    let (property, value) =
-        Property::parse_property("DTSTAMP:20070313T123432Z".to_string()).unwrap();
-    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
-    assert_eq!(property, Property::DTStamp);
+impl From<ParserResult> for Attachment {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::Attachment(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
 
-    let (property, value) =
-        Property::parse_property("COMPLETED:20070313T123432Z".to_string()).unwrap();
-    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
-    assert_eq!(property, Property::Completed);
+impl From<ParserResult> for CalAdress {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::CalAdress(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
 
-    let (property, value) =
-        Property::parse_property("CREATED:20070313T123432Z".to_string()).unwrap();
-    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
-    assert_eq!(property, Property::Created);
+impl From<ParserResult> for Trigger {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::Trigger(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
 
-    let (property, value) =
-        Property::parse_property("DTSTART:20070313T123432Z".to_string()).unwrap();
-    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
-    assert_eq!(property, Property::DTStart);
+impl From<ParserResult> for Recur {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::Recur(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
 
-    let (property, value) =
-        Property::parse_property("LAST-MODIFIED:20070313T123432Z".to_string()).unwrap();
-    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
-    assert_eq!(property, Property::LastModified);
+impl From<ParserResult> for Transp {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::Transp(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
 
-    let (property, value) =
+impl From<ParserResult> for FixedOffset {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::UtcOffset(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
+
+impl From<ParserResult> for FreeBusy {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::FreeBusy(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
+
+impl From<ParserResult> for XProp {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::XProp(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
+
+impl From<ParserResult> for IanaProp {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::IanaProp(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
+
+impl From<ParserResult> for Action {
+    fn from(result: ParserResult) -> Self {
+        match result {
+            ParserResult::Action(val) => val,
+            _ => panic!("Not casting the right result"),
+        }
+    }
+}
+
+#[test]
+fn all_properties_properly_recognised() {
+    // Date/Datetime
+    let expected_date = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 3, 13, 12, 34, 32)
+        .unwrap();
+
+    let (property, value) =
+        Property::parse_property("DTSTAMP:20070313T123432Z".to_string()).unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+    assert_eq!(property, Property::DTStamp);
+
+    let (property, value) = Property::parse_property("DTEND:20070313T123432Z".to_string()).unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+    assert_eq!(property, Property::DTEnd);
+
+    // Time zone properties
+    let (property, value) = Property::parse_property("TZOFFSETTO:-0500".to_string()).unwrap();
+    assert_eq!(
+        FixedOffset::from(value),
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    );
+    assert_eq!(property, Property::TZOffsetTo);
+
+    let (property, value) = Property::parse_property("TZOFFSETFROM:+0100".to_string()).unwrap();
+    assert_eq!(
+        FixedOffset::from(value),
+        FixedOffset::east_opt(3600).unwrap()
+    );
+    assert_eq!(property, Property::TZOffsetFrom);
+    assert_eq!(
+        Property::parse_property("TZOFFSETFROM:0100".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("TZOFFSETFROM".to_string())
+    );
+
+    let (property, value) = Property::parse_property("TZID:America/New_York".to_string()).unwrap();
+    assert_eq!(String::from(value), "America/New_York");
+    assert_eq!(property, Property::TZID);
+
+    let (property, value) =
+        Property::parse_property("COMPLETED:20070313T123432Z".to_string()).unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+    assert_eq!(property, Property::Completed);
+
+    let (property, value) =
+        Property::parse_property("CREATED:20070313T123432Z".to_string()).unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+    assert_eq!(property, Property::Created);
+
+    let (property, value) =
+        Property::parse_property("DTSTART:20070313T123432Z".to_string()).unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+    assert_eq!(property, Property::DTStart);
+
+    let (property, value) =
+        Property::parse_property("LAST-MODIFIED:20070313T123432Z".to_string()).unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+    assert_eq!(property, Property::LastModified);
+
+    let (property, value) =
         Property::parse_property("RECURRENCE-ID:20070313T123432Z".to_string()).unwrap();
     assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
     assert_eq!(property, Property::RecurrenceID);
@@ -612,6 +1144,17 @@ fn all_properties_properly_recognised() {
     assert_eq!(String::from(value), "This is a description".to_string());
     assert_eq!(property, Property::Comment);
 
+    // The commas of a CONTACT are part of the text
+    let (property, value) = Property::parse_property(
+        "CONTACT;ALTREP=\"http://example.com/pdi/jdoe.vcf\":Jim Dolittle\\, ABC Industries\\, +1-919-555-1234".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        String::from(value),
+        "Jim Dolittle, ABC Industries, +1-919-555-1234".to_string()
+    );
+    assert_eq!(property, Property::Contact);
+
     let (property, value) =
         Property::parse_property("RELATED-TO:This is a description".to_string()).unwrap();
     assert_eq!(String::from(value), "This is a description".to_string());
@@ -682,6 +1225,51 @@ fn all_properties_properly_recognised() {
     assert_eq!(property, Property::Geo);
 }
 
+#[test]
+fn identifier_aliases() {
+    assert_eq!(Property::get_property_from_identifier("DCREATED"), None);
+    assert_eq!(
+        Property::get_property_from_identifier_with_aliases(
+            "DCREATED",
+            &ParseOptions::compatibility().aliases
+        ),
+        Some(Property::Created)
+    );
+    // Known identifiers are not affected by the aliases
+    assert_eq!(
+        Property::get_property_from_identifier_with_aliases(
+            "CREATED",
+            &[("CREATED".to_string(), Property::DTStamp)]
+        ),
+        Some(Property::Created)
+    );
+
+    assert_eq!(
+        Property::parse_property("DCREATED:20070313T123432Z".to_string()).unwrap_err(),
+        ICSError::UknownProperty("DCREATED".to_string())
+    );
+    let (property, _, _) = Property::parse_property_with_options(
+        "DCREATED:20070313T123432Z".to_string(),
+        &ParseOptions::compatibility(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::Created);
+}
+
+#[test]
+fn supported_properties() {
+    assert!(!SUPPORTED_PROPERTIES.is_empty());
+    assert!(SUPPORTED_PROPERTIES.contains(&"DTSTAMP"));
+    assert!(SUPPORTED_PROPERTIES.contains(&"CONTACT"));
+    // Every supported property must be recognised
+    for identifier in SUPPORTED_PROPERTIES {
+        assert!(
+            Property::get_property_from_identifier(identifier).is_some(),
+            "{identifier}"
+        );
+    }
+}
+
 #[test]
 fn string_parsing_cases() {
     // String with another ':' in the parameter
@@ -693,6 +1281,24 @@ fn string_parsing_cases() {
     );
     assert_eq!(property, Property::UID);
 
+    // Value starting with the name of another property, only the first ':' is a separator
+    let (property, parameters, value) =
+        Property::parse_property_with_parameters("DESCRIPTION:STATUS:done".to_string()).unwrap();
+    assert_eq!(property, Property::Description);
+    assert!(parameters.is_empty());
+    assert_eq!(String::from(value), "STATUS:done".to_string());
+
+    let (property, parameters, value) =
+        Property::parse_property_with_parameters("SUMMARY;LANGUAGE=en:SUMMARY:twice".to_string())
+            .unwrap();
+    assert_eq!(property, Property::Summary);
+    assert_eq!(parameters.get("LANGUAGE"), Some("en"));
+    assert_eq!(String::from(value), "SUMMARY:twice".to_string());
+
+    // Spaces around the value are part of it
+    let (_, value) = Property::parse_property("SUMMARY: Leading space kept ".to_string()).unwrap();
+    assert_eq!(String::from(value), " Leading space kept ".to_string());
+
     // Unknown property
     let result = Property::parse_property("SDQ:content".to_string());
     assert_eq!(result, Err(ICSError::UknownProperty("SDQ".to_string())));
@@ -704,9 +1310,46 @@ fn wrong_calscale() {
     //let (property, value) = Property::parse_property("CALSCALE:Wrong".to_string()).unwrap();
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn cal_address_parsing_cases() {
+    let (property, _, value) =
+        Property::parse_property_with_parameters("ATTENDEE;RSVP=TRUE:mailto:a@b.com".to_string())
+            .unwrap();
+    assert_eq!(property, Property::Attendee);
+    let attendee = CalAdress::from(value);
+    assert_eq!(attendee.address.to_string(), "mailto:a@b.com");
+    assert_eq!(attendee.rsvp, Some(true));
+
+    let (_, _, value) =
+        Property::parse_property_with_parameters("ATTENDEE:mailto:a@b.com".to_string()).unwrap();
+    assert_eq!(CalAdress::from(value).rsvp, None);
+
+    // Only TRUE and FALSE are allowed, unless lenient
+    assert_eq!(
+        Property::parse_property("ATTENDEE;RSVP=MAYBE:mailto:a@b.com".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("RSVP".to_string())
+    );
+    let (_, _, value) = Property::parse_property_with_options(
+        "ATTENDEE;RSVP=MAYBE:mailto:a@b.com".to_string(),
+        &ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(CalAdress::from(value).rsvp, None);
+
+    let (property, value) =
+        Property::parse_property("ORGANIZER:MAILTO:jane_doe@host.com".to_string()).unwrap();
+    assert_eq!(property, Property::Organizer);
+    let organizer = CalAdress::from(value);
+    assert_eq!(organizer.email(), Some("jane_doe@host.com"));
+    assert_eq!(organizer.common_name, None);
+
+    let (_, value) =
+        Property::parse_property("ORGANIZER;CN=John Smith:MAILTO:jsmith@host.com".to_string())
+            .unwrap();
+    let organizer = CalAdress::from(value);
+    assert_eq!(organizer.address.to_string(), "MAILTO:jsmith@host.com");
+    assert_eq!(organizer.common_name.as_deref(), Some("John Smith"));
+
     // let (property, value) =
     //     Property::parse_property("ORGANIZER:MAILTO:jane_doe@host.com".to_string()).unwrap();
     // let (property, value) =
@@ -749,6 +1392,28 @@ fn geo_parsing_cases() {
         Property::parse_property("GEO:82.386013;-192.082932".to_string()).unwrap_err(),
         ICSError::PropertyConditionNotRespected("GEO".to_string())
     );
+
+    // Scientific notation
+    let (_, value) = Property::parse_property("GEO:3.7e1;-1.22e2".to_string()).unwrap();
+    assert_eq!(<(f32, f32)>::from(value), (37., -122.));
+    assert_eq!(
+        Property::parse_property("GEO:9.1e1;-1.22e2".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("GEO".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("GEO:3.7e1;-1.22e3".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("GEO".to_string())
+    );
+
+    // Values that are floats but not coordinates
+    assert_eq!(
+        Property::parse_property("GEO:NaN;-1.22e2".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("GEO".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("GEO:3.7e1;inf".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("GEO".to_string())
+    );
 }
 
 #[test]
@@ -785,6 +1450,199 @@ fn duration_parsing_cases() {
         Property::parse_property("DURATION:-PJ".to_string()).unwrap_err(),
         ICSError::PropertyConditionNotRespected("DURATION".to_string())
     );
+
+    // Lower case designators
+    assert_eq!(
+        Property::parse_property("DURATION:p1w".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("DURATION:P1w".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("DURATION:PT1h30m".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+}
+
+#[test]
+fn duration_iso_round_trip() {
+    for (value, written) in [
+        ("P15DT5H0M20S", "P15DT5H20S"),
+        ("P7W", "P7W"),
+        ("-PT15M", "-PT15M"),
+    ] {
+        let duration = duration_from_iso(value).unwrap();
+        assert_eq!(duration_to_iso(duration), written);
+        assert_eq!(
+            duration_from_iso(&duration_to_iso(duration)).unwrap(),
+            duration
+        );
+    }
+
+    assert_eq!(duration_to_iso(Duration::zero()), "PT0S");
+    assert!(duration_from_iso("15 minutes").is_err());
+}
+
+#[test]
+fn parse_duration_cases() {
+    assert_eq!(
+        parse_duration("P15DT5H0M20S").unwrap(),
+        Duration::seconds(15 * 24 * 60 * 60 + 5 * 60 * 60 + 20)
+    );
+    assert_eq!(parse_duration("+PT15M").unwrap(), Duration::minutes(15));
+    assert_eq!(parse_duration("-P2D").unwrap(), Duration::days(-2));
+
+    // Badly formed numbers are errors instead of panics
+    for value in [
+        "PTH",
+        "P-1D",
+        "PT+1H",
+        "P99999999999W",
+        "PT1.5H",
+        "P1DT",
+        "P1T1H",
+    ] {
+        assert_eq!(
+            parse_duration(value).unwrap_err(),
+            ICSError::PropertyConditionNotRespected("DURATION".to_string()),
+            "{value}"
+        );
+    }
+
+    // A duration without any weeks, days or time is refused, even by a lenient parse
+    for value in ["P", "-P", "+P"] {
+        assert_eq!(
+            parse_duration_with_options(value, &ParseOptions::lenient()).unwrap_err(),
+            ICSError::PropertyConditionNotRespected("DURATION".to_string()),
+            "{value}"
+        );
+    }
+
+    // Weeks mixed with other designators are only accepted by a lenient parse
+    assert_eq!(
+        Property::parse_property("DURATION:P1W2D".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    let (property, _, value) = Property::parse_property_with_options(
+        "DURATION:P1W2D".to_string(),
+        &ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::Duration);
+    assert_eq!(Duration::from(value), Duration::days(9));
+    assert_eq!(
+        parse_duration_with_options("-P1WT1H", &ParseOptions::lenient()).unwrap(),
+        -Duration::hours(7 * 24 + 1)
+    );
+
+    // Relative triggers follow the same rules
+    let (property, value) = Property::parse_property("TRIGGER:-PT15M".to_string()).unwrap();
+    assert_eq!(property, Property::Trigger);
+    assert_eq!(
+        Trigger::from(value),
+        Trigger::Relative(Duration::minutes(-15), Related::Start)
+    );
+    assert_eq!(
+        Property::parse_property("TRIGGER:-pt15m".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("TRIGGER".to_string())
+    );
+}
+
+#[test]
+fn enumerated_values_case_insensitive() {
+    let (property, value) = Property::parse_property("STATUS:completed".to_string()).unwrap();
+    assert_eq!(Status::from(value), Status::Completed);
+    assert_eq!(property, Property::Status);
+
+    let (_, value) = Property::parse_property("STATUS:Needs-Action".to_string()).unwrap();
+    assert_eq!(Status::from(value), Status::NeedsAction);
+
+    let (_, value) = Property::parse_property("ACTION:display".to_string()).unwrap();
+    assert_eq!(Action::from(value), Action::Display);
+
+    let (_, value) = Property::parse_property("CLASS:Private".to_string()).unwrap();
+    assert_eq!(Class::from(value), Class::PRIVATE);
+
+    let (_, value) = Property::parse_property("CLASS:X-PERSONAL".to_string()).unwrap();
+    assert_eq!(Class::from(value), Class::XNAME("X-PERSONAL".to_string()));
+
+    let (property, value) = Property::parse_property("TRANSP:transparent".to_string()).unwrap();
+    assert_eq!(Transp::from(value), Transp::Transparent);
+    assert_eq!(property, Property::Transp);
+}
+
+#[test]
+fn integer_parsing_cases() {
+    // Negative values
+    assert_eq!(
+        Property::parse_property("PRIORITY:-3".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PRIORITY must be 0..=9".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("PERCENT-COMPLETE:-3".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PERCENT-COMPLETE must be 0..=100".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("SEQUENCE:-1".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("SEQUENCE must not be negative".to_string())
+    );
+
+    // Out of range
+    assert_eq!(
+        Property::parse_property("PRIORITY:11".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PRIORITY must be 0..=9".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("PERCENT-COMPLETE:101".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PERCENT-COMPLETE must be 0..=100".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("PRIORITY:15".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PRIORITY must be 0..=9".to_string())
+    );
+    assert_eq!(
+        Property::parse_property("PERCENT-COMPLETE:250".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PERCENT-COMPLETE must be 0..=100".to_string())
+    );
+
+    // The bounds are included
+    for (line, expected) in [
+        ("PRIORITY:0", 0),
+        ("PRIORITY:9", 9),
+        ("PERCENT-COMPLETE:0", 0),
+        ("PERCENT-COMPLETE:100", 100),
+    ] {
+        let (_, value) = Property::parse_property(line.to_string()).unwrap();
+        assert_eq!(usize::from(value), expected, "{line}");
+    }
+
+    // Not an integer
+    assert_eq!(
+        Property::parse_property("PRIORITY:high".to_string()).unwrap_err(),
+        ICSError::UnableToParseProperty("PRIORITY".to_string())
+    );
+}
+
+#[test]
+fn integer_validation() {
+    assert_eq!(validate_integer(Property::Priority, 9), Ok(9));
+    assert_eq!(validate_integer(Property::PercentComplete, 100), Ok(100));
+    assert_eq!(validate_integer(Property::Sequence, 4096), Ok(4096));
+
+    assert_eq!(
+        validate_integer(Property::Priority, 10).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PRIORITY must be 0..=9".to_string())
+    );
+    assert_eq!(
+        validate_integer(Property::PercentComplete, 101).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PERCENT-COMPLETE must be 0..=100".to_string())
+    );
+    assert_eq!(
+        validate_integer(Property::Repeat, -1).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("REPEAT must not be negative".to_string())
+    );
 }
 
 #[test]
@@ -842,20 +1700,376 @@ fn date_time_parsing_cases() {
     assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
 }
 
-#[ignore = "Not implemented yet"]
+#[test]
+fn hour_only_date_time_lenient() {
+    let line = "DTSTART:20070313T12Z".to_string();
+
+    // Refused by default
+    assert_eq!(
+        Property::parse_property(line.clone()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DTSTART".to_string())
+    );
+
+    let expected_date = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 3, 13, 12, 0, 0)
+        .unwrap();
+    let (property, _, value) =
+        Property::parse_property_with_options(line, &ParseOptions::lenient()).unwrap();
+    assert_eq!(property, Property::DTStart);
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+
+    // Minute precision is still read the same way
+    let (_, _, value) = Property::parse_property_with_options(
+        "DTSTART:20070313T1230Z".to_string(),
+        &ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(DateTime::<FixedOffset>::from(value).minute(), 30);
+}
+
+#[test]
+fn completed_must_be_utc() {
+    let (property, _) = Property::parse_property("COMPLETED:20070707T100000Z".to_string()).unwrap();
+    assert_eq!(property, Property::Completed);
+
+    for line in [
+        "COMPLETED;TZID=America/New_York:20070707T100000",
+        "COMPLETED;VALUE=DATE:20070707",
+        "COMPLETED:20070707T100000",
+    ] {
+        assert_eq!(
+            Property::parse_property(line.to_string()).unwrap_err(),
+            ICSError::PropertyConditionNotRespected("COMPLETED".to_string())
+        );
+    }
+
+    // A local time is still read when lenient
+    assert!(Property::parse_property_with_options(
+        "COMPLETED;TZID=America/New_York:20070707T100000".to_string(),
+        &ParseOptions::lenient()
+    )
+    .is_ok());
+}
+
+#[test]
+fn unknown_status() {
+    assert_eq!(
+        Property::parse_property("STATUS:BOGUS".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("STATUS: BOGUS".to_string())
+    );
+}
+
+#[test]
+fn timestamps_must_be_utc() {
+    for name in ["DTSTAMP", "CREATED", "LAST-MODIFIED"] {
+        let (property, value) =
+            Property::parse_property(format!("{name}:20070313T123432Z")).unwrap();
+        assert_eq!(property.get_identier(), name);
+        assert_eq!(
+            DateTime::<FixedOffset>::from(value),
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(2007, 3, 13, 12, 34, 32)
+                .unwrap()
+        );
+
+        assert_eq!(
+            Property::parse_property(format!("{name};TZID=America/New_York:20070313T123432"))
+                .unwrap_err(),
+            ICSError::PropertyConditionNotRespected(name.to_string())
+        );
+        assert!(Property::parse_property_with_options(
+            format!("{name};TZID=America/New_York:20070313T123432"),
+            &ParseOptions::lenient()
+        )
+        .is_ok());
+    }
+}
+
+#[test]
+fn tzid_parsing_cases() {
+    // The local time is kept as is until the zone is resolved
+    let expected_date = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 3, 13, 12, 34, 32)
+        .unwrap();
+    let (property, parameters, value) = Property::parse_property_with_parameters(
+        "DTSTART;TZID=America/New_York:20070313T123432".to_string(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::DTStart);
+    assert_eq!(parameters.get("TZID"), Some("America/New_York"));
+    assert_eq!(DateTime::<FixedOffset>::from(value), expected_date);
+
+    // A local time cannot be in UTC
+    assert_eq!(
+        Property::parse_property("DTSTART;TZID=America/New_York:20070313T123432Z".to_string())
+            .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DTSTART".to_string())
+    );
+
+    // Parameters without a value
+    assert_eq!(
+        Property::parse_property("DTSTART;TZID:20070313T123432".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DTSTART".to_string())
+    );
+}
+
 #[test]
 fn trigger_parsing_cases() {
-    todo!();
+    let (property, value) = Property::parse_property("TRIGGER:-PT15M".to_string()).unwrap();
+    assert_eq!(property, Property::Trigger);
+    assert_eq!(
+        Trigger::from(value),
+        Trigger::Relative(Duration::minutes(-15), Related::Start)
+    );
+
+    // Without a sign the alarm goes off after the start
+    let (_, value) = Property::parse_property("TRIGGER:PT15M".to_string()).unwrap();
+    assert_eq!(
+        Trigger::from(value),
+        Trigger::Relative(Duration::minutes(15), Related::Start)
+    );
+    let (_, value) = Property::parse_property("TRIGGER:+PT15M".to_string()).unwrap();
+    assert_eq!(
+        Trigger::from(value),
+        Trigger::Relative(Duration::minutes(15), Related::Start)
+    );
+
+    let (_, value) = Property::parse_property("TRIGGER;RELATED=END:PT5M".to_string()).unwrap();
+    assert_eq!(
+        Trigger::from(value),
+        Trigger::Relative(Duration::minutes(5), Related::End)
+    );
+
+    let (_, value) =
+        Property::parse_property("TRIGGER;VALUE=DATE-TIME:19980101T050000Z".to_string()).unwrap();
+    assert_eq!(
+        Trigger::from(value),
+        Trigger::Absolute(
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(1998, 1, 1, 5, 0, 0)
+                .unwrap()
+        )
+    );
+
+    for line in [
+        "TRIGGER;RELATED=MIDDLE:PT5M",
+        "TRIGGER;VALUE=DATE:19980101",
+        "TRIGGER;VALUE=DATE-TIME:-PT15M",
+        "TRIGGER:19980101T050000Z",
+    ] {
+        assert_eq!(
+            Property::parse_property(line.to_string()).unwrap_err(),
+            ICSError::PropertyConditionNotRespected("TRIGGER".to_string()),
+            "{line}"
+        );
+    }
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn x_property_parsing_cases() {
-    todo!();
+    let (property, parameters, value) = Property::parse_property_with_options(
+        "X-MOZ-GENERATION;X-PARAM=1:3".to_string(),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::XProp);
+    assert_eq!(parameters.get("X-PARAM"), Some("1"));
+    let x_prop = XProp::from(value);
+    assert_eq!(x_prop.name, "X-MOZ-GENERATION");
+    assert_eq!(x_prop.value, "3");
+
+    // The value is kept as it was written, even when empty
+    let (_, value) = Property::parse_property("x-note:a\\, b".to_string()).unwrap();
+    assert_eq!(XProp::from(value).value, "a\\, b");
+    let (_, value) = Property::parse_property("X-EMPTY:".to_string()).unwrap();
+    assert_eq!(XProp::from(value).value, "");
+
+    // The name is still a token
+    assert!(Property::parse_property("X-NOT A TOKEN:1".to_string()).is_err());
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn iana_token_parse_cases() {
-    todo!();
+    // An unknown property is refused by a strict parse
+    assert_eq!(
+        Property::parse_property("COLOR:turquoise".to_string()).unwrap_err(),
+        ICSError::UknownProperty("COLOR".to_string())
+    );
+
+    let (property, _, value) = Property::parse_property_with_options(
+        "COLOR:turquoise".to_string(),
+        &ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::IanaProp);
+    let iana_prop = IanaProp::from(value);
+    assert_eq!(iana_prop.name, "COLOR");
+    assert_eq!(iana_prop.value, "turquoise");
+
+    // The name is still a token
+    assert_eq!(
+        Property::parse_property_with_options(
+            "NOT A TOKEN:1".to_string(),
+            &ParseOptions::lenient()
+        )
+        .unwrap_err(),
+        ICSError::UknownProperty("NOT A TOKEN".to_string())
+    );
+}
+
+#[test]
+fn empty_values_with_parameters() {
+    assert_eq!(
+        Property::parse_property("GEO;VALUE=FLOAT:".to_string()).unwrap_err(),
+        ICSError::UnableToParseProperty("GEO".to_string())
+    );
+
+    for line in [
+        "DTSTART;VALUE=DATE:",
+        "DURATION;X-PARAM=1:",
+        "RRULE;X-PARAM=1:",
+        "TRIGGER;RELATED=END:",
+        "ORGANIZER;CN=John Smith:",
+        "PRIORITY;X-PARAM=1:",
+        "STATUS;X-PARAM=1:",
+        "ATTACH;FMTTYPE=text/plain:",
+        "CLASS;X-PARAM=1:",
+        "TZOFFSETTO;X-PARAM=1:",
+    ] {
+        let name = line.split_once(';').unwrap().0;
+        assert_eq!(
+            Property::parse_property(line.to_string()).unwrap_err(),
+            ICSError::UnableToParseProperty(name.to_string()),
+            "{line}"
+        );
+    }
+
+    // An empty text is still a valid text
+    let (_, value) = Property::parse_property("SUMMARY;LANGUAGE=en:".to_string()).unwrap();
+    assert_eq!(String::from(value), "");
+}
+
+#[test]
+fn attach_binary() {
+    let (property, value) = Property::parse_property(
+        "ATTACH;ENCODING=BASE64;VALUE=BINARY:VGhlIHF1aWNrIGJyb3duIGZveA==".to_string(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::Attach);
+    assert_eq!(
+        Attachment::from(value),
+        Attachment::Binary(b"The quick brown fox".to_vec())
+    );
+
+    // The content must be valid BASE64
+    assert!(
+        Property::parse_property("ATTACH;ENCODING=BASE64;VALUE=BINARY:VGhl*".to_string()).is_err()
+    );
+}
+
+#[test]
+fn categories_escaped_commas() {
+    let (_, value) =
+        Property::parse_property("CATEGORIES:Food\\, Drink,Travel".to_string()).unwrap();
+    assert_eq!(<Vec<String>>::from(value), vec!["Food, Drink", "Travel"]);
+
+    // An escaped backslash does not escape the comma after it
+    let (_, value) = Property::parse_property("CATEGORIES:C:\\\\,D".to_string()).unwrap();
+    assert_eq!(<Vec<String>>::from(value), vec!["C:\\", "D"]);
+
+    let (_, value) = Property::parse_property("CATEGORIES:A,,B,".to_string()).unwrap();
+    assert_eq!(<Vec<String>>::from(value), vec!["A", "", "B", ""]);
+}
+
+#[test]
+fn text_control_characters() {
+    assert_eq!(
+        Property::parse_property("SUMMARY:Budget\u{0}review".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("SUMMARY contains a control character".to_string())
+    );
+    assert!(Property::parse_property("CATEGORIES:WORK,\u{7}".to_string()).is_err());
+
+    // A tab and an escaped line break are allowed
+    let (_, value) =
+        Property::parse_property("DESCRIPTION:Agenda\\n\t1. Budget".to_string()).unwrap();
+    assert_eq!(String::from(value), "Agenda\n\t1. Budget");
+
+    // A lenient parse keeps the character
+    let (_, _, value) = Property::parse_property_with_options(
+        "SUMMARY:Budget\u{0}review".to_string(),
+        &ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(String::from(value), "Budget\u{0}review");
+}
+
+#[test]
+fn letter_priorities() {
+    let options = ParseOptions::compatibility();
+    let (property, _, value) =
+        Property::parse_property_with_options("PRIORITY:A".to_string(), &options).unwrap();
+    assert_eq!(property, Property::Priority);
+    assert_eq!(usize::from(value), 1);
+    let (_, _, value) =
+        Property::parse_property_with_options("PRIORITY:B".to_string(), &options).unwrap();
+    assert_eq!(usize::from(value), 5);
+    let (_, _, value) =
+        Property::parse_property_with_options("PRIORITY:C".to_string(), &options).unwrap();
+    assert_eq!(usize::from(value), 9);
+
+    // Numbers are still read as such
+    let (_, _, value) =
+        Property::parse_property_with_options("PRIORITY:3".to_string(), &options).unwrap();
+    assert_eq!(usize::from(value), 3);
+
+    assert!(Property::parse_property_with_options("PRIORITY:D".to_string(), &options).is_err());
+    // Only in compatibility mode
+    assert!(Property::parse_property("PRIORITY:A".to_string()).is_err());
+}
+
+#[test]
+fn unknown_parameters_kept() {
+    let (property, parameters, _) = Property::parse_property_with_options(
+        "DTSTART;X-SOURCE=import;TZID=America/New_York:19980119T020000".to_string(),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(property, Property::DTStart);
+    assert_eq!(parameters.get("X-SOURCE"), Some("import"));
+    assert_eq!(parameters.get("TZID"), Some("America/New_York"));
+
+    // Quoted values can contain the separators
+    let (_, parameters, value) = Property::parse_property_with_options(
+        "ATTENDEE;CN=\"Doe; John: Jr\";X-NOTE=1:mailto:jdoe@example.com".to_string(),
+        &ParseOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(parameters.get("CN"), Some("\"Doe; John: Jr\""));
+    assert_eq!(parameters.get("X-NOTE"), Some("1"));
+    assert_eq!(
+        CalAdress::from(value).address.to_string(),
+        "mailto:jdoe@example.com"
+    );
+}
+
+#[test]
+fn large_sequence() {
+    let result = Property::parse_property("SEQUENCE:4294967296".to_string());
+    #[cfg(target_pointer_width = "64")]
+    assert_eq!(usize::from(result.unwrap().1), 4294967296);
+    #[cfg(not(target_pointer_width = "64"))]
+    assert_eq!(
+        result.unwrap_err(),
+        ICSError::PropertyConditionNotRespected("SEQUENCE is too large".to_string())
+    );
+
+    // Larger than any integer the crate can hold
+    assert_eq!(
+        Property::parse_property("SEQUENCE:99999999999999999999".to_string()).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("SEQUENCE is too large".to_string())
+    );
 }
@@ -12,16 +12,18 @@
 
 */
 
-use crate::ics_error::ICSError;
+use crate::{ics_error::ICSError, utils};
 
 #[cfg(test)]
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
     Audio,
     Display,
     Email,
+    IanaToken(String),
+    XName(String),
 }
 
 impl std::str::FromStr for Action {
@@ -32,9 +34,11 @@ impl std::str::FromStr for Action {
             "AUDIO" => Ok(Action::Audio),
             "DISPLAY" => Ok(Action::Display),
             "EMAIL" => Ok(Action::Email),
-            _ => Err(ICSError::PropertyConditionNotRespected(
+            _ if !utils::is_iana_token(s) => Err(ICSError::PropertyConditionNotRespected(
                 "ACTION".to_string(),
             )),
+            _ if s.starts_with("X-") => Ok(Action::XName(s.to_string())),
+            _ => Ok(Action::IanaToken(s.to_string())),
         }
     }
 }
@@ -45,6 +49,7 @@ impl From<Action> for String {
             Action::Audio => "AUDIO".to_string(),
             Action::Display => "DISPLAY".to_string(),
             Action::Email => "EMAIL".to_string(),
+            Action::IanaToken(string) | Action::XName(string) => string,
         }
     }
 }
@@ -54,6 +59,15 @@ fn from_str() {
     assert_eq!(Action::from_str("AUDIO").unwrap(), Action::Audio);
     assert_eq!(Action::from_str("DISPLAY").unwrap(), Action::Display);
     assert_eq!(Action::from_str("EMAIL").unwrap(), Action::Email);
+    assert_eq!(
+        Action::from_str("X-SMS").unwrap(),
+        Action::XName("X-SMS".to_string())
+    );
+    assert_eq!(
+        Action::from_str("PROCEDURE").unwrap(),
+        Action::IanaToken("PROCEDURE".to_string())
+    );
+    assert!(Action::from_str("NOT A TOKEN").is_err());
 }
 
 #[test]
@@ -61,4 +75,5 @@ fn to_str() {
     assert_eq!(String::from(Action::Audio), "AUDIO");
     assert_eq!(String::from(Action::Display), "DISPLAY");
     assert_eq!(String::from(Action::Email), "EMAIL");
+    assert_eq!(String::from(Action::XName("X-SMS".to_string())), "X-SMS");
 }
@@ -0,0 +1,172 @@
+/*
+The property is defined by the following notation:
+
+  attach     = "ATTACH" attachparam ( ":" uri ) /
+               (
+                 ";" "ENCODING" "=" "BASE64"
+                 ";" "VALUE" "=" "BINARY"
+                 ":" binary
+               )
+               CRLF
+
+  binary     = *(4b-char) [b-end]
+  ; A "BASE64" encoded character string, as defined by [RFC4648].
+
+  b-end      = (2b-char "==") / (3b-char "=")
+
+  b-char = ALPHA / DIGIT / "+" / "/"
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{uri::Uri, Parameters};
+use crate::ics_error::ICSError;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A document attached to a component, either referenced by a URI or given inline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Attachment {
+    Uri(Uri),
+    // The decoded content
+    Binary(Vec<u8>),
+}
+
+impl Attachment {
+    /// Builds the attachment from the value of the property and its parameters.
+    pub fn new(value: &str, parameters: &Parameters) -> Result<Attachment, ICSError> {
+        match parameters.get("VALUE") {
+            Some(value_type) if value_type.eq_ignore_ascii_case("BINARY") => {
+                // An inline attachment is always encoded in BASE64
+                if !parameters
+                    .get("ENCODING")
+                    .is_some_and(|encoding| encoding.eq_ignore_ascii_case("BASE64"))
+                {
+                    return Err(ICSError::PropertyConditionNotRespected(
+                        "ATTACH".to_string(),
+                    ));
+                }
+                Ok(Attachment::Binary(decode_base64(value).ok_or_else(
+                    || ICSError::UnableToParseProperty(format!("ATTACH:{value}")),
+                )?))
+            }
+            Some(value_type) if !value_type.eq_ignore_ascii_case("URI") => Err(
+                ICSError::PropertyConditionNotRespected("ATTACH".to_string()),
+            ),
+            _ => Ok(Attachment::Uri(Uri::from_str(value)?)),
+        }
+    }
+
+    /// Returns the URI of a referenced attachment.
+    pub fn uri(&self) -> Option<&Uri> {
+        match self {
+            Attachment::Uri(uri) => Some(uri),
+            Attachment::Binary(_) => None,
+        }
+    }
+
+    /// Returns the parameters to write with the property, those read with it along with
+    /// the ENCODING and VALUE an inline attachment needs.
+    pub fn parameters(&self, parameters: Option<&Parameters>) -> Parameters {
+        let mut parameters = parameters.cloned().unwrap_or_default();
+        parameters.remove("ENCODING");
+        parameters.remove("VALUE");
+        if let Attachment::Binary(_) = self {
+            parameters.insert("ENCODING".to_string(), "BASE64".to_string());
+            parameters.insert("VALUE".to_string(), "BINARY".to_string());
+        }
+        parameters
+    }
+}
+
+impl fmt::Display for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Attachment::Uri(uri) => write!(f, "{uri}"),
+            Attachment::Binary(content) => write!(f, "{}", encode_base64(content)),
+        }
+    }
+}
+
+fn encode_base64(content: &[u8]) -> String {
+    let mut output = String::with_capacity(content.len().div_ceil(3) * 4);
+    for chunk in content.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(BASE64_ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Decodes a BASE64 text, returns None if it is not properly padded or has another character.
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+    let value = value.as_bytes();
+    if !value.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut output = Vec::with_capacity(value.len() / 4 * 3);
+    for (chunk_index, chunk) in value.chunks(4).enumerate() {
+        let last = chunk_index == value.len() / 4 - 1;
+        let padding = chunk.iter().rev().take_while(|byte| **byte == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return None;
+        }
+        let mut group: u32 = 0;
+        for byte in &chunk[..4 - padding] {
+            let position = BASE64_ALPHABET.iter().position(|b64| b64 == byte)?;
+            group = group << 6 | position as u32;
+        }
+        group <<= 6 * padding;
+        output.extend_from_slice(&group.to_be_bytes()[1..4 - padding]);
+    }
+    Some(output)
+}
+
+#[test]
+fn base64() {
+    for (content, encoded) in [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("The quick brown fox", "VGhlIHF1aWNrIGJyb3duIGZveA=="),
+    ] {
+        assert_eq!(encode_base64(content.as_bytes()), encoded);
+        assert_eq!(decode_base64(encoded).unwrap(), content.as_bytes());
+    }
+
+    assert!(decode_base64("Zm9").is_none());
+    assert!(decode_base64("Zg==Zm9v").is_none());
+    assert!(decode_base64("Zm9*").is_none());
+}
+
+#[test]
+fn attachment_new() {
+    let mut parameters = Parameters::new();
+    let attachment = Attachment::new("ftp://example.com/pub/sounds/bell-01.aud", &parameters);
+    assert!(matches!(attachment, Ok(Attachment::Uri(_))));
+
+    parameters.insert("ENCODING".to_string(), "BASE64".to_string());
+    parameters.insert("VALUE".to_string(), "BINARY".to_string());
+    let attachment = Attachment::new("Zm9v", &parameters).unwrap();
+    assert_eq!(attachment, Attachment::Binary(b"foo".to_vec()));
+    assert_eq!(attachment.to_string(), "Zm9v");
+    assert_eq!(attachment.parameters(None), parameters);
+
+    // The encoding must be given with an inline attachment
+    parameters.remove("ENCODING");
+    assert!(Attachment::new("Zm9v", &parameters).is_err());
+}
@@ -1,2 +1,318 @@
-#[derive(Debug)]
-pub struct CalAdress {}
+/*
+The value type is defined by the following notation:
+
+  cal-address        = uri
+
+The parameters read along with it are:
+
+  cnparam    = "CN" "=" param-value
+
+  roleparam  = "ROLE" "="
+              ("CHAIR"             ; Indicates chair of the
+                                   ; calendar entity
+             / "REQ-PARTICIPANT"   ; Indicates a participant whose
+                                   ; participation is required
+             / "OPT-PARTICIPANT"   ; Indicates a participant whose
+                                   ; participation is optional
+             / "NON-PARTICIPANT"   ; Indicates a participant who
+                                   ; is copied for information
+                                   ; purposes only
+             / x-name              ; Experimental role
+             / iana-token)         ; Other IANA role
+  ; Default is REQ-PARTICIPANT
+
+  partstat-todo    = ("NEEDS-ACTION"    ; To-do needs action
+                   / "ACCEPTED"         ; To-do accepted
+                   / "DECLINED"         ; To-do declined
+                   / "TENTATIVE"        ; To-do tentatively
+                                        ; accepted
+                   / "DELEGATED"        ; To-do delegated
+                   / "COMPLETED"        ; To-do completed
+                                        ; COMPLETED property has
+                                        ; DATE-TIME completed
+                   / "IN-PROCESS"       ; To-do in process of
+                                        ; being completed
+                   / x-name             ; Experimental status
+                   / iana-token)        ; Other IANA-registered
+                                        ; status
+
+  rsvpparam = "RSVP" "=" ("TRUE" / "FALSE")
+  ; Default is FALSE
+
+Other parameters are kept as they are so that they can be written back.
+*/
+
+use super::{uri::Uri, Parameters};
+use crate::ics_error::ICSError;
+use std::str::FromStr;
+
+/// The participation role of a calendar user given by the ROLE parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Role {
+    Chair,
+    ReqParticipant,
+    OptParticipant,
+    NonParticipant,
+    Other(String),
+}
+
+impl From<&str> for Role {
+    fn from(value: &str) -> Self {
+        match value.to_ascii_uppercase().as_str() {
+            "CHAIR" => Role::Chair,
+            "REQ-PARTICIPANT" => Role::ReqParticipant,
+            "OPT-PARTICIPANT" => Role::OptParticipant,
+            "NON-PARTICIPANT" => Role::NonParticipant,
+            _ => Role::Other(value.to_string()),
+        }
+    }
+}
+
+impl From<Role> for String {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Chair => "CHAIR".to_string(),
+            Role::ReqParticipant => "REQ-PARTICIPANT".to_string(),
+            Role::OptParticipant => "OPT-PARTICIPANT".to_string(),
+            Role::NonParticipant => "NON-PARTICIPANT".to_string(),
+            Role::Other(string) => string,
+        }
+    }
+}
+
+/// The participation status of a calendar user given by the PARTSTAT parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartStat {
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+    Completed,
+    InProcess,
+    Other(String),
+}
+
+impl From<&str> for PartStat {
+    fn from(value: &str) -> Self {
+        match value.to_ascii_uppercase().as_str() {
+            "NEEDS-ACTION" => PartStat::NeedsAction,
+            "ACCEPTED" => PartStat::Accepted,
+            "DECLINED" => PartStat::Declined,
+            "TENTATIVE" => PartStat::Tentative,
+            "DELEGATED" => PartStat::Delegated,
+            "COMPLETED" => PartStat::Completed,
+            "IN-PROCESS" => PartStat::InProcess,
+            _ => PartStat::Other(value.to_string()),
+        }
+    }
+}
+
+impl From<PartStat> for String {
+    fn from(partstat: PartStat) -> Self {
+        match partstat {
+            PartStat::NeedsAction => "NEEDS-ACTION".to_string(),
+            PartStat::Accepted => "ACCEPTED".to_string(),
+            PartStat::Declined => "DECLINED".to_string(),
+            PartStat::Tentative => "TENTATIVE".to_string(),
+            PartStat::Delegated => "DELEGATED".to_string(),
+            PartStat::Completed => "COMPLETED".to_string(),
+            PartStat::InProcess => "IN-PROCESS".to_string(),
+            PartStat::Other(string) => string,
+        }
+    }
+}
+
+/// A calendar user, such as an organizer or an attendee, and the parameters describing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalAdress {
+    // Usually a MAILTO: URI
+    pub address: Uri,
+    // The name of the calendar user given by CN
+    pub common_name: Option<String>,
+    pub role: Option<Role>,
+    pub partstat: Option<PartStat>,
+    // Whether a reply is expected, None if the parameter was not given
+    pub rsvp: Option<bool>,
+    // The parameters not listed above, in the order they were read
+    pub other_parameters: Parameters,
+}
+
+impl CalAdress {
+    /// Builds the calendar user from its address and parameters.
+    /// An RSVP that is neither TRUE nor FALSE is refused when strict, and ignored otherwise.
+    pub fn new(
+        address: &str,
+        parameters: &Parameters,
+        strict: bool,
+    ) -> Result<CalAdress, ICSError> {
+        let mut cal_address = CalAdress::from_str(address)?;
+
+        // A quoted name is needed if it contains a ':', ';' or ','
+        cal_address.common_name = parameters
+            .get("CN")
+            .map(|name| name.trim_matches('"').to_string());
+
+        cal_address.role = parameters.get("ROLE").map(Role::from);
+        cal_address.partstat = parameters.get("PARTSTAT").map(PartStat::from);
+
+        for (name, value) in parameters.iter() {
+            if !matches!(name, "CN" | "ROLE" | "PARTSTAT" | "RSVP") {
+                cal_address
+                    .other_parameters
+                    .insert(name.to_string(), value.to_string());
+            }
+        }
+
+        cal_address.rsvp = match parameters.get("RSVP") {
+            Some(value) if value.eq_ignore_ascii_case("TRUE") => Some(true),
+            Some(value) if value.eq_ignore_ascii_case("FALSE") => Some(false),
+            Some(_) if strict => {
+                return Err(ICSError::PropertyConditionNotRespected("RSVP".to_string()))
+            }
+            _ => None,
+        };
+
+        Ok(cal_address)
+    }
+
+    /// Returns the email of the calendar user if the address uses the MAILTO scheme.
+    pub fn email(&self) -> Option<&str> {
+        if self.address.scheme().eq_ignore_ascii_case("MAILTO") {
+            Some(self.address.value())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the parameters to write with the address.
+    pub fn parameters(&self) -> Parameters {
+        let mut parameters = Parameters::new();
+        if let Some(common_name) = &self.common_name {
+            if common_name.contains([':', ';', ',']) {
+                parameters.insert("CN".to_string(), format!("\"{common_name}\""));
+            } else {
+                parameters.insert("CN".to_string(), common_name.clone());
+            }
+        }
+        if let Some(role) = &self.role {
+            parameters.insert("ROLE".to_string(), String::from(role.clone()));
+        }
+        if let Some(partstat) = &self.partstat {
+            parameters.insert("PARTSTAT".to_string(), String::from(partstat.clone()));
+        }
+        if let Some(rsvp) = self.rsvp {
+            parameters.insert(
+                "RSVP".to_string(),
+                if rsvp { "TRUE" } else { "FALSE" }.to_string(),
+            );
+        }
+        for (name, value) in self.other_parameters.iter() {
+            parameters.insert(name.to_string(), value.to_string());
+        }
+        parameters
+    }
+}
+
+impl std::str::FromStr for CalAdress {
+    type Err = ICSError;
+
+    /// Reads an address without parameters, it must be a URI.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(CalAdress {
+            address: Uri::from_str(s)?,
+            common_name: None,
+            role: None,
+            partstat: None,
+            rsvp: None,
+            other_parameters: Parameters::new(),
+        })
+    }
+}
+
+#[test]
+fn from_str() {
+    let organizer = CalAdress::from_str("MAILTO:jsmith@host.com").unwrap();
+    assert_eq!(organizer.address.to_string(), "MAILTO:jsmith@host.com");
+    assert_eq!(organizer.email(), Some("jsmith@host.com"));
+    assert_eq!(organizer.common_name, None);
+
+    let organizer = CalAdress::from_str("mailto:jsmith@host.com").unwrap();
+    assert_eq!(organizer.email(), Some("jsmith@host.com"));
+
+    let organizer = CalAdress::from_str("urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap();
+    assert_eq!(organizer.email(), None);
+
+    assert!(CalAdress::from_str("jsmith@host.com").is_err());
+}
+
+#[test]
+fn common_name_parameter() {
+    let mut parameters = Parameters::new();
+    parameters.insert("CN".to_string(), "John Smith".to_string());
+    let organizer = CalAdress::new("MAILTO:jsmith@host.com", &parameters, true).unwrap();
+    assert_eq!(organizer.common_name.as_deref(), Some("John Smith"));
+    assert_eq!(organizer.parameters().get("CN"), Some("John Smith"));
+
+    let mut parameters = Parameters::new();
+    parameters.insert("CN".to_string(), "\"Smith, John\"".to_string());
+    let organizer = CalAdress::new("MAILTO:jsmith@host.com", &parameters, true).unwrap();
+    assert_eq!(organizer.common_name.as_deref(), Some("Smith, John"));
+    assert_eq!(organizer.parameters().get("CN"), Some("\"Smith, John\""));
+}
+
+#[test]
+fn attendee_parameters() {
+    let mut parameters = Parameters::new();
+    parameters.insert("ROLE".to_string(), "REQ-PARTICIPANT".to_string());
+    parameters.insert("PARTSTAT".to_string(), "accepted".to_string());
+    parameters.insert("CN".to_string(), "Jane Doe".to_string());
+    parameters.insert("CUTYPE".to_string(), "INDIVIDUAL".to_string());
+    parameters.insert("X-NUM-GUESTS".to_string(), "0".to_string());
+
+    let attendee = CalAdress::new("mailto:jdoe@example.com", &parameters, true).unwrap();
+    assert_eq!(attendee.role, Some(Role::ReqParticipant));
+    assert_eq!(attendee.partstat, Some(PartStat::Accepted));
+    assert_eq!(attendee.common_name.as_deref(), Some("Jane Doe"));
+    assert_eq!(attendee.other_parameters.get("CUTYPE"), Some("INDIVIDUAL"));
+    assert_eq!(attendee.other_parameters.get("X-NUM-GUESTS"), Some("0"));
+
+    // Everything is written back
+    let written = attendee.parameters();
+    assert_eq!(written.get("PARTSTAT"), Some("ACCEPTED"));
+    assert_eq!(written.get("ROLE"), Some("REQ-PARTICIPANT"));
+    assert_eq!(written.get("CUTYPE"), Some("INDIVIDUAL"));
+    assert_eq!(written.get("X-NUM-GUESTS"), Some("0"));
+
+    assert_eq!(
+        PartStat::from("X-WAITING"),
+        PartStat::Other("X-WAITING".to_string())
+    );
+    assert_eq!(Role::from("chair"), Role::Chair);
+}
+
+#[test]
+fn rsvp_parameter() {
+    let address = "mailto:a@b.com";
+    let mut parameters = Parameters::new();
+    assert_eq!(
+        CalAdress::new(address, &parameters, true).unwrap().rsvp,
+        None
+    );
+
+    parameters.insert("RSVP".to_string(), "false".to_string());
+    let attendee = CalAdress::new(address, &parameters, true).unwrap();
+    assert_eq!(attendee.rsvp, Some(false));
+    assert_eq!(attendee.parameters().get("RSVP"), Some("FALSE"));
+
+    let mut parameters = Parameters::new();
+    parameters.insert("RSVP".to_string(), "YES".to_string());
+    assert_eq!(
+        CalAdress::new(address, &parameters, true).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("RSVP".to_string())
+    );
+    assert_eq!(
+        CalAdress::new(address, &parameters, false).unwrap().rsvp,
+        None
+    );
+}
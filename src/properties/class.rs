@@ -9,12 +9,12 @@ The property is defined by the following notation:
   ;Default is PUBLIC
 */
 
-use crate::ics_error::ICSError;
+use crate::{ics_error::ICSError, utils};
 #[cfg(test)]
 use std::str::FromStr;
 
 /// This property defines the access classification for a calendar component.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Class {
     PUBLIC,
     PRIVATE,
@@ -31,7 +31,11 @@ impl std::str::FromStr for Class {
             "PUBLIC" => Ok(Class::PUBLIC),
             "PRIVATE" => Ok(Class::PRIVATE),
             "CONFIDENTIAL" => Ok(Class::CONFIDENTIAL),
-            _ => todo!("Not implemented for IANA-TOKEN or X-NAME"),
+            _ if !utils::is_iana_token(s) => Err(ICSError::PropertyConditionNotRespected(format!(
+                "CLASS: {s}"
+            ))),
+            _ if s.starts_with("X-") => Ok(Class::XNAME(s.to_string())),
+            _ => Ok(Class::IANATOKEN(s.to_string())),
         }
     }
 }
@@ -56,7 +60,20 @@ fn from_str() {
         Class::from_str("CONFIDENTIAL").unwrap(),
         Class::CONFIDENTIAL
     );
-    // Need to add X-NAME and IANA-TOKEN
+    assert_eq!(
+        Class::from_str("X-PERSONAL").unwrap(),
+        Class::XNAME("X-PERSONAL".to_string())
+    );
+    assert_eq!(
+        Class::from_str("SHARED").unwrap(),
+        Class::IANATOKEN("SHARED".to_string())
+    );
+
+    // The error tells which value was refused
+    assert_eq!(
+        Class::from_str("NOT A TOKEN").unwrap_err(),
+        ICSError::PropertyConditionNotRespected("CLASS: NOT A TOKEN".to_string())
+    );
 }
 
 #[test]
@@ -64,5 +81,12 @@ fn to_str() {
     assert_eq!(String::from(Class::PUBLIC), "PUBLIC");
     assert_eq!(String::from(Class::PRIVATE), "PRIVATE");
     assert_eq!(String::from(Class::CONFIDENTIAL), "CONFIDENTIAL");
-    // Need to add X-NAME and IANA-TOKEN
+    assert_eq!(
+        String::from(Class::XNAME("X-PERSONAL".to_string())),
+        "X-PERSONAL"
+    );
+    assert_eq!(
+        String::from(Class::IANATOKEN("SHARED".to_string())),
+        "SHARED"
+    );
 }
@@ -0,0 +1,122 @@
+/*
+The value type is defined by the following notation:
+
+  date-time  = date "T" time ;As specified in the DATE and TIME
+                             ;value definitions
+
+  time       = time-hour time-minute time-second [time-utc]
+
+  time-utc   = "Z"
+
+The same notation describes three forms of time:
+
+  FORM #1: DATE WITH LOCAL TIME, also called floating, 19980118T230000
+  FORM #2: DATE WITH UTC TIME, 19980119T070000Z
+  FORM #3: DATE WITH LOCAL TIME AND TIME ZONE REFERENCE,
+           TZID=America/New_York:19980119T020000
+
+A property given with VALUE=DATE only holds a date, 19970714, which covers the whole day.
+*/
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::properties::Parameters;
+
+/// A time along with the form it was given in, which tells how it should be understood.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateTimeValue {
+    /// An absolute time, given with a trailing Z
+    Utc(DateTime<Utc>),
+    /// A floating time, the same local time wherever the reader is
+    Local(NaiveDateTime),
+    /// A local time of the zone given by the TZID
+    Zoned(String, NaiveDateTime),
+    /// A whole day, given with VALUE=DATE
+    Date(NaiveDate),
+}
+
+impl DateTimeValue {
+    /// Returns the form of a time property from its value and the parameters it was read with.
+    pub fn new(value: &DateTime<FixedOffset>, parameters: Option<&Parameters>) -> DateTimeValue {
+        if let Some(parameters) = parameters {
+            if parameters.get("VALUE") == Some("DATE") {
+                return DateTimeValue::Date(value.date_naive());
+            }
+            if let Some(tzid) = parameters.get("TZID") {
+                return DateTimeValue::Zoned(tzid.to_string(), value.naive_local());
+            }
+            if parameters.is_floating() {
+                return DateTimeValue::Local(value.naive_local());
+            }
+        }
+        DateTimeValue::Utc(value.with_timezone(&Utc))
+    }
+
+    /// Returns true if the time does not depend on the zone of the reader.
+    pub fn is_absolute(&self) -> bool {
+        !matches!(self, DateTimeValue::Local(_) | DateTimeValue::Date(_))
+    }
+
+    /// Returns the value as it is stored in the components, local times and dates at +00:00.
+    pub fn to_date_time(&self) -> DateTime<FixedOffset> {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        match self {
+            DateTimeValue::Utc(value) => value.with_timezone(&utc),
+            DateTimeValue::Local(value) | DateTimeValue::Zoned(_, value) => {
+                utc.from_utc_datetime(value)
+            }
+            DateTimeValue::Date(value) => {
+                utc.from_utc_datetime(&value.and_hms_opt(0, 0, 0).unwrap())
+            }
+        }
+    }
+
+    /// Sets the parameters telling the form of the value, VALUE=DATE, TZID or a floating time.
+    pub fn apply_to(&self, parameters: &mut Parameters) {
+        parameters.remove("VALUE");
+        parameters.remove("TZID");
+        parameters.set_floating(false);
+        match self {
+            DateTimeValue::Utc(_) => {}
+            DateTimeValue::Local(_) => parameters.set_floating(true),
+            DateTimeValue::Zoned(tzid, _) => parameters.insert("TZID".to_string(), tzid.clone()),
+            DateTimeValue::Date(_) => parameters.insert("VALUE".to_string(), "DATE".to_string()),
+        }
+    }
+}
+
+#[test]
+fn date_time_forms() {
+    use crate::properties::Property;
+
+    let parse = |line: &str| {
+        let (_, parameters, value) = Property::parse_property_with_options(
+            line.to_string(),
+            &crate::parse_options::ParseOptions::default(),
+        )
+        .unwrap();
+        DateTimeValue::new(&value.into(), Some(&parameters))
+    };
+    let local = chrono::NaiveDate::from_ymd_opt(1998, 1, 18)
+        .unwrap()
+        .and_hms_opt(23, 0, 0)
+        .unwrap();
+
+    assert_eq!(
+        parse("DTSTART:19980118T230000"),
+        DateTimeValue::Local(local)
+    );
+    assert_eq!(
+        parse("DTSTART:19980118T230000Z"),
+        DateTimeValue::Utc(DateTime::from_utc(local, Utc))
+    );
+    assert_eq!(
+        parse("DTSTART;TZID=America/New_York:19980118T230000"),
+        DateTimeValue::Zoned("America/New_York".to_string(), local)
+    );
+    assert!(!parse("DTSTART:19980118T230000").is_absolute());
+    assert_eq!(
+        parse("DTSTART;VALUE=DATE:19980118"),
+        DateTimeValue::Date(local.date())
+    );
+}
@@ -0,0 +1,140 @@
+/*
+The property is defined by the following notation:
+
+  freebusy   = "FREEBUSY" fbparam ":" fbvalue CRLF
+
+  fbparam    = *(
+             ;
+             ; The following is OPTIONAL,
+             ; but MUST NOT occur more than once.
+             ;
+             (";" fbtypeparam) /
+             ;
+             ; The following is OPTIONAL,
+             ; and MAY occur more than once.
+             ;
+             (";" other-param)
+             ;
+             )
+
+  fbvalue    = period *("," period)
+  ;Time value MUST be in the UTC time format.
+
+  fbtypeparam        = "FBTYPE" "=" ("FREE" / "BUSY"
+                     / "BUSY-UNAVAILABLE" / "BUSY-TENTATIVE"
+                     / x-name
+           ; Some experimental iCalendar free/busy type.
+                     / iana-token)
+           ; Some other IANA-registered iCalendar free/busy type.
+           ; Default is BUSY
+*/
+
+use std::str::FromStr;
+
+use super::{period::Period, Parameters};
+use crate::ics_error::ICSError;
+
+/// The type of free or busy time given by the FBTYPE parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FBType {
+    Free,
+    #[default]
+    Busy,
+    BusyUnavailable,
+    BusyTentative,
+    Other(String),
+}
+
+impl From<&str> for FBType {
+    fn from(value: &str) -> Self {
+        match value.to_ascii_uppercase().as_str() {
+            "FREE" => FBType::Free,
+            "BUSY" => FBType::Busy,
+            "BUSY-UNAVAILABLE" => FBType::BusyUnavailable,
+            "BUSY-TENTATIVE" => FBType::BusyTentative,
+            _ => FBType::Other(value.to_string()),
+        }
+    }
+}
+
+impl From<FBType> for String {
+    fn from(fbtype: FBType) -> Self {
+        match fbtype {
+            FBType::Free => "FREE".to_string(),
+            FBType::Busy => "BUSY".to_string(),
+            FBType::BusyUnavailable => "BUSY-UNAVAILABLE".to_string(),
+            FBType::BusyTentative => "BUSY-TENTATIVE".to_string(),
+            FBType::Other(string) => string,
+        }
+    }
+}
+
+/// Periods of free or busy time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeBusy {
+    pub fbtype: FBType,
+    pub periods: Vec<Period>,
+}
+
+impl FreeBusy {
+    /// Builds the free or busy time from the value of the property and its parameters.
+    pub fn new(value: &str, parameters: &Parameters) -> Result<FreeBusy, ICSError> {
+        let mut periods: Vec<Period> = Vec::new();
+        for period in value.split(',') {
+            periods.push(Period::from_str(period)?);
+        }
+
+        Ok(FreeBusy {
+            fbtype: parameters
+                .get("FBTYPE")
+                .map(FBType::from)
+                .unwrap_or_default(),
+            periods,
+        })
+    }
+
+    /// Returns the parameters to write with the property, the default FBTYPE is omitted.
+    pub fn parameters(&self) -> Parameters {
+        let mut parameters = Parameters::new();
+        if self.fbtype != FBType::Busy {
+            parameters.insert("FBTYPE".to_string(), String::from(self.fbtype.clone()));
+        }
+        parameters
+    }
+
+    /// Returns the value of the property, the periods separated by commas.
+    pub fn value(&self) -> String {
+        self.periods
+            .iter()
+            .map(|period| period.to_string())
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+#[test]
+fn free_busy_new() {
+    let mut parameters = Parameters::new();
+    parameters.insert("FBTYPE".to_string(), "BUSY-UNAVAILABLE".to_string());
+
+    let free_busy = FreeBusy::new(
+        "19970308T160000Z/PT8H30M,19970308T230000Z/19970309T000000Z",
+        &parameters,
+    )
+    .unwrap();
+    assert_eq!(free_busy.fbtype, FBType::BusyUnavailable);
+    assert_eq!(free_busy.periods.len(), 2);
+    assert_eq!(
+        free_busy.value(),
+        "19970308T160000Z/PT8H30M,19970308T230000Z/19970309T000000Z"
+    );
+    assert_eq!(
+        free_busy.parameters().get("FBTYPE"),
+        Some("BUSY-UNAVAILABLE")
+    );
+
+    // BUSY is the default type
+    let free_busy = FreeBusy::new("19970308T160000Z/PT3H", &Parameters::new()).unwrap();
+    assert_eq!(free_busy.fbtype, FBType::Busy);
+    assert!(free_busy.parameters().is_empty());
+}
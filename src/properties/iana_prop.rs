@@ -0,0 +1,62 @@
+/*
+The property is defined by the following notation:
+
+  iana-prop = iana-token *(";" icalparameter) ":" value CRLF
+
+Only the properties registered by the IANA that the crate does not know are read this way,
+such as COLOR or CONFERENCE. The value is kept as text so that it is written back as it was read.
+*/
+
+use super::{split_property, Parameters};
+use crate::{ics_error::ICSError, utils};
+
+/// A property registered by the IANA that the crate does not know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IanaProp {
+    pub name: String,
+    pub parameters: Parameters,
+    pub value: String,
+}
+
+impl IanaProp {
+    /// Reads the property from its unfolded line.
+    pub fn parse(line: &str) -> Result<IanaProp, ICSError> {
+        let (name, parameters, value) = split_property(line)?;
+        if !utils::is_iana_token(name) {
+            return Err(ICSError::UknownProperty(name.to_string()));
+        }
+        Ok(IanaProp {
+            name: name.to_string(),
+            parameters,
+            value: value.to_string(),
+        })
+    }
+
+    /// Writes the property line ending with a CRLF.
+    pub fn write(&self, output: &mut String) {
+        let mut line = self.name.clone();
+        for (param_name, param_value) in self.parameters.canonical() {
+            line.push_str(&format!(";{param_name}={param_value}"));
+        }
+        line.push(':');
+        line.push_str(&self.value);
+        utils::write_line(output, &line);
+    }
+}
+
+#[test]
+fn iana_prop_parse() {
+    let iana_prop = IanaProp::parse("COLOR;X-PARAM=1:turquoise").unwrap();
+    assert_eq!(iana_prop.name, "COLOR");
+    assert_eq!(iana_prop.parameters.get("X-PARAM"), Some("1"));
+    assert_eq!(iana_prop.value, "turquoise");
+
+    let mut output = String::new();
+    iana_prop.write(&mut output);
+    assert_eq!(output, "COLOR;X-PARAM=1:turquoise\r\n");
+
+    assert_eq!(
+        IanaProp::parse("NOT A TOKEN:1").unwrap_err(),
+        ICSError::UknownProperty("NOT A TOKEN".to_string())
+    );
+}
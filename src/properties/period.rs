@@ -0,0 +1,137 @@
+/*
+The value type is defined by the following notation:
+
+  period     = period-explicit / period-start
+
+  period-explicit = date-time "/" date-time
+  ; [ISO.8601.2004] complete representation basic format for a
+  ; period of time consisting of a start and end.  The start MUST
+  ; be before the end.
+
+  period-start = date-time "/" dur-value
+  ; [ISO.8601.2004] complete representation basic format for a
+  ; period of time consisting of a start and positive duration
+  ; of time.
+*/
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+
+use super::parse_duration;
+use crate::ics_error::ICSError;
+
+#[cfg(test)]
+use chrono::TimeZone;
+
+/// A precise period of time, given by its start and either its end or its duration.
+/// The times are always in UTC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Period {
+    Explicit(DateTime<FixedOffset>, DateTime<FixedOffset>),
+    Start(DateTime<FixedOffset>, Duration),
+}
+
+impl Period {
+    pub fn start(&self) -> DateTime<FixedOffset> {
+        match self {
+            Period::Explicit(start, _) | Period::Start(start, _) => *start,
+        }
+    }
+
+    pub fn end(&self) -> DateTime<FixedOffset> {
+        match self {
+            Period::Explicit(_, end) => *end,
+            Period::Start(start, duration) => *start + *duration,
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = ICSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ICSError::PropertyConditionNotRespected("PERIOD".to_string());
+        let parse_date_time = |value: &str| {
+            if !value.ends_with('Z') {
+                return Err(error());
+            }
+            DateTime::parse_from_str(&format!("{value}+0000"), "%Y%m%dT%H%M%SZ%z")
+                .map_err(|_| error())
+        };
+
+        let (start, end) = s.split_once('/').ok_or_else(error)?;
+        let start = parse_date_time(start)?;
+        // The end is a duration when it starts like one
+        let period = if end.starts_with(['P', '+', '-']) {
+            let duration = parse_duration(end).map_err(|_| error())?;
+            if duration <= Duration::zero() {
+                return Err(error());
+            }
+            Period::Start(start, duration)
+        } else {
+            let end = parse_date_time(end)?;
+            if end <= start {
+                return Err(error());
+            }
+            Period::Explicit(start, end)
+        };
+        Ok(period)
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |value: &DateTime<FixedOffset>| {
+            value
+                .with_timezone(&Utc)
+                .format("%Y%m%dT%H%M%SZ")
+                .to_string()
+        };
+        match self {
+            Period::Explicit(start, end) => write!(f, "{}/{}", format(start), format(end)),
+            Period::Start(start, duration) => write!(
+                f,
+                "{}/{}",
+                format(start),
+                crate::utils::format_duration(duration)
+            ),
+        }
+    }
+}
+
+#[test]
+fn period_from_str() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+
+    let period = Period::from_str("19970308T160000Z/PT8H30M").unwrap();
+    assert_eq!(
+        period,
+        Period::Start(
+            utc.with_ymd_and_hms(1997, 3, 8, 16, 0, 0).unwrap(),
+            Duration::minutes(8 * 60 + 30)
+        )
+    );
+    assert_eq!(
+        period.end(),
+        utc.with_ymd_and_hms(1997, 3, 9, 0, 30, 0).unwrap()
+    );
+    assert_eq!(period.to_string(), "19970308T160000Z/PT8H30M");
+
+    let period = Period::from_str("19970308T160000Z/19970308T180000Z").unwrap();
+    assert_eq!(
+        period.end(),
+        utc.with_ymd_and_hms(1997, 3, 8, 18, 0, 0).unwrap()
+    );
+    assert_eq!(period.to_string(), "19970308T160000Z/19970308T180000Z");
+
+    for value in [
+        "19970308T160000Z",
+        "19970308T160000/PT1H",
+        "19970308T160000Z/-PT1H",
+        "19970308T160000Z/19970308T150000Z",
+        "19970308T160000Z/PT",
+    ] {
+        assert!(Period::from_str(value).is_err(), "{value}");
+    }
+}
@@ -0,0 +1,640 @@
+/*
+The value is defined by the following notation:
+
+  recur           = recur-rule-part *( ";" recur-rule-part )
+                  ;
+                  ; The rule parts are not ordered in any
+                  ; particular sequence.
+                  ;
+                  ; The FREQ rule part is REQUIRED,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  ; The UNTIL or COUNT rule parts are OPTIONAL,
+                  ; but they MUST NOT occur in the same 'recur'.
+                  ;
+                  ; The other rule parts are OPTIONAL,
+                  ; but MUST NOT occur more than once.
+
+  recur-rule-part = ( "FREQ" "=" freq )
+                  / ( "UNTIL" "=" enddate )
+                  / ( "COUNT" "=" 1*DIGIT )
+                  / ( "INTERVAL" "=" 1*DIGIT )
+                  / ( "BYSECOND" "=" byseclist )
+                  / ( "BYMINUTE" "=" byminlist )
+                  / ( "BYHOUR" "=" byhrlist )
+                  / ( "BYDAY" "=" bywdaylist )
+                  / ( "BYMONTHDAY" "=" bymodaylist )
+                  / ( "BYYEARDAY" "=" byyrdaylist )
+                  / ( "BYWEEKNO" "=" bywknolist )
+                  / ( "BYMONTH" "=" bymolist )
+                  / ( "BYSETPOS" "=" bysplist )
+                  / ( "WKST" "=" weekday )
+
+  freq        = "SECONDLY" / "MINUTELY" / "HOURLY" / "DAILY"
+              / "WEEKLY" / "MONTHLY" / "YEARLY"
+
+  enddate     = date / date-time
+
+  weekdaynum  = [[plus / minus] ordwk] weekday
+  weekday     = "SU" / "MO" / "TU" / "WE" / "TH" / "FR" / "SA"
+*/
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Utc, Weekday};
+
+use crate::ics_error::ICSError;
+
+/// The type of recurrence given by the FREQ rule part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl FromStr for Frequency {
+    type Err = ICSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SECONDLY" => Ok(Frequency::Secondly),
+            "MINUTELY" => Ok(Frequency::Minutely),
+            "HOURLY" => Ok(Frequency::Hourly),
+            "DAILY" => Ok(Frequency::Daily),
+            "WEEKLY" => Ok(Frequency::Weekly),
+            "MONTHLY" => Ok(Frequency::Monthly),
+            "YEARLY" => Ok(Frequency::Yearly),
+            _ => Err(ICSError::PropertyConditionNotRespected("RRULE".to_string())),
+        }
+    }
+}
+
+impl From<Frequency> for String {
+    fn from(frequency: Frequency) -> Self {
+        match frequency {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+        .to_string()
+    }
+}
+
+/// A recurrence rule, as given by RRULE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recur {
+    pub freq: Frequency,
+    pub interval: Option<u32>,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<FixedOffset>>,
+    // Weekdays with their optional position in the month or year, such as -1 for the last one
+    pub byday: Vec<(Option<i32>, Weekday)>,
+    pub bymonthday: Vec<i32>,
+    pub bymonth: Vec<u32>,
+    pub wkst: Option<Weekday>,
+    // The other rule parts are kept as they were read
+    pub other_parts: Vec<(String, String)>,
+}
+
+impl Recur {
+    pub fn new(freq: Frequency) -> Recur {
+        Recur {
+            freq,
+            interval: None,
+            count: None,
+            until: None,
+            byday: Vec::new(),
+            bymonthday: Vec::new(),
+            bymonth: Vec::new(),
+            wkst: None,
+            other_parts: Vec::new(),
+        }
+    }
+
+    /// Iterates over the occurrences of the rule starting at dtstart, which is always the first one.
+    /// FREQ, INTERVAL, COUNT, UNTIL, BYDAY, BYMONTHDAY, BYMONTH and WKST are applied, the other
+    /// rule parts are not.
+    pub fn occurrences(&self, dtstart: DateTime<FixedOffset>) -> Occurrences<'_> {
+        Occurrences {
+            recur: self,
+            dtstart,
+            period: 0,
+            empty_periods: 0,
+            pending: VecDeque::from([dtstart]),
+            emitted: 0,
+            done: false,
+        }
+    }
+
+    // Dates of the given weekdays in the days, positions are counted from the first or last day
+    fn dates_by_day(&self, days: &[NaiveDate]) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        for (position, weekday) in &self.byday {
+            let matching: Vec<NaiveDate> = days
+                .iter()
+                .filter(|date| date.weekday() == *weekday)
+                .copied()
+                .collect();
+            match position {
+                None => dates.extend(matching),
+                Some(position) if *position > 0 => {
+                    dates.extend(matching.get(*position as usize - 1))
+                }
+                Some(position) => dates.extend(
+                    matching
+                        .len()
+                        .checked_sub(position.unsigned_abs() as usize)
+                        .map(|index| matching[index]),
+                ),
+            }
+        }
+        dates
+    }
+
+    // Dates of the month selected by BYMONTHDAY and BYDAY, or the day of DTSTART
+    fn dates_in_month(&self, year: i32, month: u32, dtstart: NaiveDate) -> Vec<NaiveDate> {
+        let days: Vec<NaiveDate> = (1..=days_in_month(year, month))
+            .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+            .collect();
+
+        let by_month_day: Vec<NaiveDate> = days
+            .iter()
+            .filter(|date| self.matches_month_day(**date))
+            .copied()
+            .collect();
+        match (self.bymonthday.is_empty(), self.byday.is_empty()) {
+            (true, true) => NaiveDate::from_ymd_opt(year, month, dtstart.day())
+                .into_iter()
+                .collect(),
+            (false, true) => by_month_day,
+            (true, false) => self.dates_by_day(&days),
+            (false, false) => self
+                .dates_by_day(&days)
+                .into_iter()
+                .filter(|date| by_month_day.contains(date))
+                .collect(),
+        }
+    }
+
+    fn matches_month_day(&self, date: NaiveDate) -> bool {
+        let last_day = days_in_month(date.year(), date.month()) as i32;
+        self.bymonthday.is_empty()
+            || self
+                .bymonthday
+                .iter()
+                .any(|day| *day == date.day() as i32 || last_day + 1 + *day == date.day() as i32)
+    }
+
+    // BYMONTH, BYMONTHDAY and BYDAY limiting the occurrences of a frequency smaller than them
+    fn matches_limits(&self, date: NaiveDate) -> bool {
+        (self.bymonth.is_empty() || self.bymonth.contains(&date.month()))
+            && self.matches_month_day(date)
+            && (self.byday.is_empty()
+                || self
+                    .byday
+                    .iter()
+                    .any(|(_, weekday)| *weekday == date.weekday()))
+    }
+
+    // Occurrences of the period number `period` counted from the one of DTSTART
+    fn expand_period(
+        &self,
+        dtstart: DateTime<FixedOffset>,
+        period: i64,
+    ) -> Vec<DateTime<FixedOffset>> {
+        let step = period * i64::from(self.interval.unwrap_or(1));
+        let start_date = dtstart.date_naive();
+
+        let dates: Vec<NaiveDate> = match self.freq {
+            Frequency::Secondly | Frequency::Minutely | Frequency::Hourly => {
+                let unit = match self.freq {
+                    Frequency::Secondly => 1,
+                    Frequency::Minutely => 60,
+                    _ => 60 * 60,
+                };
+                let occurrence = dtstart + Duration::seconds(step * unit);
+                if self.matches_limits(occurrence.date_naive()) {
+                    return vec![occurrence];
+                }
+                return Vec::new();
+            }
+            Frequency::Daily => {
+                let date = start_date + Duration::days(step);
+                if self.matches_limits(date) {
+                    vec![date]
+                } else {
+                    Vec::new()
+                }
+            }
+            Frequency::Weekly => {
+                let wkst = self.wkst.unwrap_or(Weekday::Mon);
+                let week_start = start_date
+                    - Duration::days(
+                        (7 + start_date.weekday().num_days_from_monday() as i64
+                            - wkst.num_days_from_monday() as i64)
+                            % 7,
+                    )
+                    + Duration::weeks(step);
+                let week: Vec<NaiveDate> =
+                    (0..7).map(|day| week_start + Duration::days(day)).collect();
+                week.into_iter()
+                    .filter(|date| {
+                        if self.byday.is_empty() {
+                            date.weekday() == start_date.weekday()
+                        } else {
+                            self.byday
+                                .iter()
+                                .any(|(_, weekday)| *weekday == date.weekday())
+                        }
+                    })
+                    .filter(|date| self.bymonth.is_empty() || self.bymonth.contains(&date.month()))
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let months = i64::from(start_date.month0()) + step;
+                let year = start_date.year() + months.div_euclid(12) as i32;
+                let month = months.rem_euclid(12) as u32 + 1;
+                if !self.bymonth.is_empty() && !self.bymonth.contains(&month) {
+                    Vec::new()
+                } else {
+                    self.dates_in_month(year, month, start_date)
+                }
+            }
+            Frequency::Yearly => {
+                let year = start_date.year() + step as i32;
+                if self.bymonth.is_empty() && self.bymonthday.is_empty() && !self.byday.is_empty() {
+                    // The positions of BYDAY are counted in the whole year
+                    let days: Vec<NaiveDate> = (1..=366)
+                        .filter_map(|day| NaiveDate::from_yo_opt(year, day))
+                        .collect();
+                    self.dates_by_day(&days)
+                } else {
+                    let months: Vec<u32> = if !self.bymonth.is_empty() {
+                        self.bymonth.clone()
+                    } else if !self.bymonthday.is_empty() {
+                        (1..=12).collect()
+                    } else {
+                        vec![start_date.month()]
+                    };
+                    months
+                        .into_iter()
+                        .flat_map(|month| self.dates_in_month(year, month, start_date))
+                        .collect()
+                }
+            }
+        };
+
+        dates
+            .into_iter()
+            .filter_map(|date| {
+                dtstart
+                    .offset()
+                    .from_local_datetime(&date.and_time(dtstart.time()))
+                    .single()
+            })
+            .collect()
+    }
+}
+
+/// Iterator over the occurrences of a recurrence rule, in chronological order.
+pub struct Occurrences<'a> {
+    recur: &'a Recur,
+    dtstart: DateTime<FixedOffset>,
+    // Number of the next period to expand
+    period: i64,
+    empty_periods: usize,
+    pending: VecDeque<DateTime<FixedOffset>>,
+    emitted: u32,
+    done: bool,
+}
+
+// Rules that cannot match, such as the 30th of february, end after this many empty periods
+const MAX_EMPTY_PERIODS: usize = 10_000;
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime<FixedOffset>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if let Some(occurrence) = self.pending.pop_front() {
+                let after_until = self.recur.until.is_some_and(|until| occurrence > until);
+                let count_reached = self.recur.count.is_some_and(|count| self.emitted >= count);
+                if after_until || count_reached {
+                    self.done = true;
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(occurrence);
+            }
+
+            let mut occurrences = self.recur.expand_period(self.dtstart, self.period);
+            self.period += 1;
+            // DTSTART is already the first occurrence
+            occurrences.retain(|occurrence| *occurrence > self.dtstart);
+            occurrences.sort();
+            occurrences.dedup();
+
+            if occurrences.is_empty() {
+                self.empty_periods += 1;
+                self.done = self.empty_periods > MAX_EMPTY_PERIODS;
+            } else {
+                self.empty_periods = 0;
+            }
+            self.pending.extend(occurrences);
+        }
+        None
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|date| date.pred_opt())
+        .map_or(31, |date| date.day())
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, ICSError> {
+    match value {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(ICSError::PropertyConditionNotRespected("RRULE".to_string())),
+    }
+}
+
+fn format_weekday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+impl FromStr for Recur {
+    type Err = ICSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ICSError::PropertyConditionNotRespected("RRULE".to_string());
+        // Every number of a rule part must be within its range
+        let parse_number =
+            |value: &str, range: std::ops::RangeInclusive<i64>| -> Result<i64, ICSError> {
+                match value.parse::<i64>() {
+                    Ok(number) if range.contains(&number) => Ok(number),
+                    _ => Err(error()),
+                }
+            };
+
+        let mut freq = None;
+        let mut recur = Recur::new(Frequency::Daily);
+        let mut seen: Vec<&str> = Vec::new();
+
+        for part in s.split(';') {
+            let (name, value) = part.split_once('=').ok_or_else(error)?;
+            if seen.contains(&name) {
+                return Err(error());
+            }
+            seen.push(name);
+
+            match name {
+                "FREQ" => freq = Some(Frequency::from_str(value)?),
+                "INTERVAL" => {
+                    recur.interval = Some(parse_number(value, 1..=u32::MAX.into())? as u32)
+                }
+                "COUNT" => recur.count = Some(parse_number(value, 1..=u32::MAX.into())? as u32),
+                "UNTIL" => {
+                    // A date is the start of that day, a local date-time is kept as is
+                    let mut date_time = value.trim_end_matches('Z').to_string();
+                    if date_time.len() == 8 {
+                        date_time.push_str("T000000");
+                    }
+                    date_time.push_str("+0000");
+                    recur.until = Some(
+                        DateTime::parse_from_str(&date_time, "%Y%m%dT%H%M%S%z")
+                            .map_err(|_| error())?,
+                    );
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let split = day.len().saturating_sub(2);
+                        if !day.is_char_boundary(split) {
+                            return Err(error());
+                        }
+                        let (position, weekday) = day.split_at(split);
+                        let position = match position {
+                            "" => None,
+                            _ => {
+                                Some(parse_number(position.trim_start_matches('+'), -53..=53)?
+                                    as i32)
+                            }
+                        };
+                        if position == Some(0) {
+                            return Err(error());
+                        }
+                        recur.byday.push((position, parse_weekday(weekday)?));
+                    }
+                }
+                "BYMONTHDAY" => {
+                    for day in value.split(',') {
+                        let day = parse_number(day.trim_start_matches('+'), -31..=31)?;
+                        if day == 0 {
+                            return Err(error());
+                        }
+                        recur.bymonthday.push(day as i32);
+                    }
+                }
+                "BYMONTH" => {
+                    for month in value.split(',') {
+                        recur.bymonth.push(parse_number(month, 1..=12)? as u32);
+                    }
+                }
+                "WKST" => recur.wkst = Some(parse_weekday(value)?),
+                _ => recur
+                    .other_parts
+                    .push((name.to_string(), value.to_string())),
+            }
+        }
+
+        // COUNT and UNTIL both give the end of the recurrence
+        if recur.count.is_some() && recur.until.is_some() {
+            return Err(error());
+        }
+        recur.freq = freq.ok_or_else(error)?;
+        Ok(recur)
+    }
+}
+
+impl fmt::Display for Recur {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", String::from(self.freq))?;
+        if let Some(interval) = self.interval {
+            write!(f, ";INTERVAL={interval}")?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(
+                f,
+                ";UNTIL={}",
+                until.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ")
+            )?;
+        }
+        if !self.byday.is_empty() {
+            let days: Vec<String> = self
+                .byday
+                .iter()
+                .map(|(position, weekday)| match position {
+                    Some(position) => format!("{position}{}", format_weekday(*weekday)),
+                    None => format_weekday(*weekday).to_string(),
+                })
+                .collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.bymonthday.is_empty() {
+            let days: Vec<String> = self.bymonthday.iter().map(i32::to_string).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if !self.bymonth.is_empty() {
+            let months: Vec<String> = self.bymonth.iter().map(u32::to_string).collect();
+            write!(f, ";BYMONTH={}", months.join(","))?;
+        }
+        if let Some(wkst) = self.wkst {
+            write!(f, ";WKST={}", format_weekday(wkst))?;
+        }
+        for (name, value) in &self.other_parts {
+            write!(f, ";{name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn recur_from_str() {
+    let recur = Recur::from_str("FREQ=MONTHLY;BYDAY=-1FR,+2MO,TU;BYMONTH=1,6;INTERVAL=2").unwrap();
+    assert_eq!(recur.freq, Frequency::Monthly);
+    assert_eq!(recur.interval, Some(2));
+    assert_eq!(
+        recur.byday,
+        vec![
+            (Some(-1), Weekday::Fri),
+            (Some(2), Weekday::Mon),
+            (None, Weekday::Tue)
+        ]
+    );
+    assert_eq!(recur.bymonth, vec![1, 6]);
+    assert_eq!(
+        recur.to_string(),
+        "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,2MO,TU;BYMONTH=1,6"
+    );
+
+    let recur = Recur::from_str("FREQ=DAILY;UNTIL=19971224T000000Z;WKST=SU;BYSETPOS=1").unwrap();
+    assert_eq!(
+        recur.until,
+        Some(
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(1997, 12, 24, 0, 0, 0)
+                .unwrap()
+        )
+    );
+    assert_eq!(recur.wkst, Some(Weekday::Sun));
+    assert_eq!(
+        recur.other_parts,
+        vec![("BYSETPOS".to_string(), "1".to_string())]
+    );
+
+    for value in [
+        "FREQ=FORTNIGHTLY",
+        "INTERVAL=2",
+        "FREQ=DAILY;FREQ=WEEKLY",
+        "FREQ=DAILY;COUNT=2;UNTIL=19971224",
+        "FREQ=DAILY;COUNT=0",
+        "FREQ=MONTHLY;BYMONTHDAY=32",
+        "FREQ=YEARLY;BYMONTH=13",
+        "FREQ=WEEKLY;BYDAY=XX",
+        "FREQ=WEEKLY;BYDAY=0MO",
+        "FREQ=WEEKLY;BYDAY=MÖ",
+    ] {
+        assert_eq!(
+            Recur::from_str(value).unwrap_err(),
+            ICSError::PropertyConditionNotRespected("RRULE".to_string()),
+            "{value}"
+        );
+    }
+}
+
+#[test]
+fn recur_occurrences() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let date =
+        |year: i32, month: u32, day: u32| utc.with_ymd_and_hms(year, month, day, 9, 0, 0).unwrap();
+    let occurrences = |rule: &str, dtstart: DateTime<FixedOffset>, limit: usize| {
+        Recur::from_str(rule)
+            .unwrap()
+            .occurrences(dtstart)
+            .take(limit)
+            .collect::<Vec<_>>()
+    };
+
+    // Monday 1997-09-01
+    assert_eq!(
+        occurrences("FREQ=WEEKLY;BYDAY=MO,WE", date(1997, 9, 1), 5),
+        vec![
+            date(1997, 9, 1),
+            date(1997, 9, 3),
+            date(1997, 9, 8),
+            date(1997, 9, 10),
+            date(1997, 9, 15)
+        ]
+    );
+    assert_eq!(
+        occurrences("FREQ=DAILY;INTERVAL=2;COUNT=3", date(1997, 9, 2), 10),
+        vec![date(1997, 9, 2), date(1997, 9, 4), date(1997, 9, 6)]
+    );
+    assert_eq!(
+        occurrences("FREQ=DAILY;UNTIL=19970905T090000Z", date(1997, 9, 2), 10).len(),
+        4
+    );
+    // Last friday of the month
+    assert_eq!(
+        occurrences("FREQ=MONTHLY;BYDAY=-1FR", date(1997, 9, 26), 3),
+        vec![date(1997, 9, 26), date(1997, 10, 31), date(1997, 11, 28)]
+    );
+    // The 31st only exists in some months
+    assert_eq!(
+        occurrences("FREQ=MONTHLY;BYMONTHDAY=31", date(1997, 1, 31), 3),
+        vec![date(1997, 1, 31), date(1997, 3, 31), date(1997, 5, 31)]
+    );
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYMONTH=1,7;COUNT=3", date(1997, 1, 10), 10),
+        vec![date(1997, 1, 10), date(1997, 7, 10), date(1998, 1, 10)]
+    );
+    // A rule that never matches ends
+    assert_eq!(
+        occurrences("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", date(1997, 1, 10), 10),
+        vec![date(1997, 1, 10)]
+    );
+}
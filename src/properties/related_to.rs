@@ -0,0 +1,136 @@
+/*
+The property is defined by the following notation:
+
+  related    = "RELATED-TO" relparam ":" text CRLF
+
+  relparam   = *(
+             ;
+             ; The following parameter is OPTIONAL,
+             ; but MUST NOT occur more than once.
+             ;
+             (";" reltypeparam) /
+             ;
+             ; The following is OPTIONAL,
+             ; and MAY occur more than once.
+             ;
+             (";" other-param)
+             ;
+             )
+
+  reltypeparam       = "RELTYPE" "="
+                      ("PARENT"    ; Parent relationship - Default
+                     / "CHILD"     ; Child relationship
+                     / "SIBLING"   ; Sibling relationship
+                     / iana-token  ; Some other IANA-registered
+                                   ; iCalendar relationship type
+                     / x-name)     ; A non-standard, experimental
+                                   ; relationship type
+*/
+
+use super::{uri::Uri, Parameters};
+use std::str::FromStr;
+
+/// The type of hierarchical relationship given by the RELTYPE parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RelType {
+    #[default]
+    Parent,
+    Child,
+    Sibling,
+    Other(String),
+}
+
+impl From<&str> for RelType {
+    fn from(value: &str) -> Self {
+        match value.to_ascii_uppercase().as_str() {
+            "PARENT" => RelType::Parent,
+            "CHILD" => RelType::Child,
+            "SIBLING" => RelType::Sibling,
+            _ => RelType::Other(value.to_string()),
+        }
+    }
+}
+
+impl From<RelType> for String {
+    fn from(reltype: RelType) -> Self {
+        match reltype {
+            RelType::Parent => "PARENT".to_string(),
+            RelType::Child => "CHILD".to_string(),
+            RelType::Sibling => "SIBLING".to_string(),
+            RelType::Other(string) => string,
+        }
+    }
+}
+
+/// A relationship to another calendar component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedTo {
+    // Usually the UID of the other component, but some files give a URI instead
+    pub value: String,
+    pub reltype: RelType,
+}
+
+impl RelatedTo {
+    /// Builds the relationship from the value of the property and its parameters.
+    pub fn new(value: String, parameters: &Parameters) -> RelatedTo {
+        RelatedTo {
+            value,
+            reltype: parameters
+                .get("RELTYPE")
+                .map(RelType::from)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns true if the value looks like a URI rather than a plain UID.
+    pub fn is_uri(&self) -> bool {
+        Uri::from_str(&self.value).is_ok()
+    }
+
+    /// Returns the parameters to write with the property, the default RELTYPE is omitted.
+    pub fn parameters(&self) -> Parameters {
+        let mut parameters = Parameters::new();
+        if self.reltype != RelType::Parent {
+            parameters.insert("RELTYPE".to_string(), String::from(self.reltype.clone()));
+        }
+        parameters
+    }
+}
+
+#[test]
+fn reltype_from_str() {
+    assert_eq!(RelType::from("PARENT"), RelType::Parent);
+    assert_eq!(RelType::from("child"), RelType::Child);
+    assert_eq!(RelType::from("SIBLING"), RelType::Sibling);
+    assert_eq!(
+        RelType::from("X-DEPENDS-ON"),
+        RelType::Other("X-DEPENDS-ON".to_string())
+    );
+}
+
+#[test]
+fn related_to_parameters() {
+    let mut parameters = Parameters::new();
+    let related_to = RelatedTo::new("parent@example.com".to_string(), &parameters);
+    assert_eq!(related_to.reltype, RelType::Parent);
+    assert!(related_to.parameters().is_empty());
+
+    parameters.insert("RELTYPE".to_string(), "CHILD".to_string());
+    let related_to = RelatedTo::new("child@example.com".to_string(), &parameters);
+    assert_eq!(related_to.reltype, RelType::Child);
+    assert_eq!(related_to.parameters().get("RELTYPE"), Some("CHILD"));
+}
+
+#[test]
+fn related_to_uri() {
+    let parameters = Parameters::new();
+    let related_to = RelatedTo::new("http://host/item/1".to_string(), &parameters);
+    assert_eq!(related_to.value, "http://host/item/1");
+    assert!(related_to.is_uri());
+
+    let related_to = RelatedTo::new(
+        "jsmith.part7.19960817T083000.xyzMail@example.com".to_string(),
+        &parameters,
+    );
+    assert!(!related_to.is_uri());
+}
@@ -29,7 +29,7 @@ use crate::ics_error::ICSError;
 #[cfg(test)]
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Status {
     NeedsAction,
     Completed,
@@ -74,9 +74,9 @@ impl std::str::FromStr for Status {
             "DRAFT" => Ok(Status::Draft),
             "FINAL" => Ok(Status::Final),
             "CANCELLED" => Ok(Status::Cancelled),
-            _ => Err(ICSError::PropertyConditionNotRespected(
-                "STATUS".to_string(),
-            )),
+            _ => Err(ICSError::PropertyConditionNotRespected(format!(
+                "STATUS: {s}"
+            ))),
         }
     }
 }
@@ -152,6 +152,12 @@ fn from_str() {
     assert_eq!(Status::from_str("DRAFT").unwrap(), Status::Draft);
     assert_eq!(Status::from_str("FINAL").unwrap(), Status::Final);
     assert_eq!(Status::from_str("CANCELLED").unwrap(), Status::Cancelled);
+
+    // The error tells which value was refused
+    assert_eq!(
+        Status::from_str("BOGUS").unwrap_err(),
+        ICSError::PropertyConditionNotRespected("STATUS: BOGUS".to_string())
+    );
 }
 
 #[test]
@@ -0,0 +1,64 @@
+/*
+The property is defined by the following notation:
+
+  transp     = "TRANSP" transparam ":" transvalue CRLF
+
+  transparam = *(";" other-param)
+
+  transvalue = "OPAQUE"
+              ;Blocks or opaque on busy time searches.
+              / "TRANSPARENT"
+              ;Transparent on busy time searches.
+  ;Default value is OPAQUE
+*/
+
+use crate::ics_error::ICSError;
+#[cfg(test)]
+use std::str::FromStr;
+
+/// This property defines whether an event takes up time in busy time searches.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Transp {
+    #[default]
+    Opaque,
+    Transparent,
+}
+
+impl std::str::FromStr for Transp {
+    type Err = ICSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "OPAQUE" => Ok(Transp::Opaque),
+            "TRANSPARENT" => Ok(Transp::Transparent),
+            _ => Err(ICSError::PropertyConditionNotRespected(
+                "TRANSP".to_string(),
+            )),
+        }
+    }
+}
+
+impl From<Transp> for String {
+    fn from(transp: Transp) -> Self {
+        match transp {
+            Transp::Opaque => "OPAQUE".to_string(),
+            Transp::Transparent => "TRANSPARENT".to_string(),
+        }
+    }
+}
+
+#[test]
+fn from_str() {
+    assert_eq!(Transp::from_str("OPAQUE").unwrap(), Transp::Opaque);
+    assert_eq!(
+        Transp::from_str("TRANSPARENT").unwrap(),
+        Transp::Transparent
+    );
+    assert!(Transp::from_str("BUSY").is_err());
+}
+
+#[test]
+fn to_str() {
+    assert_eq!(String::from(Transp::Opaque), "OPAQUE");
+    assert_eq!(String::from(Transp::Transparent), "TRANSPARENT");
+}
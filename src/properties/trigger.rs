@@ -0,0 +1,158 @@
+/*
+The property is defined by the following notation:
+
+  trigger    = "TRIGGER" (trigrel / trigabs) CRLF
+
+  trigrel    = *(
+             ;
+             ; The following are OPTIONAL,
+             ; but MUST NOT occur more than once.
+             ;
+             (";" "VALUE" "=" "DURATION") /
+             (";" trigrelparam) /
+             ;
+             ; The following is OPTIONAL,
+             ; and MAY occur more than once.
+             ;
+             (";" other-param)
+             ;
+             ) ":"  dur-value
+
+  trigabs    = *(
+             ;
+             ; The following is REQUIRED,
+             ; but MUST NOT occur more than once.
+             ;
+             (";" "VALUE" "=" "DATE-TIME") /
+             ;
+             ; The following is OPTIONAL,
+             ; and MAY occur more than once.
+             ;
+             (";" other-param)
+             ;
+             ) ":" date-time
+
+  trigrelparam       = "RELATED" "=" ("START" / "END")
+*/
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+
+use super::{parse_duration, Parameters};
+use crate::ics_error::ICSError;
+
+#[cfg(test)]
+use chrono::TimeZone;
+
+/// The end of the component a relative trigger is counted from, given by the RELATED parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Related {
+    #[default]
+    Start,
+    End,
+}
+
+/// The time at which an alarm goes off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trigger {
+    Relative(Duration, Related),
+    // Always in UTC
+    Absolute(DateTime<FixedOffset>),
+}
+
+impl Trigger {
+    /// Builds the trigger from the value of the property and its parameters.
+    pub fn new(value: &str, parameters: &Parameters) -> Result<Trigger, ICSError> {
+        let error = || ICSError::PropertyConditionNotRespected("TRIGGER".to_string());
+
+        match parameters.get("VALUE") {
+            Some("DATE-TIME") => {
+                if parameters.get("RELATED").is_some() || !value.ends_with('Z') {
+                    return Err(error());
+                }
+                match DateTime::parse_from_str(&format!("{value}+0000"), "%Y%m%dT%H%M%SZ%z") {
+                    Ok(date_time) => Ok(Trigger::Absolute(date_time)),
+                    Err(_) => Err(error()),
+                }
+            }
+            Some("DURATION") | None => {
+                let related = match parameters.get("RELATED") {
+                    Some("START") | None => Related::Start,
+                    Some("END") => Related::End,
+                    Some(_) => return Err(error()),
+                };
+                match parse_duration(value) {
+                    Ok(duration) => Ok(Trigger::Relative(duration, related)),
+                    Err(_) => Err(error()),
+                }
+            }
+            Some(_) => Err(error()),
+        }
+    }
+
+    /// Returns the parameters to write with the property, the default RELATED is omitted.
+    pub fn parameters(&self) -> Parameters {
+        let mut parameters = Parameters::new();
+        match self {
+            Trigger::Relative(_, Related::End) => {
+                parameters.insert("RELATED".to_string(), "END".to_string())
+            }
+            Trigger::Relative(_, Related::Start) => {}
+            Trigger::Absolute(_) => parameters.insert("VALUE".to_string(), "DATE-TIME".to_string()),
+        }
+        parameters
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trigger::Relative(duration, _) => {
+                write!(f, "{}", crate::utils::format_duration(duration))
+            }
+            Trigger::Absolute(date_time) => write!(
+                f,
+                "{}",
+                date_time.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ")
+            ),
+        }
+    }
+}
+
+#[test]
+fn trigger_new() {
+    let mut parameters = Parameters::new();
+    assert_eq!(
+        Trigger::new("-PT15M", &parameters).unwrap(),
+        Trigger::Relative(Duration::minutes(-15), Related::Start)
+    );
+
+    parameters.insert("RELATED".to_string(), "END".to_string());
+    let trigger = Trigger::new("PT5M", &parameters).unwrap();
+    assert_eq!(
+        trigger,
+        Trigger::Relative(Duration::minutes(5), Related::End)
+    );
+    assert_eq!(trigger.parameters().get("RELATED"), Some("END"));
+    assert_eq!(trigger.to_string(), "PT5M");
+
+    let mut parameters = Parameters::new();
+    parameters.insert("VALUE".to_string(), "DATE-TIME".to_string());
+    let trigger = Trigger::new("19980101T050000Z", &parameters).unwrap();
+    assert_eq!(
+        trigger,
+        Trigger::Absolute(
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(1998, 1, 1, 5, 0, 0)
+                .unwrap()
+        )
+    );
+    assert_eq!(trigger.parameters().get("VALUE"), Some("DATE-TIME"));
+    assert_eq!(trigger.to_string(), "19980101T050000Z");
+
+    // An absolute trigger must be in UTC
+    assert!(Trigger::new("19980101T050000", &parameters).is_err());
+    assert!(Trigger::new("-PT15M", &parameters).is_err());
+}
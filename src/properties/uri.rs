@@ -1,2 +1,126 @@
-#[derive(Debug)]
-pub struct Uri {}
+/*
+The value type is defined by the following notation:
+
+  uri = scheme ":" hier-part [ "?" query ] [ "#" fragment ]
+
+  scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+
+The usual forms in calendars are:
+
+  http://example.com/calendar.ics
+  https://example.com/calendar.ics
+  ftp://example.com/pub/sounds/bell-01.aud
+  mailto:jane_doe@example.com
+  CID:jsmith.part3.960817T083000.xyzMail@example.com
+*/
+
+use crate::ics_error::ICSError;
+use std::fmt;
+#[cfg(test)]
+use std::str::FromStr;
+
+/// A reference to a resource, such as the sound file of an AUDIO alarm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uri {
+    // Kept in the case it was read, it is compared case insensitively
+    scheme: String,
+    // Everything after the ':'
+    value: String,
+}
+
+impl Uri {
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl std::str::FromStr for Uri {
+    type Err = ICSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ICSError::UnableToParseProperty(s.to_string());
+
+        let (scheme, value) = s.split_once(':').ok_or_else(error)?;
+        if !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+            || value.is_empty()
+            || value.contains(char::is_whitespace)
+        {
+            return Err(error());
+        }
+
+        // The known schemes have some more requirements
+        let valid = match scheme.to_ascii_lowercase().as_str() {
+            "http" | "https" | "ftp" => match value.strip_prefix("//") {
+                Some(rest) => !rest.is_empty() && !rest.starts_with('/'),
+                None => false,
+            },
+            "mailto" => value.contains('@'),
+            _ => true,
+        };
+        if !valid {
+            return Err(error());
+        }
+
+        Ok(Uri {
+            scheme: scheme.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.scheme, self.value)
+    }
+}
+
+impl From<Uri> for String {
+    fn from(uri: Uri) -> Self {
+        uri.to_string()
+    }
+}
+
+#[test]
+fn from_str() {
+    let uri = Uri::from_str("ftp://example.com/pub/sounds/bell-01.aud").unwrap();
+    assert_eq!(uri.scheme(), "ftp");
+    assert_eq!(uri.value(), "//example.com/pub/sounds/bell-01.aud");
+    assert_eq!(uri.to_string(), "ftp://example.com/pub/sounds/bell-01.aud");
+
+    for value in [
+        "http://example.com/calendar.ics",
+        "https://example.com/calendar.ics?user=1#today",
+        "mailto:john_doe@example.com",
+        "MAILTO:john_doe@example.com",
+        "CID:jsmith.part3.960817T083000.xyzMail@example.com",
+        "urn:uuid:f81d4fae-7dec-11d0-a765-00a0c91e6bf6",
+    ] {
+        assert_eq!(Uri::from_str(value).unwrap().to_string(), value);
+    }
+
+    for value in [
+        // No scheme
+        "example.com/pub/sounds/bell-01.aud",
+        "1ftp://example.com",
+        "",
+        // No value
+        "CID:",
+        // Malformed known schemes
+        "http:example.com",
+        "https:///calendar.ics",
+        "mailto:john_doe",
+        "http://example.com/my calendar.ics",
+    ] {
+        assert_eq!(
+            Uri::from_str(value).unwrap_err(),
+            ICSError::UnableToParseProperty(value.to_string())
+        );
+    }
+}
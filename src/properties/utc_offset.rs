@@ -0,0 +1,98 @@
+/*
+The value type is defined by the following notation:
+
+  utc-offset = time-numzone
+
+  time-numzone = ("+" / "-") time-hour time-minute [time-second]
+
+The "+" sign MUST be specified for positive offsets, and the value "-0000" is not allowed.
+*/
+
+use chrono::FixedOffset;
+
+use crate::ics_error::ICSError;
+
+/// Parses a UTC offset such as +0100 or -0500, as used by TZOFFSETFROM and TZOFFSETTO.
+pub fn parse_utc_offset(value: &str) -> Result<FixedOffset, ICSError> {
+    let error = || ICSError::PropertyConditionNotRespected("UTC-OFFSET".to_string());
+
+    let (factor, digits) = if let Some(digits) = value.strip_prefix('+') {
+        (1, digits)
+    } else if let Some(digits) = value.strip_prefix('-') {
+        (-1, digits)
+    } else {
+        return Err(error());
+    };
+    if !(digits.len() == 4 || digits.len() == 6) || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error());
+    }
+    // The string only holds ascii digits, it can be split anywhere
+    let hours: i32 = digits[0..2].parse().map_err(|_| error())?;
+    let minutes: i32 = digits[2..4].parse().map_err(|_| error())?;
+    let seconds: i32 = match digits.get(4..6) {
+        Some(seconds) => seconds.parse().map_err(|_| error())?,
+        None => 0,
+    };
+    if hours > 23 || minutes > 59 || seconds > 59 {
+        return Err(error());
+    }
+
+    let offset = hours * 3600 + minutes * 60 + seconds;
+    if factor == -1 && offset == 0 {
+        return Err(error());
+    }
+    FixedOffset::east_opt(factor * offset).ok_or_else(error)
+}
+
+/// Formats a UTC offset the way it is read, the seconds are only given when there are some.
+pub fn format_utc_offset(offset: &FixedOffset) -> String {
+    let seconds = offset.local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let seconds = seconds.abs();
+
+    let mut output = format!("{sign}{:02}{:02}", seconds / 3600, seconds / 60 % 60);
+    if seconds % 60 != 0 {
+        output.push_str(&format!("{:02}", seconds % 60));
+    }
+    output
+}
+
+#[test]
+fn utc_offset_parsing() {
+    assert_eq!(
+        parse_utc_offset("+0100").unwrap(),
+        FixedOffset::east_opt(3600).unwrap()
+    );
+    assert_eq!(
+        parse_utc_offset("-0500").unwrap(),
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    );
+    assert_eq!(
+        parse_utc_offset("+053012").unwrap(),
+        FixedOffset::east_opt(5 * 3600 + 30 * 60 + 12).unwrap()
+    );
+    assert_eq!(
+        parse_utc_offset("+0000").unwrap(),
+        FixedOffset::east_opt(0).unwrap()
+    );
+
+    for value in [
+        "0100",
+        "-0000",
+        "+01",
+        "+01:00",
+        "+2400",
+        "+0160",
+        "",
+        "+０１００",
+    ] {
+        assert!(parse_utc_offset(value).is_err(), "{value}");
+    }
+}
+
+#[test]
+fn utc_offset_formatting() {
+    for value in ["+0100", "-0500", "+053012", "+0000", "-0930"] {
+        assert_eq!(format_utc_offset(&parse_utc_offset(value).unwrap()), value);
+    }
+}
@@ -0,0 +1,67 @@
+/*
+The property is defined by the following notation:
+
+  x-prop = x-name *(";" icalparameter) ":" value CRLF
+
+The value is not known, it is kept as text so that the property is written back as it was read.
+*/
+
+use super::{split_property, Parameters};
+use crate::{ics_error::ICSError, utils};
+
+/// A non-standard property, whose name starts with X-.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XProp {
+    pub name: String,
+    pub parameters: Parameters,
+    pub value: String,
+}
+
+impl XProp {
+    /// Returns true if the line is the one of a non-standard property.
+    pub fn is_x_prop(line: &str) -> bool {
+        line.get(..2)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("X-"))
+    }
+
+    /// Reads the property from its unfolded line.
+    pub fn parse(line: &str) -> Result<XProp, ICSError> {
+        let (name, parameters, value) = split_property(line)?;
+        if !XProp::is_x_prop(name) || !utils::is_iana_token(name) {
+            return Err(ICSError::UknownProperty(name.to_string()));
+        }
+        Ok(XProp {
+            name: name.to_string(),
+            parameters,
+            value: value.to_string(),
+        })
+    }
+
+    /// Writes the property line ending with a CRLF.
+    pub fn write(&self, output: &mut String) {
+        let mut line = self.name.clone();
+        for (param_name, param_value) in self.parameters.canonical() {
+            line.push_str(&format!(";{param_name}={param_value}"));
+        }
+        line.push(':');
+        line.push_str(&self.value);
+        utils::write_line(output, &line);
+    }
+}
+
+#[test]
+fn x_prop_parse() {
+    let x_prop = XProp::parse("X-WR-CALNAME;X-PARAM=1:Work: meetings").unwrap();
+    assert_eq!(x_prop.name, "X-WR-CALNAME");
+    assert_eq!(x_prop.parameters.get("X-PARAM"), Some("1"));
+    assert_eq!(x_prop.value, "Work: meetings");
+
+    let mut output = String::new();
+    x_prop.write(&mut output);
+    assert_eq!(output, "X-WR-CALNAME;X-PARAM=1:Work: meetings\r\n");
+
+    assert_eq!(
+        XProp::parse("SUMMARY:Meeting").unwrap_err(),
+        ICSError::UknownProperty("SUMMARY".to_string())
+    );
+}
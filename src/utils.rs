@@ -1,35 +1,40 @@
-use std::{
-    fs::File,
-    io::{BufReader, Lines},
-};
-
+use std::io::{self, BufRead, Lines, Read};
 #[cfg(test)]
-use std::io::BufRead;
+use std::{fs::File, io::BufReader};
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
 
-use crate::{ics_error::ICSError, properties::ParserResult};
+use crate::{
+    ics_error::{Diagnostic, ICSError},
+    parse_options::ParseOptions,
+    properties::{
+        attachment::Attachment, cal_adress::CalAdress, iana_prop::IanaProp, recur::Recur,
+        x_prop::XProp, Parameters, ParserResult, Property,
+    },
+};
+
+// Lines should not be longer than 75 octets, excluding the line break.
+const MAX_LINE_LENGTH: usize = 75;
 
 pub fn process_multi_line_property(
     current_line: String,
-    line_reader: &mut Lines<BufReader<File>>,
+    line_reader: &mut Lines<impl BufRead>,
 ) -> (String, Option<Result<String, std::io::Error>>) {
     let mut out_line = current_line;
+    // A CRLF read as a lone LF leaves the carriage return at the end of the line
+    if out_line.ends_with('\r') {
+        out_line.pop();
+    }
 
     let mut next_line = line_reader.next();
-    loop {
-        match next_line {
-            Some(ref mut result) => match result {
-                Ok(line) => {
-                    if line.starts_with(' ') {
-                        // Remove the first character
-                        line.remove(0);
-                        out_line.push_str(line);
-                    } else {
-                        break;
-                    }
-                }
-                Err(_) => break,
-            },
-            None => break,
+    while let Some(Ok(ref mut line)) = next_line {
+        // Both a space and a horizontal tab mark a folded line
+        if line.starts_with(' ') || line.starts_with('\t') {
+            // Remove the first character
+            line.remove(0);
+            out_line.push_str(line.strip_suffix('\r').unwrap_or(line));
+        } else {
+            break;
         }
         next_line = line_reader.next();
     }
@@ -45,12 +50,507 @@ pub fn apply_unique_property<T: std::convert::From<crate::properties::ParserResu
     match arg {
         Some(_) => Err(ICSError::DuplicateUniqueProperty(property_name)),
         None => {
-            *arg = Some(T::try_from(value).unwrap());
+            *arg = Some(T::from(value));
             Ok(())
         }
     }
 }
 
+/// Reader verifying the input respects the size limits of the options while it is read.
+/// The bytes are checked before being handed out so that a huge line cannot fill the memory.
+pub struct SizeLimitedReader<R: BufRead> {
+    inner: R,
+    max_line_len: usize,
+    max_lines: usize,
+    line_count: usize,
+    line_length: usize,
+    line_started: bool,
+    // Number of bytes at the start of the inner buffer that were already checked
+    checked: usize,
+}
+
+impl<R: BufRead> SizeLimitedReader<R> {
+    pub fn new(inner: R, options: &ParseOptions) -> SizeLimitedReader<R> {
+        SizeLimitedReader {
+            inner,
+            max_line_len: options.max_line_len,
+            max_lines: options.max_lines,
+            line_count: 0,
+            line_length: 0,
+            line_started: false,
+            checked: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Read for SizeLimitedReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let length = {
+            let buffer = self.fill_buf()?;
+            let length = buffer.len().min(out.len());
+            out[..length].copy_from_slice(&buffer[..length]);
+            length
+        };
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl<R: BufRead> BufRead for SizeLimitedReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let buffer = self.inner.fill_buf()?;
+
+        for byte in &buffer[self.checked..] {
+            if !self.line_started {
+                self.line_started = true;
+                self.line_count += 1;
+                if self.line_count > self.max_lines {
+                    return Err(io::Error::other(ICSError::InputTooLarge));
+                }
+            }
+
+            match byte {
+                b'\n' => {
+                    self.line_length = 0;
+                    self.line_started = false;
+                }
+                // The carriage return of a CRLF is part of the line break
+                b'\r' => {}
+                _ => {
+                    self.line_length += 1;
+                    if self.line_length > self.max_line_len {
+                        return Err(io::Error::other(ICSError::InputTooLarge));
+                    }
+                }
+            }
+        }
+        self.checked = buffer.len();
+
+        Ok(buffer)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.checked -= amt;
+    }
+}
+
+/// Refuses the inputs starting with a UTF-16 byte order mark, which cannot be read as lines of UTF-8.
+pub fn verify_encoding(start: &[u8]) -> Result<(), ICSError> {
+    let encoding = match start {
+        [0xFF, 0xFE, ..] => "UTF-16LE",
+        [0xFE, 0xFF, ..] => "UTF-16BE",
+        _ => return Ok(()),
+    };
+    Err(ICSError::UnsupportedEncoding(format!(
+        "{encoding} input, the file must be converted to UTF-8"
+    )))
+}
+
+/// Reads numbered unfolded lines, leaving out each line quoted by an error and trying again.
+/// Each line left out gives a diagnostic, the error is returned if it does not quote a line.
+pub fn read_dropping_lines<T>(
+    lines: &mut Vec<(usize, String)>,
+    diagnostics: &mut Vec<Diagnostic>,
+    read: impl Fn(&[u8]) -> Result<T, ICSError>,
+) -> Result<T, ICSError> {
+    loop {
+        let content: String = lines
+            .iter()
+            .map(|(_, line)| format!("{line}\r\n"))
+            .collect();
+        let error = match read(content.as_bytes()) {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        // The repeated property is the last one
+        let quoted = match &error {
+            ICSError::UnexpectedProperty(text)
+            | ICSError::DuplicateUniqueProperty(text)
+            | ICSError::PropertyConditionNotRespected(text) => {
+                lines.iter().rposition(|(_, line)| line == text)
+            }
+            _ => None,
+        };
+        match quoted {
+            Some(index) => {
+                let (line, _) = lines.remove(index);
+                diagnostics.push(Diagnostic { line, error });
+            }
+            None => return Err(error),
+        }
+    }
+}
+
+/// Returns the parameters that were given to the property, if any.
+pub fn find_parameters(
+    parameters: &[(Property, Parameters)],
+    property: Property,
+) -> Option<&Parameters> {
+    parameters
+        .iter()
+        .find(|(param_property, _)| *param_property == property)
+        .map(|(_, parameters)| parameters)
+}
+
+/// Returns the parameters of the n-th occurrence of a property that can be repeated.
+pub fn find_nth_parameters(
+    parameters: &[(Property, Parameters)],
+    property: Property,
+    n: usize,
+) -> Option<&Parameters> {
+    parameters
+        .iter()
+        .filter(|(param_property, _)| *param_property == property)
+        .nth(n)
+        .map(|(_, parameters)| parameters)
+}
+
+/// Writes a property line ending with a CRLF, folding it if it is too long.
+pub fn write_property(
+    output: &mut String,
+    property: Property,
+    parameters: Option<&Parameters>,
+    value: &str,
+) {
+    let mut line = property.get_identier().to_string();
+    if let Some(parameters) = parameters {
+        for (param_name, param_value) in parameters.canonical() {
+            line.push_str(&format!(";{param_name}={param_value}"));
+        }
+    }
+    line.push(':');
+    line.push_str(value);
+
+    write_line(output, &line);
+}
+
+/// Writes the ATTACH of a component, unless their original lines were kept.
+pub fn write_attachments(
+    output: &mut String,
+    attachments: &[Attachment],
+    parameters: &[(Property, Parameters)],
+    raw_properties: &[(Property, String)],
+) {
+    // The original lines have already been written
+    if has_raw_property(raw_properties, Property::Attach) {
+        return;
+    }
+    for (index, attachment) in attachments.iter().enumerate() {
+        let parameters = find_nth_parameters(parameters, Property::Attach, index);
+        write_property(
+            output,
+            Property::Attach,
+            Some(&attachment.parameters(parameters)),
+            &attachment.to_string(),
+        );
+    }
+}
+
+/// Writes the non-standard properties of a component, unless their original lines were kept.
+pub fn write_x_properties(
+    output: &mut String,
+    x_properties: &[XProp],
+    raw_properties: &[(Property, String)],
+) {
+    // The original lines have already been written
+    if has_raw_property(raw_properties, Property::XProp) {
+        return;
+    }
+    for x_property in x_properties {
+        x_property.write(output);
+    }
+}
+
+/// Writes the unknown registered properties of a component, unless their original lines were kept.
+pub fn write_iana_properties(
+    output: &mut String,
+    iana_properties: &[IanaProp],
+    raw_properties: &[(Property, String)],
+) {
+    // The original lines have already been written
+    if has_raw_property(raw_properties, Property::IanaProp) {
+        return;
+    }
+    for iana_property in iana_properties {
+        iana_property.write(output);
+    }
+}
+
+/// Writes an unfolded content line ending with a CRLF, folding it if it is too long.
+pub fn write_line(output: &mut String, line: &str) {
+    // Fold the line without splitting a multi byte character.
+    let mut line_length = 0;
+    for character in line.chars() {
+        if line_length + character.len_utf8() > MAX_LINE_LENGTH {
+            output.push_str("\r\n ");
+            line_length = 1;
+        }
+        output.push(character);
+        line_length += character.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// Reads the escaped characters of a TEXT value, an unknown escape is kept as is.
+pub fn unescape_text(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            output.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n' | 'N') => output.push('\n'),
+            Some(escaped @ ('\\' | ';' | ',')) => output.push(escaped),
+            Some(other) => {
+                output.push('\\');
+                output.push(other);
+            }
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+/// Splits a list of TEXT values on the commas that are not escaped, and unescapes each value.
+pub fn split_text_list(value: &str) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, character) in value.char_indices() {
+        match character {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(unescape_text(&value[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(unescape_text(&value[start..]));
+    values
+}
+
+/// Escapes the characters that cannot be written as is in a TEXT value.
+pub fn escape_text(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '\\' => output.push_str("\\\\"),
+            ';' => output.push_str("\\;"),
+            ',' => output.push_str("\\,"),
+            '\n' => output.push_str("\\n"),
+            // A CRLF is written as a single line break
+            '\r' => {}
+            _ => output.push(character),
+        }
+    }
+    output
+}
+
+/// Verifies a TEXT value, once unescaped, has no control character other than a tab
+/// or the line break given by `\n`.
+pub fn validate_text(property: Property, value: &str) -> Result<(), ICSError> {
+    if value
+        .chars()
+        .any(|char| char.is_control() && char != '\t' && char != '\n')
+    {
+        return Err(ICSError::PropertyConditionNotRespected(format!(
+            "{} contains a control character",
+            property.get_identier()
+        )));
+    }
+    Ok(())
+}
+
+/// Returns true if the value is an iana-token, made of letters, digits and dashes.
+/// An x-name is an iana-token starting with X-.
+pub fn is_iana_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '-')
+}
+
+/// Drops the parameters and the original lines kept for a property.
+pub fn forget_property(
+    parameters: &mut Vec<(Property, Parameters)>,
+    raw_properties: &mut Vec<(Property, String)>,
+    property: Property,
+) {
+    parameters.retain(|(param_property, _)| *param_property != property);
+    raw_properties.retain(|(raw_property, _)| *raw_property != property);
+}
+
+/// Returns true if an extension property names the organizer or an attendee,
+/// as X-MS-OLK-SENDER or X-MOZ-ORGANIZER do.
+fn is_attendee_x_property(name: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    ["ORGANIZER", "ATTENDEE", "SENDER"]
+        .iter()
+        .any(|role| name.ends_with(role))
+}
+
+/// Drops the ORGANIZER, the ATTENDEE and the extension properties naming them from a component.
+pub fn redact_attendees(
+    organizer: &mut Option<CalAdress>,
+    attendee: &mut Vec<CalAdress>,
+    x_properties: &mut Vec<XProp>,
+    parameters: &mut Vec<(Property, Parameters)>,
+    raw_properties: &mut Vec<(Property, String)>,
+) {
+    *organizer = None;
+    attendee.clear();
+    forget_property(parameters, raw_properties, Property::Organizer);
+    forget_property(parameters, raw_properties, Property::Attendee);
+
+    // The parameters of the extension properties are matched by their position
+    let redacted: Vec<bool> = x_properties
+        .iter()
+        .map(|x_property| is_attendee_x_property(&x_property.name))
+        .collect();
+    let mut redacted_iter = redacted.iter();
+    x_properties.retain(|_| !redacted_iter.next().unwrap_or(&false));
+    let mut redacted_iter = redacted.iter();
+    parameters.retain(|(property, _)| {
+        *property != Property::XProp || !redacted_iter.next().unwrap_or(&false)
+    });
+    raw_properties.retain(|(property, line)| {
+        *property != Property::XProp
+            || !is_attendee_x_property(line.split([';', ':']).next().unwrap_or_default())
+    });
+}
+
+/// Returns true if an occurrence of the rule that is not excluded by EXDATE overlaps the range,
+/// the expansion stops once the occurrences start after `to`.
+pub fn rule_overlaps(
+    rrule: Option<&Recur>,
+    dtstart: Option<DateTime<FixedOffset>>,
+    exdate: &[DateTime<FixedOffset>],
+    to: DateTime<FixedOffset>,
+    overlaps: impl Fn(DateTime<FixedOffset>) -> bool,
+) -> bool {
+    match (rrule, dtstart) {
+        (Some(rrule), Some(dtstart)) => rrule
+            .occurrences(dtstart)
+            .take_while(|occurrence| *occurrence <= to)
+            .any(|occurrence| !exdate.contains(&occurrence) && overlaps(occurrence)),
+        _ => false,
+    }
+}
+
+/// Swaps a GEO whose latitude is out of range while the longitude would fit,
+/// returning the warning telling the swap.
+pub fn swap_geo(geo: &mut Option<(f32, f32)>, uid: &str) -> Option<String> {
+    match *geo {
+        Some((lat, long)) if !(-90. ..=90.).contains(&lat) => {
+            *geo = Some((long, lat));
+            Some(format!(
+                "GEO of {uid} had its latitude and longitude swapped"
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Returns true if the original line of the property was kept.
+pub fn has_raw_property(raw_properties: &[(Property, String)], property: Property) -> bool {
+    raw_properties
+        .iter()
+        .any(|(raw_property, _)| *raw_property == property)
+}
+
+/// Returns true if the n-th occurrence of a time property was given as a DATE.
+pub fn is_date_value(parameters: &[(Property, Parameters)], property: Property, n: usize) -> bool {
+    find_nth_parameters(parameters, property, n)
+        .is_some_and(|parameters| parameters.get("VALUE") == Some("DATE"))
+}
+
+/// Verifies the EXDATE and RDATE are of the same value type as the DTSTART.
+pub fn validate_date_types(
+    parameters: &[(Property, Parameters)],
+    exdate_count: usize,
+    rdate_count: usize,
+) -> Result<(), ICSError> {
+    let date_start = is_date_value(parameters, Property::DTStart, 0);
+    for (property, count) in [
+        (Property::ExDate, exdate_count),
+        (Property::RDate, rdate_count),
+    ] {
+        if (0..count).any(|n| is_date_value(parameters, property, n) != date_start) {
+            return Err(ICSError::PropertyConditionNotRespected(
+                property.get_identier().to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Formats a time the same way it was read, depending on the VALUE and TZID parameters.
+pub fn format_date_time(value: &DateTime<FixedOffset>, parameters: Option<&Parameters>) -> String {
+    if let Some(parameters) = parameters {
+        if parameters.get("VALUE") == Some("DATE") {
+            return value.format("%Y%m%d").to_string();
+        }
+        // Local times are kept as they were read.
+        if parameters.get("TZID").is_some() || parameters.is_floating() {
+            return value.format("%Y%m%dT%H%M%S").to_string();
+        }
+    }
+    value
+        .with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Formats a duration in the ISO 8601 format used by the DURATION property.
+pub fn format_duration(value: &Duration) -> String {
+    let mut output = String::new();
+    if *value < Duration::zero() {
+        output.push('-');
+    }
+    output.push('P');
+
+    let mut seconds = value.num_seconds().abs();
+    if seconds == 0 {
+        output.push_str("T0S");
+        return output;
+    }
+
+    // A duration in weeks cannot be mixed with the other designators.
+    if seconds % (7 * 24 * 60 * 60) == 0 {
+        output.push_str(&format!("{}W", seconds / (7 * 24 * 60 * 60)));
+        return output;
+    }
+
+    let days = seconds / (24 * 60 * 60);
+    seconds %= 24 * 60 * 60;
+    if days != 0 {
+        output.push_str(&format!("{days}D"));
+    }
+
+    if seconds != 0 {
+        output.push('T');
+        let hours = seconds / (60 * 60);
+        let minutes = (seconds % (60 * 60)) / 60;
+        seconds %= 60;
+        if hours != 0 {
+            output.push_str(&format!("{hours}H"));
+        }
+        if minutes != 0 {
+            output.push_str(&format!("{minutes}M"));
+        }
+        if seconds != 0 {
+            output.push_str(&format!("{seconds}S"));
+        }
+    }
+
+    output
+}
+
 #[test]
 fn multi_line_test() {
     let f = File::open("./tests/test_files/Other/MultiLineTest.txt").unwrap();
@@ -67,3 +567,102 @@ fn multi_line_test() {
         "This is an example of a multi line string".to_string()
     );
 }
+
+#[test]
+fn multi_line_tab_folding() {
+    let f = File::open("./tests/test_files/Other/TabFoldedTest.txt").unwrap();
+    let mut lines = BufReader::new(f).lines();
+    let first_line = lines.next().unwrap().unwrap();
+
+    let (current_line, next_line) = process_multi_line_property(first_line, &mut lines);
+
+    // No carriage return is left in the value
+    assert_eq!(
+        current_line,
+        "DESCRIPTION:This is an example of a tab folded line".to_string()
+    );
+    assert_eq!(next_line.unwrap().unwrap(), "SUMMARY:Next property\r");
+}
+
+#[test]
+fn write_property_folding() {
+    let mut output = String::new();
+    write_property(
+        &mut output,
+        Property::Description,
+        None,
+        "This is a very long description that is going to be folded on several lines by the writer",
+    );
+
+    let lines: Vec<&str> = output.split_terminator("\r\n").collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].len(), 75);
+    assert!(lines[1].starts_with(' '));
+
+    // Unfolding gives back the original line
+    assert_eq!(
+        output.replace("\r\n ", ""),
+        "DESCRIPTION:This is a very long description that is going to be folded on several lines by the writer\r\n"
+    );
+}
+
+#[test]
+fn duration_formatting() {
+    assert_eq!(format_duration(&Duration::weeks(7)), "P7W");
+    assert_eq!(format_duration(&Duration::minutes(-15)), "-PT15M");
+    assert_eq!(
+        format_duration(&Duration::seconds(15 * 24 * 60 * 60 + 5 * 60 * 60 + 20)),
+        "P15DT5H20S"
+    );
+    assert_eq!(format_duration(&Duration::days(2)), "P2D");
+    assert_eq!(format_duration(&Duration::zero()), "PT0S");
+}
+
+#[test]
+fn text_escaping() {
+    assert_eq!(unescape_text("Line1\\nLine2"), "Line1\nLine2");
+    assert_eq!(unescape_text("Line1\\NLine2"), "Line1\nLine2");
+    assert_eq!(unescape_text("a\\, b\\; c\\\\d"), "a, b; c\\d");
+    // Unknown escapes are kept
+    assert_eq!(unescape_text("C:\\temp\\"), "C:\\temp\\");
+
+    assert_eq!(escape_text("Line1\r\nLine2"), "Line1\\nLine2");
+    assert_eq!(escape_text("a, b; c\\d"), "a\\, b\\; c\\\\d");
+
+    let text = "Agenda:\n1. Budget, planning; review\n2. C:\\share";
+    assert_eq!(unescape_text(&escape_text(text)), text);
+}
+
+#[test]
+fn input_size_verification() {
+    let options = ParseOptions {
+        max_line_len: 20,
+        max_lines: 3,
+        ..Default::default()
+    };
+    let read_all = |input: &str| -> Result<Vec<String>, ICSError> {
+        let reader = SizeLimitedReader::new(input.as_bytes(), &options);
+        let mut lines = Vec::new();
+        for line in reader.lines() {
+            lines.push(line?);
+        }
+        Ok(lines)
+    };
+
+    assert_eq!(
+        read_all("BEGIN:VCALENDAR\r\nVERSION:2.0\r\nEND:VCALENDAR\r\n")
+            .unwrap()
+            .len(),
+        3
+    );
+
+    assert_eq!(
+        read_all("BEGIN:VCALENDAR\nVERSION:2.0\nVERSION:2.0\nEND:VCALENDAR").unwrap_err(),
+        ICSError::InputTooLarge
+    );
+
+    assert_eq!(
+        read_all("BEGIN:VCALENDAR\nPRODID:This is a very long product\n").unwrap_err(),
+        ICSError::InputTooLarge
+    );
+}
@@ -75,24 +75,28 @@ alarmc     = "BEGIN" ":" "VALARM" CRLF
  */
 
 use crate::ics_error::ICSError;
+use crate::parse_options::ParseOptions;
 use crate::properties::action::Action;
-use crate::properties::uri::Uri;
-use crate::properties::Property;
+use crate::properties::attachment::Attachment;
+use crate::properties::cal_adress::CalAdress;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::trigger::{Related, Trigger};
+use crate::properties::x_prop::XProp;
+use crate::properties::{Parameters, Property};
 use crate::utils;
-use chrono::{DateTime, Duration, FixedOffset, Utc};
-use std::fs::File;
-use std::io::{BufReader, Lines};
+use chrono::{DateTime, Duration, FixedOffset};
+use std::io::{BufRead, Lines};
 
 #[cfg(test)]
-use chrono::TimeZone;
+use std::fs::File;
 #[cfg(test)]
-use std::io::BufRead;
+use std::io::BufReader;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VAlarm {
     // Necessary variables
     pub action: Action,
-    pub trigger: String, // Need to create it's own value
+    pub trigger: Trigger,
 
     // Sometimes necessary variable
     pub summary: Option<String>,
@@ -103,12 +107,25 @@ pub struct VAlarm {
     pub repeat: Option<usize>,
 
     // This has different possibilities depending on the type of Valarm
-    pub attach: Vec<Uri>,
-    // xprop, iana prop
+    pub attach: Vec<Attachment>,
+    pub attendee: Vec<CalAdress>,
+    // iana prop
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
 }
 
 impl VAlarm {
-    pub fn new_empty(action: Action, trigger: String) -> VAlarm {
+    pub fn new_empty(action: Action, trigger: Trigger) -> VAlarm {
         VAlarm {
             action,
             trigger,
@@ -117,14 +134,28 @@ impl VAlarm {
             duration: None,
             repeat: None,
             attach: Vec::new(),
+            attendee: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
         }
     }
 
-    /// Reads the content of a VTODO object. The buffer passed should already have consumed the BEGIN:VTODO.
-    pub fn parse_from_bufreader(
-        line_reader: &mut Lines<BufReader<File>>,
+    /// Reads the content of a VALARM object. The buffer passed should already have consumed the BEGIN:VALARM.
+    pub fn parse_from_bufreader(line_reader: &mut Lines<impl BufRead>) -> Result<VAlarm, ICSError> {
+        VAlarm::parse_from_bufreader_with_options(line_reader, &ParseOptions::default())
+    }
+
+    /// Reads the content of a VALARM object with the given options. The buffer passed should already have consumed the BEGIN:VALARM.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
     ) -> Result<VAlarm, ICSError> {
-        let mut vtodo: VAlarm = VAlarm::new_empty(Action::Display, "".to_string());
+        let mut valarm: VAlarm = VAlarm::new_empty(
+            Action::Display,
+            Trigger::Relative(Duration::zero(), Related::Start),
+        );
         let mut has_action = false;
         let mut has_trigger = false;
 
@@ -136,10 +167,7 @@ impl VAlarm {
             match line {
                 Some(line) => {
                     // Read line
-                    processed_line = match line {
-                        Ok(val) => val,
-                        Err(_) => return Err(ICSError::ReadError),
-                    };
+                    processed_line = line?;
                     // End the process if we have arrived at the end.
                     if processed_line.starts_with("END:VALARM") {
                         break;
@@ -147,6 +175,15 @@ impl VAlarm {
                 }
                 None => return Err(ICSError::BeginWithoutEnd),
             }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+            // No component can be nested in a VALARM
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                return Err(ICSError::UnexpectedComponent(component.to_string()));
+            }
 
             // Here we need to be able to process multi line arguments.
             let property_string: String;
@@ -155,17 +192,47 @@ impl VAlarm {
 
             // I clone the line here to avoid borrowing it as I might give it to an error.
             // This is probably slow but let's leave that problem for future smarter me.
-            let (property, value) = Property::parse_property(property_string.clone())?;
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+            // Kept even when empty so that repeated properties can be matched by position
+            valarm.parameters.push((property, parameters));
+            if options.preserve_raw {
+                valarm
+                    .raw_properties
+                    .push((property, property_string.clone()));
+            }
 
             match property {
-                Property::Duration => todo!(),
-                Property::Description => todo!(),
-                Property::Summary => todo!(),
-                Property::Action => todo!(),
-                Property::URL => todo!(),
-                Property::Attach => todo!(),
-                Property::Trigger => todo!(),
-                Property::Repeat => todo!(),
+                Property::Duration => {
+                    utils::apply_unique_property(&mut valarm.duration, value, property_string)?
+                }
+                Property::Description => {
+                    utils::apply_unique_property(&mut valarm.description, value, property_string)?
+                }
+                Property::Summary => {
+                    utils::apply_unique_property(&mut valarm.summary, value, property_string)?
+                }
+                Property::Action => {
+                    if has_action {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_action = true;
+                    valarm.action = value.into();
+                }
+                Property::Attach => valarm.attach.push(value.into()),
+                Property::Attendee => valarm.attendee.push(value.into()),
+                Property::Trigger => {
+                    if has_trigger {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_trigger = true;
+                    valarm.trigger = value.into();
+                }
+                Property::Repeat => {
+                    utils::apply_unique_property(&mut valarm.repeat, value, property_string)?
+                }
+                Property::XProp => valarm.x_properties.push(value.into()),
+                Property::IanaProp => valarm.iana_properties.push(value.into()),
                 _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
             }
         }
@@ -177,11 +244,162 @@ impl VAlarm {
             return Err(ICSError::MissingNecessaryProperty("TRIGGER".to_string()));
         }
 
-        Ok(vtodo)
+        valarm.validate()?;
+
+        Ok(valarm)
+    }
+
+    /// Lists the times the alarm goes off, in chronological order, for a component starting and
+    /// ending at the given times. A relative trigger whose end of the component is unknown never fires.
+    pub fn fire_times(
+        &self,
+        start: Option<DateTime<FixedOffset>>,
+        end: Option<DateTime<FixedOffset>>,
+    ) -> Vec<DateTime<FixedOffset>> {
+        let first = match &self.trigger {
+            Trigger::Absolute(time) => Some(*time),
+            Trigger::Relative(duration, Related::Start) => start.map(|start| start + *duration),
+            Trigger::Relative(duration, Related::End) => end.map(|end| end + *duration),
+        };
+        let first = match first {
+            Some(first) => first,
+            None => return Vec::new(),
+        };
+
+        // The repetitions come after the first time, one DURATION apart
+        let mut times = vec![first];
+        if let (Some(duration), Some(repeat)) = (self.duration, self.repeat) {
+            times.extend((1..=repeat as i32).map(|count| first + duration * count));
+        }
+        times
+    }
+
+    /// Verifies the properties required or refused by the ACTION of the alarm are respected.
+    pub fn validate(&self) -> Result<(), ICSError> {
+        // A repeated alarm needs both the number of repetitions and the delay between them
+        match (self.duration.is_some(), self.repeat.is_some()) {
+            (true, false) => return Err(ICSError::MissingNecessaryProperty("REPEAT".to_string())),
+            (false, true) => {
+                return Err(ICSError::MissingNecessaryProperty("DURATION".to_string()))
+            }
+            _ => {}
+        }
+
+        // An AUDIO alarm only plays its sound, it has no text and at most one sound file.
+        // Nothing is known of the IANA and X- actions, so nothing is required from them.
+        if self.action == Action::Audio {
+            if self.description.is_some() {
+                return Err(ICSError::UnexpectedProperty("DESCRIPTION".to_string()));
+            }
+            if self.attach.len() > 1 {
+                return Err(ICSError::DuplicateUniqueProperty("ATTACH".to_string()));
+            }
+        } else if matches!(self.action, Action::Display | Action::Email)
+            && self.description.is_none()
+        {
+            return Err(ICSError::MissingNecessaryProperty(
+                "DESCRIPTION".to_string(),
+            ));
+        }
+        // An EMAIL alarm is sent with a subject to at least one attendee
+        if self.action == Action::Email {
+            if self.summary.is_none() {
+                return Err(ICSError::MissingNecessaryProperty("SUMMARY".to_string()));
+            }
+            if self.attendee.is_empty() {
+                return Err(ICSError::MissingNecessaryProperty("ATTENDEE".to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the VALARM in the ics format, from BEGIN:VALARM to END:VALARM.
+    pub fn write(&self) -> String {
+        let mut output = String::from("BEGIN:VALARM\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_property(
+            &mut output,
+            Property::Action,
+            &String::from(self.action.clone()),
+        );
+        if !utils::has_raw_property(&self.raw_properties, Property::Trigger) {
+            // The parameters depend on the kind of trigger
+            utils::write_property(
+                &mut output,
+                Property::Trigger,
+                Some(&self.trigger.parameters()),
+                &self.trigger.to_string(),
+            );
+        }
+        if let Some(duration) = &self.duration {
+            self.write_property(
+                &mut output,
+                Property::Duration,
+                &utils::format_duration(duration),
+            );
+        }
+        if let Some(repeat) = &self.repeat {
+            self.write_property(&mut output, Property::Repeat, &repeat.to_string());
+        }
+        if let Some(description) = &self.description {
+            self.write_property(
+                &mut output,
+                Property::Description,
+                &utils::escape_text(description),
+            );
+        }
+        if let Some(summary) = &self.summary {
+            self.write_property(&mut output, Property::Summary, &utils::escape_text(summary));
+        }
+        utils::write_attachments(
+            &mut output,
+            &self.attach,
+            &self.parameters,
+            &self.raw_properties,
+        );
+        for attendee in &self.attendee {
+            self.write_cal_address(&mut output, Property::Attendee, attendee);
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        output.push_str("END:VALARM\r\n");
+        output
+    }
+
+    fn write_cal_address(&self, output: &mut String, property: Property, value: &CalAdress) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        // Each address has its own parameters
+        utils::write_property(
+            output,
+            property,
+            Some(&value.parameters()),
+            &value.address.to_string(),
+        );
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
     }
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn valarm_read_example_1() {
     let f = File::open("./tests/test_files/valarm/example1").unwrap();
@@ -191,10 +409,27 @@ fn valarm_read_example_1() {
     let mut lines = buf_reader.lines();
     println!("Removing first line : {}", lines.next().unwrap().unwrap());
 
-    //let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(valarm.action, Action::Email);
+    assert_eq!(
+        valarm.trigger,
+        Trigger::Relative(Duration::days(-2), Related::End)
+    );
+    assert_eq!(valarm.attendee.len(), 1);
+    assert_eq!(
+        valarm.attendee[0].address.to_string(),
+        "mailto:john_doe@example.com"
+    );
+    assert_eq!(
+        valarm.summary,
+        Some("*** REMINDER: SEND AGENDA FOR WEEKLY STAFF MEETING ***".to_string())
+    );
+    assert_eq!(
+        valarm.attach[0].to_string(),
+        "http://example.com/templates/agenda.doc"
+    );
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn valarm_read_example_2() {
     let f = File::open("./tests/test_files/valarm/example2").unwrap();
@@ -204,10 +439,17 @@ fn valarm_read_example_2() {
     let mut lines = buf_reader.lines();
     println!("Removing first line : {}", lines.next().unwrap().unwrap());
 
-    //let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(valarm.action, Action::Display);
+    assert_eq!(
+        valarm.trigger,
+        Trigger::Relative(Duration::minutes(-30), Related::Start)
+    );
+    assert_eq!(valarm.repeat, Some(2));
+    assert_eq!(valarm.duration, Some(Duration::minutes(15)));
+    assert!(valarm.description.is_some());
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn valarm_read_example_3() {
     let f = File::open("./tests/test_files/valarm/example3").unwrap();
@@ -217,10 +459,29 @@ fn valarm_read_example_3() {
     let mut lines = buf_reader.lines();
     println!("Removing first line : {}", lines.next().unwrap().unwrap());
 
-    //let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(valarm.action, Action::Display);
+    assert_eq!(valarm.trigger.to_string(), "20221117T150000Z");
+    assert!(valarm
+        .write()
+        .contains("TRIGGER;VALUE=DATE-TIME:20221117T150000Z\r\n"));
+    assert_eq!(valarm.description, Some("Group-Office Alert".to_string()));
+}
+
+#[test]
+fn valarm_read_x_action() {
+    let f = File::open("./tests/test_files/valarm/x_action").unwrap();
+    let buf_reader = BufReader::new(f);
+
+    // Consume the first VALARM line
+    let mut lines = buf_reader.lines();
+    lines.next();
+
+    let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(valarm.action, Action::XName("X-SMS".to_string()));
+    assert!(valarm.write().contains("ACTION:X-SMS\r\n"));
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
 fn valarm_read_example_4() {
     let f = File::open("./tests/test_files/valarm/example4").unwrap();
@@ -230,5 +491,119 @@ fn valarm_read_example_4() {
     let mut lines = buf_reader.lines();
     println!("Removing first line : {}", lines.next().unwrap().unwrap());
 
-    //let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    let valarm = VAlarm::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(valarm.action, Action::Audio);
+    assert_eq!(valarm.attach.len(), 1);
+    assert_eq!(
+        valarm.attach[0].to_string(),
+        "ftp://example.com/pub/sounds/bell-01.aud"
+    );
+    assert!(valarm.description.is_none());
+    assert_eq!(valarm.repeat, Some(4));
+}
+
+#[test]
+fn valarm_audio_with_description() {
+    let f = File::open("./tests/test_files/valarm/audio_with_description").unwrap();
+    let buf_reader = BufReader::new(f);
+
+    // Consume the first VTODO line
+    let mut lines = buf_reader.lines();
+    lines.next().unwrap().unwrap();
+
+    assert_eq!(
+        VAlarm::parse_from_bufreader(&mut lines).unwrap_err(),
+        ICSError::UnexpectedProperty("DESCRIPTION".to_string())
+    );
+}
+
+#[test]
+fn valarm_missing_properties() {
+    let parse = |content: &str| {
+        VAlarm::parse_from_bufreader(&mut std::io::Cursor::new(content.as_bytes()).lines())
+    };
+
+    assert_eq!(
+        parse("ACTION:DISPLAY\r\nTRIGGER:-PT30M\r\nEND:VALARM\r\n").unwrap_err(),
+        ICSError::MissingNecessaryProperty("DESCRIPTION".to_string())
+    );
+    assert_eq!(
+        parse(
+            "ACTION:EMAIL\r\nTRIGGER:-PT30M\r\nDESCRIPTION:Text\r\nSUMMARY:Text\r\nEND:VALARM\r\n"
+        )
+        .unwrap_err(),
+        ICSError::MissingNecessaryProperty("ATTENDEE".to_string())
+    );
+    assert_eq!(
+        parse("ACTION:AUDIO\r\nTRIGGER:-PT30M\r\nREPEAT:2\r\nEND:VALARM\r\n").unwrap_err(),
+        ICSError::MissingNecessaryProperty("DURATION".to_string())
+    );
+    assert_eq!(
+        parse("ACTION:AUDIO\r\nTRIGGER:-PT30M\r\nDURATION:PT15M\r\nEND:VALARM\r\n").unwrap_err(),
+        ICSError::MissingNecessaryProperty("REPEAT".to_string())
+    );
+    assert!(parse("ACTION:AUDIO\r\nTRIGGER:-PT30M\r\nEND:VALARM\r\n").is_ok());
+
+    assert_eq!(
+        parse("ACTION:AUDIO\r\nTRIGGER:-PT30M\r\nBEGIN:VALARM\r\nEND:VALARM\r\n").unwrap_err(),
+        ICSError::UnexpectedComponent("VALARM".to_string())
+    );
+}
+
+#[test]
+fn valarm_validate() {
+    let trigger = Trigger::Relative(Duration::minutes(-30), Related::Start);
+
+    // An EMAIL alarm is refused without its subject
+    let mut valarm = VAlarm::new_empty(Action::Email, trigger.clone());
+    valarm.description = Some("A draft agenda needs to be sent out".to_string());
+    valarm
+        .attendee
+        .push(CalAdress::new("mailto:john_doe@example.com", &Parameters::new(), true).unwrap());
+    assert_eq!(
+        valarm.validate().unwrap_err(),
+        ICSError::MissingNecessaryProperty("SUMMARY".to_string())
+    );
+    valarm.summary = Some("*** REMINDER: SEND AGENDA FOR WEEKLY STAFF MEETING ***".to_string());
+    assert!(valarm.validate().is_ok());
+
+    let mut valarm = VAlarm::new_empty(Action::Display, trigger);
+    valarm.description = Some("Breakfast meeting with executive team".to_string());
+    assert!(valarm.validate().is_ok());
+}
+
+#[test]
+fn fire_times() {
+    use chrono::TimeZone;
+
+    let start = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2024, 1, 1, 9, 0, 0)
+        .unwrap();
+    let mut valarm = VAlarm::new_empty(
+        Action::Audio,
+        Trigger::Relative(Duration::minutes(-30), Related::Start),
+    );
+    valarm.duration = Some(Duration::minutes(10));
+    valarm.repeat = Some(2);
+    assert_eq!(
+        valarm.fire_times(Some(start), None),
+        [
+            start - Duration::minutes(30),
+            start - Duration::minutes(20),
+            start - Duration::minutes(10)
+        ]
+    );
+
+    // An unsigned trigger goes off after the start
+    valarm.trigger = Trigger::Relative(Duration::minutes(15), Related::Start);
+    valarm.repeat = None;
+    assert_eq!(
+        valarm.fire_times(Some(start), None),
+        [start + Duration::minutes(15)]
+    );
+
+    // The end is needed for a trigger related to it
+    valarm.trigger = Trigger::Relative(Duration::zero(), Related::End);
+    assert!(valarm.fire_times(Some(start), None).is_empty());
 }
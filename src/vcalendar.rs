@@ -55,22 +55,41 @@ iCalendar object will consist of just a single "VEVENT", "VTODO" or
 "VJOURNAL" calendar component.
 */
 
+use chrono::{DateTime, Duration, FixedOffset};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::str::FromStr;
 
-use crate::ics_error::ICSError;
+use crate::component::{Component, ComponentKind};
+use crate::ics_error::{Diagnostic, ICSError};
+use crate::parse_options::ParseOptions;
 
+use crate::properties::action::Action;
+use crate::properties::cal_adress::CalAdress;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::related_to::RelType;
+use crate::properties::status::Status;
+use crate::properties::x_prop::XProp;
 use crate::properties::Property;
 use crate::utils;
+use crate::valarm::VAlarm;
 use crate::vevent::VEvent;
+use crate::vfreebusy::VFreeBusy;
 use crate::vjournal::VJournal;
+use crate::vtimezone::VTimezone;
 use crate::vtodo::VTodo;
 
 #[cfg(test)]
-use crate::properties::status::Status;
+use crate::properties::recur::Recur;
+#[cfg(test)]
+use crate::properties::related_to::RelatedTo;
+#[cfg(test)]
+use crate::properties::trigger::Related;
+use crate::properties::trigger::Trigger;
 #[cfg(test)]
-use chrono::{FixedOffset, TimeZone};
+use chrono::TimeZone;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -84,10 +103,24 @@ pub struct VCalendar {
     calscale: Option<String>,
     method: Option<String>,
 
-    // One of the components
-    vjournal: Option<VJournal>,
-    vtodo: Option<VTodo>,
-    vevent: Option<VEvent>,
+    // Non-standard calendar properties, such as X-WR-CALNAME
+    x_properties: Vec<XProp>,
+    // Registered calendar properties the crate does not know, only kept when the parse is not strict
+    iana_properties: Vec<IanaProp>,
+
+    // Time zones referenced by the components
+    vtimezones: Vec<VTimezone>,
+
+    // The components, at least one must be given
+    vjournals: Vec<VJournal>,
+    vtodos: Vec<VTodo>,
+    vevents: Vec<VEvent>,
+
+    // Busy time, published on its own or along with the other components
+    vfreebusy: Vec<VFreeBusy>,
+
+    // Problems found while reading that did not prevent the calendar from being read
+    warnings: Vec<String>,
 }
 
 impl VCalendar {
@@ -97,13 +130,25 @@ impl VCalendar {
             version: "2.0".to_string(),
             calscale: None,
             method: None,
-            vjournal: None,
-            vtodo: None,
-            vevent: None,
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            vtimezones: Vec::new(),
+            vjournals: Vec::new(),
+            vtodos: Vec::new(),
+            vevents: Vec::new(),
+            vfreebusy: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
     pub fn load_vcal_from_file(path: &Path) -> Result<VCalendar, ICSError> {
+        VCalendar::load_vcal_from_file_with_options(path, &ParseOptions::default())
+    }
+
+    pub fn load_vcal_from_file_with_options(
+        path: &Path,
+        options: &ParseOptions,
+    ) -> Result<VCalendar, ICSError> {
         match path.extension() {
             Some(ext_value) => {
                 if ext_value != "ics" {
@@ -113,20 +158,179 @@ impl VCalendar {
             None => return Err(ICSError::NotICSFile),
         }
 
+        let f = match File::open(path) {
+            Ok(f) => f,
+            Err(_) => return Err(ICSError::ReadError),
+        };
+        VCalendar::from_reader_with_options(BufReader::new(f), options)
+    }
+
+    /// Reads a calendar file and reports every problem found instead of stopping at the first one.
+    /// The calendar is None if it could not be read even without the lines and components in error.
+    pub fn load_with_diagnostics(path: &Path) -> (Option<VCalendar>, Vec<Diagnostic>) {
+        VCalendar::load_with_diagnostics_with_options(path, &ParseOptions::default())
+    }
+
+    /// Reads a calendar file with the given options and reports every problem found.
+    pub fn load_with_diagnostics_with_options(
+        path: &Path,
+        options: &ParseOptions,
+    ) -> (Option<VCalendar>, Vec<Diagnostic>) {
+        let whole_file = |error| (None, vec![Diagnostic { line: 0, error }]);
+        if path.extension().is_none_or(|extension| extension != "ics") {
+            return whole_file(ICSError::NotICSFile);
+        }
+        let content = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(error) => return whole_file(error.into()),
+        };
+        if let Err(error) = utils::verify_encoding(&content) {
+            return whole_file(error);
+        }
+        match String::from_utf8(content) {
+            Ok(content) => VCalendar::diagnose(&content, options),
+            Err(_) => whole_file(ICSError::ReadError),
+        }
+    }
+
+    // Leaves out each property line that cannot be read, then each component that is still invalid,
+    // and reads the calendar from what is left. Every line or component left out gives a diagnostic.
+    fn diagnose(content: &str, options: &ParseOptions) -> (Option<VCalendar>, Vec<Diagnostic>) {
+        // Unfold the lines, keeping the number of the line each one starts on
+        let mut lines: Vec<(usize, String)> = Vec::new();
+        for (index, line) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+            match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+                (Some(continuation), Some((_, previous))) => previous.push_str(continuation),
+                _ => lines.push((index + 1, line.to_string())),
+            }
+        }
+
+        let mut diagnostics = Vec::new();
+        lines.retain(|(number, line)| {
+            if line.trim().is_empty() || line.starts_with("BEGIN") || line.starts_with("END") {
+                return true;
+            }
+            match Property::parse_property_with_options(line.clone(), options) {
+                Ok(_) => true,
+                Err(error) => {
+                    diagnostics.push(Diagnostic {
+                        line: *number,
+                        error,
+                    });
+                    false
+                }
+            }
+        });
+
+        // The components of the calendar are read on their own, so that one can be left out
+        let mut kept = Vec::new();
+        let mut in_calendar = false;
+        let mut index = 0;
+        while index < lines.len() {
+            let (number, line) = &lines[index];
+            if !in_calendar || !line.starts_with("BEGIN:") {
+                in_calendar |= line.starts_with("BEGIN:VCALENDAR");
+                kept.push(lines[index].clone());
+                index += 1;
+                continue;
+            }
+
+            // The component ends with the END line matching its BEGIN
+            let mut depth = 0;
+            let end = lines[index..]
+                .iter()
+                .position(|(_, line)| {
+                    if line.starts_with("BEGIN") {
+                        depth += 1;
+                    } else if line.starts_with("END") {
+                        depth -= 1;
+                    }
+                    depth == 0
+                })
+                .map_or(lines.len() - 1, |length| index + length);
+            let mut component = lines[index + 1..=end].to_vec();
+            let name = &line["BEGIN:".len()..];
+            let result = utils::read_dropping_lines(&mut component, &mut diagnostics, |content| {
+                let lines = &mut content.lines();
+                match name {
+                    "VTODO" => VTodo::parse_from_bufreader_with_options(lines, options).map(|_| ()),
+                    "VEVENT" => {
+                        VEvent::parse_from_bufreader_with_options(lines, options).map(|_| ())
+                    }
+                    "VJOURNAL" => {
+                        VJournal::parse_from_bufreader_with_options(lines, options).map(|_| ())
+                    }
+                    "VFREEBUSY" => {
+                        VFreeBusy::parse_from_bufreader_with_options(lines, options).map(|_| ())
+                    }
+                    "VTIMEZONE" => {
+                        VTimezone::parse_from_bufreader_with_options(lines, options).map(|_| ())
+                    }
+                    _ => Err(ICSError::UnknownComponent(name.to_string())),
+                }
+            });
+            match result {
+                Ok(()) => {
+                    kept.push(lines[index].clone());
+                    kept.append(&mut component);
+                }
+                Err(error) => diagnostics.push(Diagnostic {
+                    line: *number,
+                    error,
+                }),
+            }
+            index = end + 1;
+        }
+
+        let first_line = kept.first().map_or(0, |(number, _)| *number);
+        let vcal_object = utils::read_dropping_lines(&mut kept, &mut diagnostics, |content| {
+            VCalendar::from_reader_with_options(content, options)
+        });
+        let vcal_object = match vcal_object {
+            Ok(vcal_object) => Some(vcal_object),
+            Err(error) => {
+                diagnostics.push(Diagnostic {
+                    line: first_line,
+                    error,
+                });
+                None
+            }
+        };
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        (vcal_object, diagnostics)
+    }
+
+    /// Reads a calendar from any buffered source, such as the body of an HTTP response.
+    /// Same as `VCalendar::from_reader`, named after `load_vcal_from_file`.
+    pub fn load_vcal_from_reader<R: BufRead>(reader: R) -> Result<VCalendar, ICSError> {
+        VCalendar::from_reader(reader)
+    }
+
+    /// Reads a calendar from any buffered source, such as a file, a network stream or a string.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<VCalendar, ICSError> {
+        VCalendar::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Reads a calendar from any buffered source with the given options.
+    pub fn from_reader_with_options<R: BufRead>(
+        reader: R,
+        options: &ParseOptions,
+    ) -> Result<VCalendar, ICSError> {
         let mut has_prod_id = false;
         let mut has_version = false;
+        let mut missing_end = false;
+
+        // A UTF-16 file would only be read as garbage, so it is refused before anything else
+        let mut reader = reader;
+        utils::verify_encoding(reader.fill_buf()?)?;
 
-        let f = File::open(path).unwrap();
-        let buf_reader = BufReader::new(f);
-        let mut line_reader = buf_reader.lines();
+        // The size of the input is verified while it is read
+        let mut line_reader = utils::SizeLimitedReader::new(reader, options).lines();
         let mut vcal_object = VCalendar::new_empty();
 
         // Find first BEGIN:VCALENDAR
         match line_reader.next() {
-            Some(result) => match result {
-                Ok(line) => line,
-                Err(_) => return Err(ICSError::ReadError),
-            },
+            Some(result) => result?,
             None => return Err(ICSError::NoBegin),
         };
 
@@ -138,14 +342,24 @@ impl VCalendar {
             match line {
                 Some(line) => {
                     // Read line
-                    processed_line = line.unwrap();
+                    processed_line = line?;
                     // End the process if we have arrived at the end.
                     if processed_line.starts_with("END:VCALENDAR") {
                         break;
                     }
                 }
+                // A truncated file is still read when its components are complete
+                None if !options.strict => {
+                    missing_end = true;
+                    break;
+                }
                 None => return Err(ICSError::BeginWithoutEnd),
             }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
             // Here we need to be able to process multi line arguments.
 
             if processed_line.starts_with("BEGIN") {
@@ -155,24 +369,43 @@ impl VCalendar {
                 };
 
                 match begin_val {
-                    "VTODO" => {
-                        if vcal_object.vtodo.is_some() {
-                            return Err(ICSError::DuplicateUniqueProperty(begin_val.to_string()));
-                        }
-                        vcal_object.vtodo = Some(VTodo::parse_from_bufreader(&mut line_reader)?);
-                    }
+                    "VTODO" => vcal_object
+                        .vtodos
+                        .push(VTodo::parse_from_bufreader_with_options(
+                            &mut line_reader,
+                            options,
+                        )?),
                     "VEVENT" => {
-                        if vcal_object.vevent.is_some() {
-                            return Err(ICSError::DuplicateUniqueProperty(begin_val.to_string()));
-                        }
-                        vcal_object.vevent = Some(VEvent::parse_from_bufreader(&mut line_reader)?);
+                        vcal_object
+                            .vevents
+                            .push(VEvent::parse_from_bufreader_with_options(
+                                &mut line_reader,
+                                options,
+                            )?)
                     }
                     "VJOURNAL" => {
-                        if vcal_object.vjournal.is_some() {
-                            return Err(ICSError::DuplicateUniqueProperty(begin_val.to_string()));
-                        }
-                        vcal_object.vjournal =
-                            Some(VJournal::parse_from_bufreader(&mut line_reader)?);
+                        vcal_object
+                            .vjournals
+                            .push(VJournal::parse_from_bufreader_with_options(
+                                &mut line_reader,
+                                options,
+                            )?)
+                    }
+                    "VFREEBUSY" => {
+                        vcal_object
+                            .vfreebusy
+                            .push(VFreeBusy::parse_from_bufreader_with_options(
+                                &mut line_reader,
+                                options,
+                            )?);
+                    }
+                    "VTIMEZONE" => {
+                        vcal_object
+                            .vtimezones
+                            .push(VTimezone::parse_from_bufreader_with_options(
+                                &mut line_reader,
+                                options,
+                            )?);
                     }
                     _ => return Err(ICSError::UnknownComponent(begin_val.to_string())),
                 }
@@ -188,21 +421,22 @@ impl VCalendar {
 
             // I clone the line here to avoid borrowing it as I might give it to an error.
             // This is probably slow but let's leave that problem for future smarter me.
-            let (property, value) = Property::parse_property(property_string.clone())?;
+            let (property, _, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
             match property {
                 Property::ProdID => {
                     if has_prod_id {
                         return Err(ICSError::DuplicateUniqueProperty(property_string));
                     }
                     has_prod_id = true;
-                    vcal_object.prodid = value.try_into().unwrap();
+                    vcal_object.prodid = value.into();
                 }
                 Property::Version => {
                     if has_version {
                         return Err(ICSError::DuplicateUniqueProperty(property_string));
                     }
                     has_version = true;
-                    vcal_object.version = value.try_into().unwrap();
+                    vcal_object.version = value.into();
                 }
                 Property::CalScale => {
                     utils::apply_unique_property(&mut vcal_object.calscale, value, property_string)?
@@ -210,40 +444,605 @@ impl VCalendar {
                 Property::Method => {
                     utils::apply_unique_property(&mut vcal_object.method, value, property_string)?
                 }
+                Property::XProp => vcal_object.x_properties.push(value.into()),
+                Property::IanaProp => vcal_object.iana_properties.push(value.into()),
                 _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
             }
         }
 
-        // Verify duplicate property
-        match (
-            &vcal_object.vevent,
-            &vcal_object.vjournal,
-            &vcal_object.vtodo,
-        ) {
-            (None, None, Some(_)) => {}
-            (None, Some(_), None) => {}
-            (Some(_), None, None) => {}
-            (None, None, None) => {
-                return Err(ICSError::MissingNecessaryProperty(
-                    "VTODO, VCALENDAR, VJOURNAL".to_string(),
-                ))
-            }
-            (_, _, _) => {
-                return Err(ICSError::DuplicateUniqueProperty(
-                    "VTODO, VCALENDAR, VJOURNAL".to_string(),
-                ))
-            }
+        // At least one component is needed, a calendar may only publish busy time
+        if vcal_object.components().is_empty() {
+            return Err(ICSError::MissingNecessaryProperty(
+                "VEVENT, VTODO, VJOURNAL, VFREEBUSY".to_string(),
+            ));
         }
 
         if !has_prod_id {
             return Err(ICSError::MissingNecessaryProperty("PRODID".to_string()));
         }
-        if !has_version {
+        if !has_version && options.strict {
             return Err(ICSError::MissingNecessaryProperty("VERSION".to_string()));
         }
 
+        // The VTIMEZONE can come after the components that use them
+        vcal_object.resolve_timezones()?;
+        vcal_object.validate_uids()?;
+        vcal_object.warnings = vcal_object.cancellation_warnings();
+        if !has_version {
+            vcal_object.warnings.push(format!(
+                "VERSION is missing, {} is assumed",
+                vcal_object.version
+            ));
+        }
+        if missing_end {
+            vcal_object
+                .warnings
+                .push("END:VCALENDAR is missing".to_string());
+        }
+        let component_warnings: Vec<String> = vcal_object
+            .vtodos
+            .iter()
+            .flat_map(|vtodo| vtodo.warnings.iter())
+            .chain(
+                vcal_object
+                    .vevents
+                    .iter()
+                    .flat_map(|vevent| vevent.warnings.iter()),
+            )
+            .cloned()
+            .collect();
+        vcal_object.warnings.extend(component_warnings);
+
         Ok(vcal_object)
     }
+
+    // Places the times given with a TZID in their zone, they are read as local times at +00:00
+    fn resolve_timezones(&mut self) -> Result<(), ICSError> {
+        for vtodo in &mut self.vtodos {
+            vtodo.resolve_timezones(&self.vtimezones)?;
+        }
+        for vevent in &mut self.vevents {
+            vevent.resolve_timezones(&self.vtimezones)?;
+        }
+        for vjournal in &mut self.vjournals {
+            vjournal.resolve_timezones(&self.vtimezones)?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if the calendar cancels its components, with METHOD:CANCEL.
+    pub fn is_cancellation(&self) -> bool {
+        self.method
+            .as_ref()
+            .is_some_and(|method| method.eq_ignore_ascii_case("CANCEL"))
+    }
+
+    /// Returns the soonest time after `after` at which an alarm of the calendar goes off, with that alarm.
+    /// The repetitions of the alarms and every occurrence of the recurring components are considered,
+    /// an occurrence overridden by a RECURRENCE-ID uses the alarms of its override.
+    pub fn next_alarm_after(
+        &self,
+        after: DateTime<FixedOffset>,
+    ) -> Option<(DateTime<FixedOffset>, &VAlarm)> {
+        let vtodos = self.vtodos.iter().map(|vtodo| {
+            (
+                &vtodo.uid,
+                vtodo.recurrence_id,
+                vtodo.dtstart,
+                vtodo.effective_due(),
+                (&vtodo.rrule, &vtodo.rdate, &vtodo.exdate),
+                &vtodo.alarms,
+            )
+        });
+        let vevents = self.vevents.iter().map(|vevent| {
+            (
+                &vevent.uid,
+                vevent.recurrence_id,
+                vevent.dtstart,
+                vevent.effective_end(),
+                (&vevent.rrule, &vevent.rdate, &vevent.exdate),
+                &vevent.alarms,
+            )
+        });
+        let components: Vec<_> = vtodos.chain(vevents).collect();
+
+        let mut next: Option<(DateTime<FixedOffset>, &VAlarm)> = None;
+        for (uid, recurrence_id, dtstart, end, (rrule, rdate, exdate), alarms) in &components {
+            // A VTODO with only a DUE recurs from it
+            let anchor = match dtstart.or(*end) {
+                Some(anchor) => anchor,
+                None => continue,
+            };
+            let length = end.map(|end| end - anchor);
+            let overridden = |occurrence: &DateTime<FixedOffset>| {
+                recurrence_id.is_none()
+                    && components
+                        .iter()
+                        .any(|other| other.0 == *uid && other.1.as_ref() == Some(occurrence))
+            };
+            let skipped = |occurrence: &DateTime<FixedOffset>| {
+                exdate.contains(occurrence) || overridden(occurrence)
+            };
+
+            for alarm in alarms.iter() {
+                let fire_times = |occurrence: DateTime<FixedOffset>| {
+                    alarm.fire_times(
+                        dtstart.map(|_| occurrence),
+                        length.map(|length| occurrence + length),
+                    )
+                };
+                let consider = |next: &mut Option<_>, times: Vec<DateTime<FixedOffset>>| {
+                    if let Some(time) = times.into_iter().find(|time| *time > after) {
+                        if next.is_none_or(|(next_time, _)| time < next_time) {
+                            *next = Some((time, alarm));
+                        }
+                    }
+                };
+
+                // An absolute trigger does not depend on the occurrence
+                if let Trigger::Absolute(_) = alarm.trigger {
+                    consider(&mut next, fire_times(anchor));
+                    continue;
+                }
+
+                for occurrence in rdate.iter().filter(|occurrence| !skipped(occurrence)) {
+                    consider(&mut next, fire_times(*occurrence));
+                }
+                let occurrences: Box<dyn Iterator<Item = DateTime<FixedOffset>>> = match rrule {
+                    Some(rrule) => Box::new(rrule.occurrences(anchor)),
+                    None => Box::new(std::iter::once(anchor)),
+                };
+                for occurrence in occurrences.filter(|occurrence| !skipped(occurrence)) {
+                    let times = fire_times(occurrence);
+                    // The later occurrences cannot go off sooner
+                    match (times.first(), next) {
+                        (None, _) => break,
+                        (Some(first), Some((next_time, _))) if *first >= next_time => break,
+                        _ => consider(&mut next, times),
+                    }
+                }
+            }
+        }
+        next
+    }
+
+    /// Returns the PRODID of the calendar, naming the product that created it.
+    pub fn prodid(&self) -> &str {
+        &self.prodid
+    }
+
+    /// Returns the VERSION of iCalendar the calendar follows.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Returns the CALSCALE of the calendar, GREGORIAN is assumed when it has none.
+    pub fn calscale(&self) -> Option<&str> {
+        self.calscale.as_deref()
+    }
+
+    /// Returns the METHOD of the calendar, only given when it is part of a scheduling exchange.
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    /// Returns the non-standard properties of the calendar, such as X-WR-CALNAME.
+    pub fn x_properties(&self) -> &[XProp] {
+        &self.x_properties
+    }
+
+    /// Returns the registered calendar properties the crate does not know, such as COLOR.
+    pub fn iana_properties(&self) -> &[IanaProp] {
+        &self.iana_properties
+    }
+
+    /// Returns the zone the calendar should be displayed in, given by X-WR-TIMEZONE.
+    pub fn default_timezone(&self) -> Option<&str> {
+        self.x_properties
+            .iter()
+            .find(|x_property| x_property.name.eq_ignore_ascii_case("X-WR-TIMEZONE"))
+            .map(|x_property| x_property.value.as_str())
+    }
+
+    /// Returns the problems found while reading the calendar that did not prevent it from being read.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Lists the components of a cancellation that are not STATUS:CANCELLED.
+    fn cancellation_warnings(&self) -> Vec<String> {
+        if !self.is_cancellation() {
+            return Vec::new();
+        }
+        self.components()
+            .iter()
+            .filter(|component| component.kind() != ComponentKind::FreeBusy)
+            .filter(|component| component.status() != Some(&Status::Cancelled))
+            .map(|component| {
+                format!(
+                    "METHOD:CANCEL but {} is not STATUS:CANCELLED",
+                    component.uid().unwrap_or_default()
+                )
+            })
+            .collect()
+    }
+
+    /// Verifies that no two components of the same type share a UID, unless one overrides
+    /// an occurrence with a RECURRENCE-ID. Components of different types may share a UID.
+    pub fn validate_uids(&self) -> Result<(), ICSError> {
+        let components = self.components();
+        for (index, component) in components.iter().enumerate() {
+            let uid = match component.uid() {
+                Some(uid) => uid,
+                None => continue,
+            };
+            let duplicate = components[index + 1..].iter().any(|other| {
+                other.kind() == component.kind()
+                    && other.uid() == Some(uid)
+                    && !component.has_recurrence_id()
+                    && !other.has_recurrence_id()
+            });
+            if duplicate {
+                return Err(ICSError::DuplicateUniqueProperty(format!("UID:{uid}")));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the VEVENT components of the calendar.
+    pub fn events(&self) -> &[VEvent] {
+        &self.vevents
+    }
+
+    /// Returns the VTODO components of the calendar.
+    pub fn todos(&self) -> &[VTodo] {
+        &self.vtodos
+    }
+
+    /// Returns the VJOURNAL components of the calendar.
+    pub fn journals(&self) -> &[VJournal] {
+        &self.vjournals
+    }
+
+    /// Returns the VFREEBUSY components of the calendar.
+    pub fn freebusy(&self) -> &[VFreeBusy] {
+        &self.vfreebusy
+    }
+
+    /// Returns the time zones defined by the calendar.
+    pub fn timezones(&self) -> &[VTimezone] {
+        &self.vtimezones
+    }
+
+    /// Returns the time zone defined by the calendar with the given TZID.
+    pub fn timezone(&self, tzid: &str) -> Option<&VTimezone> {
+        self.vtimezones
+            .iter()
+            .find(|vtimezone| vtimezone.tzid == tzid)
+    }
+
+    /// Returns all the components of the calendar.
+    pub fn components(&self) -> Vec<Component<'_>> {
+        let mut components: Vec<Component<'_>> = Vec::new();
+        components.extend(self.vevents.iter().map(Component::Event));
+        components.extend(self.vtodos.iter().map(Component::Todo));
+        components.extend(self.vjournals.iter().map(Component::Journal));
+        components.extend(self.vfreebusy.iter().map(Component::FreeBusy));
+        components
+    }
+
+    /// Returns the UID of every component, a UID is repeated by the overrides of a recurrence.
+    pub fn uids(&self) -> Vec<&str> {
+        self.components()
+            .into_iter()
+            .filter_map(|component| component.uid())
+            .collect()
+    }
+
+    /// Returns the type shared by all the components of the calendar.
+    /// Returns None if the calendar is empty or mixes several types.
+    pub fn primary_kind(&self) -> Option<ComponentKind> {
+        let mut kinds = self
+            .components()
+            .into_iter()
+            .map(|component| component.kind());
+        let first = kinds.next()?;
+        if kinds.all(|kind| kind == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+
+    /// Writes the calendar in the ics format, from BEGIN:VCALENDAR to END:VCALENDAR.
+    /// The required PRODID and VERSION come first, then CALSCALE and METHOD,
+    /// then the time zones so that the TZID they define are known before the other components.
+    pub fn write(&self) -> String {
+        self.write_components(&self.components())
+    }
+
+    /// Returns the calendar as jCal, the JSON form of iCalendar given by RFC 7265.
+    #[cfg(feature = "jcal")]
+    pub fn to_jcal(&self) -> serde_json::Value {
+        crate::jcal::vcalendar(self)
+    }
+
+    /// Writes the calendar properties around the given components instead of its own.
+    pub(crate) fn write_components(&self, components: &[Component<'_>]) -> String {
+        let mut output = String::from("BEGIN:VCALENDAR\r\n");
+
+        utils::write_property(&mut output, Property::ProdID, None, &self.prodid);
+        utils::write_property(&mut output, Property::Version, None, &self.version);
+        if let Some(calscale) = &self.calscale {
+            utils::write_property(&mut output, Property::CalScale, None, calscale);
+        }
+        if let Some(method) = &self.method {
+            utils::write_property(&mut output, Property::Method, None, method);
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &[]);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &[]);
+
+        for vtimezone in &self.vtimezones {
+            output.push_str(&vtimezone.write());
+        }
+
+        for component in components {
+            match component {
+                Component::Event(vevent) => output.push_str(&vevent.write()),
+                Component::Todo(vtodo) => output.push_str(&vtodo.write()),
+                Component::Journal(vjournal) => output.push_str(&vjournal.write()),
+                Component::FreeBusy(vfreebusy) => output.push_str(&vfreebusy.write()),
+            }
+        }
+
+        output.push_str("END:VCALENDAR\r\n");
+        output
+    }
+
+    /// Removes the components whose time falls entirely outside of [from, to],
+    /// along with the occurrences outside of it. Components without time are kept.
+    pub fn clip_to_range(&mut self, from: DateTime<FixedOffset>, to: DateTime<FixedOffset>) {
+        self.vtodos
+            .retain_mut(|vtodo| vtodo.clip_to_range(from, to));
+        self.vevents
+            .retain_mut(|vevent| vevent.clip_to_range(from, to));
+        self.vjournals
+            .retain_mut(|vjournal| vjournal.clip_to_range(from, to));
+    }
+
+    /// Removes the ATTENDEE and ORGANIZER of every component, along with the extension
+    /// properties naming them, to publish a busy-only view.
+    /// EMAIL alarms are dropped as they cannot exist without an attendee.
+    pub fn redact_attendees(&mut self) {
+        for vtodo in &mut self.vtodos {
+            utils::redact_attendees(
+                &mut vtodo.organizer,
+                &mut vtodo.attendee,
+                &mut vtodo.x_properties,
+                &mut vtodo.parameters,
+                &mut vtodo.raw_properties,
+            );
+            vtodo.alarms.retain(|alarm| alarm.action != Action::Email);
+        }
+        for vevent in &mut self.vevents {
+            utils::redact_attendees(
+                &mut vevent.organizer,
+                &mut vevent.attendee,
+                &mut vevent.x_properties,
+                &mut vevent.parameters,
+                &mut vevent.raw_properties,
+            );
+            vevent.alarms.retain(|alarm| alarm.action != Action::Email);
+        }
+        for vjournal in &mut self.vjournals {
+            utils::redact_attendees(
+                &mut vjournal.organizer,
+                &mut vjournal.attendee,
+                &mut vjournal.x_properties,
+                &mut vjournal.parameters,
+                &mut vjournal.raw_properties,
+            );
+        }
+        for vfreebusy in &mut self.vfreebusy {
+            utils::redact_attendees(
+                &mut vfreebusy.organizer,
+                &mut vfreebusy.attendee,
+                &mut vfreebusy.x_properties,
+                &mut vfreebusy.parameters,
+                &mut vfreebusy.raw_properties,
+            );
+        }
+    }
+
+    /// Lists the distinct organizers of the components of the calendar.
+    pub fn organizers(&self) -> Vec<&CalAdress> {
+        let mut organizers: Vec<&CalAdress> = Vec::new();
+        for component in self.components() {
+            if let Some(organizer) = component.organizer() {
+                if !organizers.contains(&organizer) {
+                    organizers.push(organizer);
+                }
+            }
+        }
+        organizers
+    }
+
+    /// Returns every alarm of the calendar along with the component it belongs to.
+    pub fn all_alarms(&self) -> Vec<(Component<'_>, &VAlarm)> {
+        let mut alarms: Vec<(Component<'_>, &VAlarm)> = Vec::new();
+        for component in self.components() {
+            for alarm in component.alarms() {
+                alarms.push((component, alarm));
+            }
+        }
+        alarms
+    }
+
+    /// Returns the pending VTODO that is due the earliest, the ones without due come last.
+    pub fn next_todo(&self) -> Option<&VTodo> {
+        self.vtodos
+            .iter()
+            .filter(|vtodo| {
+                vtodo.completed.is_none()
+                    && !matches!(vtodo.status, Some(Status::Completed | Status::Cancelled))
+            })
+            .min_by_key(|vtodo| {
+                let due = vtodo.effective_due();
+                (due.is_none(), due)
+            })
+    }
+
+    /// Groups the VTODO by their STATUS, the ones without STATUS are left out.
+    pub fn todos_by_status(&self) -> HashMap<Status, Vec<&VTodo>> {
+        let mut buckets: HashMap<Status, Vec<&VTodo>> = HashMap::new();
+        for vtodo in &self.vtodos {
+            if let Some(status) = &vtodo.status {
+                buckets.entry(status.clone()).or_default().push(vtodo);
+            }
+        }
+        buckets
+    }
+
+    /// Sums the durations of the VTODO that are children of the given one through RELATED-TO.
+    /// Returns None if no child has a known duration.
+    pub fn total_duration_for(&self, parent_uid: &str) -> Option<Duration> {
+        // The children listed by the parent itself with RELTYPE=CHILD
+        let listed_children: Vec<&str> = self
+            .vtodos
+            .iter()
+            .filter(|vtodo| vtodo.uid == parent_uid)
+            .flat_map(|vtodo| vtodo.related_to.iter())
+            .filter(|related_to| related_to.reltype == RelType::Child)
+            .map(|related_to| related_to.value.as_str())
+            .collect();
+
+        self.vtodos
+            .iter()
+            .filter(|vtodo| {
+                listed_children.contains(&vtodo.uid.as_str())
+                    || vtodo.related_to.iter().any(|related_to| {
+                        related_to.reltype == RelType::Parent && related_to.value == parent_uid
+                    })
+            })
+            .filter_map(|vtodo| vtodo.effective_duration())
+            .reduce(|total, duration| total + duration)
+    }
+
+    /// Lists the TZID referenced by the components that no VTIMEZONE of the calendar defines.
+    pub fn missing_timezones(&self) -> Vec<String> {
+        let mut missing: Vec<String> = Vec::new();
+
+        let mut tzids: Vec<&str> = Vec::new();
+        for vevent in &self.vevents {
+            tzids.extend(vevent.tzids());
+        }
+        for vtodo in &self.vtodos {
+            tzids.extend(vtodo.tzids());
+        }
+        for vjournal in &self.vjournals {
+            tzids.extend(vjournal.tzids());
+        }
+        for tzid in tzids {
+            if self.timezone(tzid).is_none() && !missing.iter().any(|value| value == tzid) {
+                missing.push(tzid.to_string());
+            }
+        }
+
+        missing
+    }
+}
+
+impl FromStr for VCalendar {
+    type Err = ICSError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VCalendar::from_reader(s.as_bytes())
+    }
+}
+
+/// Builds a calendar to write, build verifies it has its required properties and a component.
+#[derive(Debug)]
+pub struct VCalendarBuilder {
+    vcalendar: VCalendar,
+}
+
+impl Default for VCalendarBuilder {
+    fn default() -> Self {
+        VCalendarBuilder::new()
+    }
+}
+
+impl VCalendarBuilder {
+    /// Starts a calendar with the PRODID of the crate and the VERSION 2.0.
+    pub fn new() -> VCalendarBuilder {
+        VCalendarBuilder {
+            vcalendar: VCalendar::new_empty(),
+        }
+    }
+
+    pub fn prodid(mut self, prodid: String) -> VCalendarBuilder {
+        self.vcalendar.prodid = prodid;
+        self
+    }
+
+    pub fn version(mut self, version: String) -> VCalendarBuilder {
+        self.vcalendar.version = version;
+        self
+    }
+
+    pub fn calscale(mut self, calscale: String) -> VCalendarBuilder {
+        self.vcalendar.calscale = Some(calscale);
+        self
+    }
+
+    pub fn method(mut self, method: String) -> VCalendarBuilder {
+        self.vcalendar.method = Some(method);
+        self
+    }
+
+    pub fn add_todo(mut self, vtodo: VTodo) -> VCalendarBuilder {
+        self.vcalendar.vtodos.push(vtodo);
+        self
+    }
+
+    pub fn add_event(mut self, vevent: VEvent) -> VCalendarBuilder {
+        self.vcalendar.vevents.push(vevent);
+        self
+    }
+
+    pub fn add_journal(mut self, vjournal: VJournal) -> VCalendarBuilder {
+        self.vcalendar.vjournals.push(vjournal);
+        self
+    }
+
+    pub fn add_freebusy(mut self, vfreebusy: VFreeBusy) -> VCalendarBuilder {
+        self.vcalendar.vfreebusy.push(vfreebusy);
+        self
+    }
+
+    pub fn add_timezone(mut self, vtimezone: VTimezone) -> VCalendarBuilder {
+        self.vcalendar.vtimezones.push(vtimezone);
+        self
+    }
+
+    /// Returns the calendar, or the first rule it does not respect, as parsing it would.
+    pub fn build(self) -> Result<VCalendar, ICSError> {
+        let vcalendar = self.vcalendar;
+        if vcalendar.components().is_empty() {
+            return Err(ICSError::MissingNecessaryProperty(
+                "VEVENT, VTODO, VJOURNAL, VFREEBUSY".to_string(),
+            ));
+        }
+        if vcalendar.prodid.is_empty() {
+            return Err(ICSError::MissingNecessaryProperty("PRODID".to_string()));
+        }
+        if vcalendar.version.is_empty() {
+            return Err(ICSError::MissingNecessaryProperty("VERSION".to_string()));
+        }
+        vcalendar.validate_uids()?;
+        Ok(vcalendar)
+    }
 }
 
 #[test]
@@ -270,7 +1069,14 @@ fn vtodo_example_1() {
     let vcal_object =
         VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vtodo/example1.ics")).unwrap();
 
-    //let vtodo = vcal_object.vtodo.unwrap();
+    assert_eq!(vcal_object.prodid(), "-//Apple Inc.//iOS 12.5.1//EN");
+    assert_eq!(vcal_object.version(), "2.0");
+    assert_eq!(vcal_object.calscale(), Some("GREGORIAN"));
+    assert_eq!(vcal_object.method(), None);
+    assert_eq!(
+        vcal_object.todos()[0].description,
+        Some("qsdfqsdf".to_string())
+    );
 }
 
 #[test]
@@ -278,7 +1084,7 @@ fn vtodo_example_2() {
     let vcal_object =
         VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vtodo/example2.ics")).unwrap();
 
-    let vtodo = vcal_object.vtodo.unwrap();
+    let vtodo = &vcal_object.todos()[0];
 
     let expected_date = FixedOffset::east_opt(0)
         .unwrap()
@@ -298,8 +1104,8 @@ fn vtodo_example_2() {
         .unwrap();
     assert_eq!(vtodo.last_modified.unwrap(), expected_date);
 
-    assert_eq!(vtodo.status.unwrap(), Status::NeedsAction);
-    assert_eq!(vtodo.summary.unwrap(), "test".to_string());
+    assert_eq!(vtodo.status, Some(Status::NeedsAction));
+    assert_eq!(vtodo.summary, Some("test".to_string()));
 
     assert_eq!(
         vtodo.uid,
@@ -307,40 +1113,1305 @@ fn vtodo_example_2() {
     );
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
-fn vtodo_example_3() {
-    //let vcal_object =
-    //  VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vtodo/example3.ics")).unwrap();
+fn vjournal_example_1() {
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vjournal/example1.ics"))
+            .unwrap();
 
-    //let vtodo = vcal_object.vtodo.unwrap();
+    assert_eq!(vcal_object.primary_kind(), Some(ComponentKind::Journal));
+    let vjournal = &vcal_object.journals()[0];
+    assert_eq!(vjournal.uid, "19970901T130000Z-123405@example.com");
+    assert!(vcal_object
+        .write()
+        .contains("SUMMARY:Staff meeting minutes\r\n"));
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
-fn missing_properties() {
-    // No VTODO,VEVENT,VCALENDAR
-    // No ProdID
-    // No Version
-    todo!();
+fn vevent_with_timezone() {
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vevent/example3.ics"))
+            .unwrap();
+
+    assert_eq!(vcal_object.timezones().len(), 1);
+    assert!(vcal_object.timezone("America/Montreal").is_some());
+    // The TZID of the VEVENT is defined by the calendar
+    assert!(vcal_object.missing_timezones().is_empty());
+
+    // The local time is placed in the zone of the VTIMEZONE
+    assert_eq!(
+        vcal_object.events()[0].dtstart,
+        Some(
+            FixedOffset::west_opt(4 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2022, 9, 14, 16, 0, 0)
+                .unwrap()
+        )
+    );
+
+    let output = vcal_object.write();
+    assert!(output.find("END:VTIMEZONE\r\n").unwrap() < output.find("BEGIN:VEVENT\r\n").unwrap());
+    assert!(output.contains("DTSTART;TZID=America/Montreal:20220914T160000\r\n"));
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
-fn duplicate_unique_properties() {
-    // Two VTODO, VEVENT, VCALENDAR
-    // One VTODO and andother ... Etc
-    todo!();
+fn unknown_timezone() {
+    let calendar = |tzid: &str| {
+        format!(
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+             BEGIN:VTODO\r\n\
+             UID:20070313T123432Z-456553@example.com\r\n\
+             DTSTAMP:20070313T123432Z\r\n\
+             DUE;TZID={tzid}:20070501T110000\r\n\
+             END:VTODO\r\n\
+             END:VCALENDAR\r\n"
+        )
+    };
+
+    assert_eq!(
+        VCalendar::from_str(&calendar("Mars/Olympus_Mons")).unwrap_err(),
+        ICSError::UnknownTimezone("Mars/Olympus_Mons".to_string())
+    );
+
+    // Without a VTIMEZONE, a time zone name is still understood
+    let vcal_object = VCalendar::from_str(&calendar("America/New_York")).unwrap();
+    assert_eq!(vcal_object.missing_timezones(), vec!["America/New_York"]);
+    assert_eq!(
+        vcal_object.todos()[0].due,
+        Some(
+            FixedOffset::west_opt(4 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2007, 5, 1, 11, 0, 0)
+                .unwrap()
+        )
+    );
 }
 
-#[ignore = "Not implemented yet"]
 #[test]
-fn x_components_tests() {
-    todo!();
+fn vfreebusy_example_1() {
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vfreebusy/example1.ics"))
+            .unwrap();
+
+    assert_eq!(vcal_object.primary_kind(), Some(ComponentKind::FreeBusy));
+    assert_eq!(vcal_object.freebusy()[0].freebusy.len(), 3);
+    assert!(vcal_object
+        .write()
+        .contains("BEGIN:VFREEBUSY\r\nDTSTAMP:19970901T120000Z\r\n"));
+}
+
+#[test]
+fn missing_timezones() {
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vtodo/missing_timezone.ics"))
+            .unwrap();
+    assert_eq!(vcal_object.missing_timezones(), vec!["America/New_York"]);
+
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vtodo/example2.ics")).unwrap();
+    assert!(vcal_object.missing_timezones().is_empty());
+}
+
+#[test]
+fn input_too_large() {
+    let path = Path::new("./tests/test_files/vtodo/example2.ics");
+
+    let options = ParseOptions {
+        max_lines: 5,
+        ..Default::default()
+    };
+    assert_eq!(
+        VCalendar::load_vcal_from_file_with_options(path, &options).unwrap_err(),
+        ICSError::InputTooLarge
+    );
+
+    let options = ParseOptions {
+        max_line_len: 20,
+        ..Default::default()
+    };
+    assert_eq!(
+        VCalendar::load_vcal_from_file_with_options(path, &options).unwrap_err(),
+        ICSError::InputTooLarge
+    );
+
+    let options = ParseOptions {
+        max_line_len: 75,
+        max_lines: 20,
+        ..Default::default()
+    };
+    assert!(VCalendar::load_vcal_from_file_with_options(path, &options).is_ok());
+}
+
+#[test]
+fn all_alarms() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.alarms.push(VAlarm::new_empty(
+        Action::Display,
+        Trigger::Relative(Duration::minutes(-30), Related::Start),
+    ));
+    vtodo.alarms.push(VAlarm::new_empty(
+        Action::Audio,
+        Trigger::Relative(Duration::minutes(-15), Related::Start),
+    ));
+
+    let vcal_object = VCalendar {
+        vtodos: vec![vtodo],
+        vevents: vec![VEvent {
+            alarms: vec![VAlarm::new_empty(
+                Action::Email,
+                Trigger::Relative(Duration::days(-2), Related::Start),
+            )],
+            ..VEvent::new_empty(dtstamp, "19970610T172345Z-AF23B2@example.com".to_string())
+        }],
+        ..VCalendar::new_empty()
+    };
+
+    let alarms = vcal_object.all_alarms();
+    assert_eq!(alarms.len(), 3);
+
+    assert!(matches!(alarms[0].0, Component::Event(_)));
+    assert_eq!(alarms[0].1.action, Action::Email);
+    assert!(matches!(alarms[1].0, Component::Todo(_)));
+    assert_eq!(alarms[1].1.action, Action::Display);
+    assert!(matches!(alarms[2].0, Component::Todo(_)));
+    assert_eq!(
+        alarms[2].1.trigger,
+        Trigger::Relative(Duration::minutes(-15), Related::Start)
+    );
+
+    assert!(VCalendar::new_empty().all_alarms().is_empty());
+}
+
+#[test]
+fn primary_kind() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+
+    assert_eq!(VCalendar::new_empty().primary_kind(), None);
+
+    let mut vcal_object = VCalendar {
+        vtodos: vec![VTodo::new_empty(
+            dtstamp,
+            "20070514T103211Z-123404@example.com".to_string(),
+        )],
+        ..VCalendar::new_empty()
+    };
+    assert_eq!(vcal_object.primary_kind(), Some(ComponentKind::Todo));
+
+    // A mixed calendar has no primary type
+    vcal_object.vevents.push(VEvent::new_empty(
+        dtstamp,
+        "19970610T172345Z-AF23B2@example.com".to_string(),
+    ));
+    assert_eq!(vcal_object.primary_kind(), None);
+}
+
+#[test]
+fn write_ordering() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+
+    let vcal_object = VCalendar {
+        calscale: Some("GREGORIAN".to_string()),
+        method: Some("PUBLISH".to_string()),
+        vtodos: vec![VTodo::new_empty(
+            dtstamp,
+            "20070514T103211Z-123404@example.com".to_string(),
+        )],
+        vevents: vec![VEvent::new_empty(
+            dtstamp,
+            "19970610T172345Z-AF23B2@example.com".to_string(),
+        )],
+        vtimezones: vec![VTimezone::new_empty("America/New_York".to_string())],
+        ..VCalendar::new_empty()
+    };
+
+    let output = vcal_object.write();
+    assert!(output.starts_with("BEGIN:VCALENDAR\r\nPRODID:"));
+    assert!(output.ends_with("END:VTODO\r\nEND:VCALENDAR\r\n"));
+
+    let position = |line: &str| output.find(line).unwrap();
+    assert!(position("PRODID:") < position("VERSION:2.0\r\n"));
+    assert!(position("VERSION:2.0\r\n") < position("CALSCALE:GREGORIAN\r\n"));
+    assert!(position("CALSCALE:GREGORIAN\r\n") < position("METHOD:PUBLISH\r\n"));
+    assert!(position("METHOD:PUBLISH\r\n") < position("BEGIN:VTIMEZONE\r\n"));
+    assert!(position("END:VTIMEZONE\r\n") < position("BEGIN:VEVENT\r\n"));
+    assert!(position("END:VEVENT\r\n") < position("BEGIN:VTODO\r\n"));
+}
+
+#[test]
+fn next_todo() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.due = Some(dtstamp);
+    let mut vcal_object = VCalendar {
+        vtodos: vec![vtodo],
+        ..VCalendar::new_empty()
+    };
+    assert_eq!(
+        vcal_object.next_todo().unwrap().uid,
+        "20070514T103211Z-123404@example.com"
+    );
+
+    // Completed or cancelled to-dos are not pending anymore
+    vcal_object.vtodos[0].status = Some(Status::Completed);
+    assert!(vcal_object.next_todo().is_none());
+    vcal_object.vtodos[0].status = Some(Status::Cancelled);
+    assert!(vcal_object.next_todo().is_none());
+    vcal_object.vtodos[0].status = Some(Status::NeedsAction);
+    vcal_object.vtodos[0].completed = Some(dtstamp);
+    assert!(vcal_object.next_todo().is_none());
+
+    // The earliest due of the pending to-dos is picked, the ones without due come last
+    let mut later = VTodo::new_empty(dtstamp, "later@example.com".to_string());
+    later.due = Some(dtstamp + Duration::days(2));
+    let mut sooner = VTodo::new_empty(dtstamp, "sooner@example.com".to_string());
+    sooner.due = Some(dtstamp + Duration::days(1));
+    vcal_object
+        .vtodos
+        .push(VTodo::new_empty(dtstamp, "undated@example.com".to_string()));
+    vcal_object.vtodos.push(later);
+    vcal_object.vtodos.push(sooner);
+    assert_eq!(vcal_object.next_todo().unwrap().uid, "sooner@example.com");
+
+    assert!(VCalendar::new_empty().next_todo().is_none());
+}
+
+#[test]
+fn vtodo_example_3() {
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vtodo/example3.ics")).unwrap();
+
+    let vtodo = &vcal_object.todos()[0];
+    assert_eq!(vtodo.uid, "63ab77af-51b3-4411-8255-94d11066aedc");
+    assert_eq!(vtodo.alarms.len(), 1);
+    assert_eq!(vtodo.alarms[0].action, Action::Display);
+    assert_eq!(
+        vtodo.alarms[0].description,
+        Some("Group-Office Alert".to_string())
+    );
+    assert_eq!(vtodo.percent, Some(30));
 }
 
 #[ignore = "Not implemented yet"]
 #[test]
-fn iana_token_components_tests() {
+fn missing_properties() {
+    // No VTODO,VEVENT,VCALENDAR
+    // No ProdID
+    // No Version
     todo!();
 }
+
+#[test]
+fn duplicate_unique_properties() {
+    let calendar = |components: &str| {
+        format!(
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+             {components}\
+             END:VCALENDAR\r\n"
+        )
+    };
+    let component = |name: &str, uid: &str| {
+        format!("BEGIN:{name}\r\nUID:{uid}\r\nDTSTAMP:20070313T123432Z\r\nEND:{name}\r\n")
+    };
+
+    // Several components of each type are read
+    let vcal_object = VCalendar::from_str(&calendar(&format!(
+        "{}{}{}",
+        component("VTODO", "first@example.com"),
+        component("VTODO", "second@example.com"),
+        component("VEVENT", "first@example.com"),
+    )))
+    .unwrap();
+    assert_eq!(vcal_object.todos().len(), 2);
+    assert_eq!(vcal_object.todos()[1].uid, "second@example.com");
+    assert_eq!(vcal_object.events().len(), 1);
+    assert!(vcal_object.journals().is_empty());
+
+    // But two components of the same type cannot share a UID
+    assert_eq!(
+        VCalendar::from_str(&calendar(&format!(
+            "{}{}",
+            component("VTODO", "first@example.com"),
+            component("VTODO", "first@example.com"),
+        )))
+        .unwrap_err(),
+        ICSError::DuplicateUniqueProperty("UID:first@example.com".to_string())
+    );
+
+    // A calendar needs at least one component
+    assert_eq!(
+        VCalendar::from_str(&calendar("")).unwrap_err(),
+        ICSError::MissingNecessaryProperty("VEVENT, VTODO, VJOURNAL, VFREEBUSY".to_string())
+    );
+}
+
+#[test]
+fn x_components_tests() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   X-WR-CALNAME:Work\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   X-MICROSOFT-CDO-BUSYSTATUS;X-PARAM=1:BUSY\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:DISPLAY\r\n\
+                   DESCRIPTION:Reminder\r\n\
+                   TRIGGER:-PT15M\r\n\
+                   X-WR-ALARMUID:alarm-1\r\n\
+                   END:VALARM\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+    let vcal_object = VCalendar::from_str(content).unwrap();
+    assert_eq!(vcal_object.x_properties()[0].name, "X-WR-CALNAME");
+    assert_eq!(vcal_object.x_properties()[0].value, "Work");
+
+    let vevent = &vcal_object.events()[0];
+    assert_eq!(vevent.x_properties[0].name, "X-MICROSOFT-CDO-BUSYSTATUS");
+    assert_eq!(vevent.x_properties[0].parameters.get("X-PARAM"), Some("1"));
+    assert_eq!(vevent.x_properties[0].value, "BUSY");
+    assert_eq!(vevent.alarms[0].x_properties[0].name, "X-WR-ALARMUID");
+    assert_eq!(vevent.alarms[0].x_properties[0].value, "alarm-1");
+
+    // They are written back as they were read
+    let written = vcal_object.write();
+    assert!(written.contains("\r\nX-WR-CALNAME:Work\r\n"));
+    assert!(written.contains("\r\nX-MICROSOFT-CDO-BUSYSTATUS;X-PARAM=1:BUSY\r\n"));
+    assert!(written.contains("\r\nX-WR-ALARMUID:alarm-1\r\n"));
+}
+
+#[test]
+fn iana_token_components_tests() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   COLOR:turquoise\r\n\
+                   BEGIN:VTODO\r\n\
+                   UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   COLOR:black\r\n\
+                   CONFERENCE;VALUE=URI;FEATURE=PHONE:tel:+1-888-555-0456,,,555123\r\n\
+                   END:VTODO\r\n\
+                   BEGIN:VJOURNAL\r\n\
+                   UID:19970901T130000Z-123405@example.com\r\n\
+                   DTSTAMP:19970901T130000Z\r\n\
+                   COLOR:red\r\n\
+                   END:VJOURNAL\r\n\
+                   END:VCALENDAR\r\n";
+
+    // The tokens are refused unless the parsing is lenient
+    assert_eq!(
+        VCalendar::from_str(content).unwrap_err(),
+        ICSError::UknownProperty("COLOR".to_string())
+    );
+
+    let vcal_object =
+        VCalendar::from_reader_with_options(content.as_bytes(), &ParseOptions::lenient()).unwrap();
+    assert_eq!(vcal_object.iana_properties().len(), 1);
+    assert_eq!(vcal_object.iana_properties()[0].value, "turquoise");
+
+    let vtodo = &vcal_object.todos()[0];
+    let names: Vec<&str> = vtodo
+        .iana_properties
+        .iter()
+        .map(|property| property.name.as_str())
+        .collect();
+    assert_eq!(names, ["COLOR", "CONFERENCE"]);
+    assert_eq!(vtodo.iana_properties[0].value, "black");
+    assert_eq!(
+        vtodo.iana_properties[1].parameters.get("FEATURE"),
+        Some("PHONE")
+    );
+    assert_eq!(
+        vtodo.iana_properties[1].value,
+        "tel:+1-888-555-0456,,,555123"
+    );
+    assert_eq!(vcal_object.journals()[0].iana_properties[0].value, "red");
+
+    // They are written back in the components they were read from
+    let written = vcal_object.write();
+    assert!(written.contains("\r\nCOLOR:black\r\n"));
+    assert!(written.contains("\r\nCONFERENCE;"));
+    let rewritten =
+        VCalendar::from_reader_with_options(written.as_bytes(), &ParseOptions::lenient()).unwrap();
+    assert_eq!(rewritten.iana_properties(), vcal_object.iana_properties());
+    let rewritten_vtodo = &rewritten.todos()[0];
+    assert_eq!(rewritten_vtodo.iana_properties.len(), 2);
+    assert_eq!(
+        rewritten_vtodo.iana_properties[1].value,
+        "tel:+1-888-555-0456,,,555123"
+    );
+}
+
+#[test]
+fn total_duration_for() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+
+    let mut child = VTodo::new_empty(dtstamp, "child@example.com".to_string());
+    child.duration = Some(Duration::hours(2));
+    child.related_to.push(RelatedTo {
+        value: "parent@example.com".to_string(),
+        reltype: RelType::Parent,
+    });
+    let mut vcal_object = VCalendar {
+        vtodos: vec![child],
+        ..VCalendar::new_empty()
+    };
+    assert_eq!(
+        vcal_object.total_duration_for("parent@example.com"),
+        Some(Duration::hours(2))
+    );
+    assert_eq!(vcal_object.total_duration_for("other@example.com"), None);
+
+    // The duration can also come from DTSTART and DUE
+    let child = &mut vcal_object.vtodos[0];
+    child.duration = None;
+    child.dtstart = Some(dtstamp);
+    child.due = Some(dtstamp + Duration::minutes(90));
+    assert_eq!(
+        vcal_object.total_duration_for("parent@example.com"),
+        Some(Duration::minutes(90))
+    );
+
+    // A second child listed by the parent itself adds up
+    let mut parent = VTodo::new_empty(dtstamp, "parent@example.com".to_string());
+    parent.duration = Some(Duration::hours(8));
+    parent.related_to.push(RelatedTo {
+        value: "listed@example.com".to_string(),
+        reltype: RelType::Child,
+    });
+    let mut listed = VTodo::new_empty(dtstamp, "listed@example.com".to_string());
+    listed.duration = Some(Duration::minutes(30));
+    vcal_object.vtodos.push(parent);
+    vcal_object.vtodos.push(listed);
+    assert_eq!(
+        vcal_object.total_duration_for("parent@example.com"),
+        Some(Duration::minutes(120))
+    );
+
+    // A sibling is not a child
+    vcal_object.vtodos[0].related_to[0].reltype = RelType::Sibling;
+    assert_eq!(
+        vcal_object.total_duration_for("parent@example.com"),
+        Some(Duration::minutes(30))
+    );
+}
+
+#[test]
+fn from_str() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   BEGIN:VTODO\r\n\
+                   UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   SUMMARY:Submit Quebec Income Tax Return for 2006\r\n\
+                   END:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+
+    let vcal_object: VCalendar = content.parse().unwrap();
+    assert_eq!(
+        vcal_object.prodid,
+        "-//ABC Corporation//NONSGML My Product//EN"
+    );
+    assert_eq!(
+        vcal_object.todos()[0].uid,
+        "20070313T123432Z-456553@example.com"
+    );
+
+    // The written calendar can be read back
+    let written = VCalendar::from_str(content).unwrap().write();
+    let vcal_object = VCalendar::from_reader(written.as_bytes()).unwrap();
+    assert_eq!(vcal_object.todos().len(), 1);
+
+    // Size limits apply to any source
+    let options = ParseOptions {
+        max_lines: 5,
+        ..Default::default()
+    };
+    assert_eq!(
+        VCalendar::from_reader_with_options(content.as_bytes(), &options).unwrap_err(),
+        ICSError::InputTooLarge
+    );
+}
+
+#[test]
+fn load_vcal_from_reader() {
+    // An HTTP client gives the body as a reader, simulated here by a cursor
+    let body = std::fs::read("./tests/test_files/vtodo/example2.ics").unwrap();
+    let reader = BufReader::new(std::io::Cursor::new(body));
+
+    let vcal_object = VCalendar::load_vcal_from_reader(reader).unwrap();
+    assert_eq!(
+        vcal_object.todos()[0].uid,
+        "F01AAFD6-686E-4FD7-8A94-9D7EB876A6F6"
+    );
+}
+
+#[test]
+fn organizers() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let organizer = |address: &str| address.parse::<CalAdress>().unwrap();
+
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.organizer = Some(organizer("mailto:jsmith@example.com"));
+    let mut vcal_object = VCalendar {
+        vtodos: vec![vtodo],
+        vevents: vec![VEvent {
+            organizer: Some(organizer("mailto:jane_doe@example.com")),
+            ..VEvent::new_empty(dtstamp, "19970610T172345Z-AF23B2@example.com".to_string())
+        }],
+        ..VCalendar::new_empty()
+    };
+
+    let organizers = vcal_object.organizers();
+    assert_eq!(organizers.len(), 2);
+    assert_eq!(
+        organizers[0].address.to_string(),
+        "mailto:jane_doe@example.com"
+    );
+    assert_eq!(
+        organizers[1].address.to_string(),
+        "mailto:jsmith@example.com"
+    );
+
+    // The same organizer is only listed once
+    vcal_object.vevents[0].organizer = Some(organizer("mailto:jsmith@example.com"));
+    assert_eq!(vcal_object.organizers().len(), 1);
+
+    assert!(VCalendar::new_empty().organizers().is_empty());
+}
+
+#[test]
+fn write_round_trip() {
+    for path in [
+        "./tests/test_files/vtodo/example2.ics",
+        "./tests/test_files/vtodo/missing_timezone.ics",
+    ] {
+        let vcal_object = VCalendar::load_vcal_from_file(Path::new(path)).unwrap();
+        let output = vcal_object.write();
+        assert!(output
+            .split_inclusive('\n')
+            .all(|line| line.ends_with("\r\n")));
+
+        // Reading the output back gives the same calendar
+        let read_back = VCalendar::from_str(&output).unwrap();
+        assert_eq!(read_back.prodid, vcal_object.prodid);
+        assert_eq!(read_back.version, vcal_object.version);
+        assert_eq!(read_back.calscale, vcal_object.calscale);
+        assert_eq!(read_back.method, vcal_object.method);
+        assert_eq!(
+            read_back.missing_timezones(),
+            vcal_object.missing_timezones()
+        );
+
+        let (vtodo, read_back_vtodo) = (&vcal_object.todos()[0], &read_back.todos()[0]);
+        assert_eq!(read_back_vtodo.uid, vtodo.uid);
+        assert_eq!(read_back_vtodo.dtstamp, vtodo.dtstamp);
+        assert_eq!(read_back_vtodo.dtstart, vtodo.dtstart);
+        assert_eq!(read_back_vtodo.due, vtodo.due);
+        assert_eq!(read_back_vtodo.status, vtodo.status);
+        assert_eq!(read_back_vtodo.summary, vtodo.summary);
+
+        assert_eq!(read_back.write(), output);
+    }
+}
+
+#[test]
+fn clip_to_range() {
+    let day = |day: u32, month: u32| -> DateTime<FixedOffset> {
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2007, month, day, 10, 0, 0)
+            .unwrap()
+    };
+    let (from, to) = (day(1, 5), day(31, 5));
+
+    // Due in the month
+    let mut vtodo = VTodo::new_empty(day(1, 4), "20070514T103211Z-123404@example.com".to_string());
+    vtodo.dtstart = Some(day(28, 4));
+    vtodo.due = Some(day(2, 5));
+    let mut vcal_object = VCalendar {
+        vtodos: vec![vtodo],
+        ..VCalendar::new_empty()
+    };
+    vcal_object.clip_to_range(from, to);
+    assert_eq!(vcal_object.todos().len(), 1);
+
+    // Entirely before the month, but with occurrences in and after it
+    let vtodo = &mut vcal_object.vtodos[0];
+    vtodo.due = Some(day(29, 4));
+    vtodo.rdate = vec![day(10, 5), day(10, 6)];
+    vcal_object.clip_to_range(from, to);
+    assert_eq!(vcal_object.todos()[0].rdate, vec![day(10, 5)]);
+
+    // Nothing left in the month
+    vcal_object.vtodos[0].rdate.clear();
+    vcal_object.clip_to_range(from, to);
+    assert!(vcal_object.todos().is_empty());
+
+    // Without any time, the to-do is kept
+    let mut vcal_object = VCalendar {
+        vtodos: vec![VTodo::new_empty(
+            day(1, 4),
+            "20070514T103211Z-123404@example.com".to_string(),
+        )],
+        ..VCalendar::new_empty()
+    };
+    vcal_object.clip_to_range(from, to);
+    assert_eq!(vcal_object.todos().len(), 1);
+
+    // A weekly event starting before the month has occurrences in it
+    let mut vevent =
+        VEvent::new_empty(day(1, 4), "19970610T172345Z-AF23B2@example.com".to_string());
+    vevent.dtstart = Some(day(2, 4));
+    vevent.duration = Some(Duration::hours(1));
+    vevent.rrule = Some(Recur::from_str("FREQ=WEEKLY").unwrap());
+    let mut vcal_object = VCalendar {
+        vevents: vec![vevent],
+        ..VCalendar::new_empty()
+    };
+    vcal_object.clip_to_range(from, to);
+    assert_eq!(vcal_object.events().len(), 1);
+
+    // Unless its occurrences end before it
+    vcal_object.vevents[0].rrule = Some(Recur::from_str("FREQ=WEEKLY;COUNT=3").unwrap());
+    vcal_object.clip_to_range(from, to);
+    assert!(vcal_object.events().is_empty());
+}
+
+#[test]
+fn validate_uids() {
+    let dtstamp: DateTime<FixedOffset> = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+
+    // A VTODO and a VEVENT may be linked by sharing a UID
+    let vcal_object = VCalendar {
+        vtodos: vec![VTodo::new_empty(
+            dtstamp,
+            "20070514T103211Z-123404@example.com".to_string(),
+        )],
+        vevents: vec![VEvent::new_empty(
+            dtstamp,
+            "20070514T103211Z-123404@example.com".to_string(),
+        )],
+        ..VCalendar::new_empty()
+    };
+    assert!(vcal_object.validate_uids().is_ok());
+}
+
+#[test]
+fn is_cancellation() {
+    let calendar = |status: &str| {
+        format!(
+            "BEGIN:VCALENDAR\r\n\
+             VERSION:2.0\r\n\
+             PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+             METHOD:CANCEL\r\n\
+             BEGIN:VEVENT\r\n\
+             UID:19970610T172345Z-AF23B2@example.com\r\n\
+             DTSTAMP:19970610T172345Z\r\n\
+             SEQUENCE:1\r\n\
+             {status}\
+             END:VEVENT\r\n\
+             END:VCALENDAR\r\n"
+        )
+    };
+
+    let vcal_object = VCalendar::from_str(&calendar("STATUS:CANCELLED\r\n")).unwrap();
+    assert!(vcal_object.is_cancellation());
+    assert!(vcal_object.warnings().is_empty());
+
+    // The cancellation is kept when written back
+    let read_back = VCalendar::from_str(&vcal_object.write()).unwrap();
+    assert!(read_back.is_cancellation());
+    assert_eq!(read_back.events()[0].status, Some(Status::Cancelled));
+    assert!(read_back.warnings().is_empty());
+
+    // A cancelled component without the status is still read, with a warning
+    let vcal_object = VCalendar::from_str(&calendar("STATUS:CONFIRMED\r\n")).unwrap();
+    assert!(vcal_object.is_cancellation());
+    assert_eq!(
+        vcal_object.warnings(),
+        ["METHOD:CANCEL but 19970610T172345Z-AF23B2@example.com is not STATUS:CANCELLED"]
+    );
+    let vcal_object = VCalendar::from_str(&calendar("")).unwrap();
+    assert_eq!(vcal_object.warnings().len(), 1);
+
+    assert!(!VCalendar::new_empty().is_cancellation());
+}
+
+#[test]
+fn utf16_input() {
+    let content = "BEGIN:VCALENDAR\r\nEND:VCALENDAR\r\n";
+    let mut utf16_le = vec![0xFF, 0xFE];
+    let mut utf16_be = vec![0xFE, 0xFF];
+    for unit in content.encode_utf16() {
+        utf16_le.extend_from_slice(&unit.to_le_bytes());
+        utf16_be.extend_from_slice(&unit.to_be_bytes());
+    }
+
+    assert_eq!(
+        VCalendar::from_reader(utf16_le.as_slice()).unwrap_err(),
+        ICSError::UnsupportedEncoding(
+            "UTF-16LE input, the file must be converted to UTF-8".to_string()
+        )
+    );
+    assert_eq!(
+        VCalendar::from_reader(utf16_be.as_slice()).unwrap_err(),
+        ICSError::UnsupportedEncoding(
+            "UTF-16BE input, the file must be converted to UTF-8".to_string()
+        )
+    );
+}
+
+#[test]
+fn redact_attendees() {
+    let mut vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vevent/example3.ics"))
+            .unwrap();
+    assert_eq!(vcal_object.events()[0].attendee.len(), 2);
+    let dtstart = vcal_object.events()[0].dtstart;
+
+    vcal_object.redact_attendees();
+    assert!(vcal_object.organizers().is_empty());
+    let vevent = &vcal_object.events()[0];
+    assert!(vevent.attendee.is_empty());
+    assert_eq!(vevent.dtstart, dtstart);
+    assert_eq!(
+        vevent.summary.as_deref(),
+        Some("Made using my instance of GO")
+    );
+
+    // Nothing is left in the output either
+    let output = vcal_object.write();
+    assert!(!output.contains("ATTENDEE"));
+    assert!(!output.contains("ORGANIZER"));
+    assert!(!output.contains("mailto:"));
+    assert!(output.contains("SUMMARY:Made using my instance of GO\r\n"));
+
+    // The extension properties naming them are removed too, even from the kept lines
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   ORGANIZER:mailto:jsmith@example.com\r\n\
+                   X-MS-OLK-SENDER;CN=John Smith:mailto:jsmith@example.com\r\n\
+                   X-MICROSOFT-CDO-BUSYSTATUS;X-PARAM=1:BUSY\r\n\
+                   X-MOZ-ORGANIZER:mailto:jsmith@example.com\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+    let preserve_raw = ParseOptions {
+        preserve_raw: true,
+        ..Default::default()
+    };
+    for options in [ParseOptions::default(), preserve_raw] {
+        let mut vcal_object =
+            VCalendar::from_reader_with_options(content.as_bytes(), &options).unwrap();
+        vcal_object.redact_attendees();
+        let vevent = &vcal_object.events()[0];
+        assert_eq!(vevent.x_properties.len(), 1);
+        assert_eq!(vevent.x_properties[0].name, "X-MICROSOFT-CDO-BUSYSTATUS");
+
+        let output = vcal_object.write();
+        assert!(!output.contains("mailto:"));
+        assert!(output.contains("\r\nX-MICROSOFT-CDO-BUSYSTATUS;X-PARAM=1:BUSY\r\n"));
+    }
+}
+
+#[test]
+fn blank_lines() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   \r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   \r\n\
+                   BEGIN:VTODO\r\n\
+                   UID:20070313T123432Z-456553@example.com\r\n\
+                   \r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:AUDIO\r\n\
+                   TRIGGER:-PT30M\r\n\
+                   END:VALARM\r\n\
+                   \r\n\
+                   END:VTODO\r\n\
+                   \r\n\
+                   \r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   END:VEVENT\r\n\
+                   \r\n\
+                   END:VCALENDAR\r\n";
+
+    let vcal_object = VCalendar::from_str(content).unwrap();
+    assert_eq!(vcal_object.todos().len(), 1);
+    assert_eq!(vcal_object.todos()[0].alarms.len(), 1);
+    assert_eq!(vcal_object.events().len(), 1);
+}
+
+#[test]
+fn uids() {
+    let vcal_object = VCalendar::load_vcal_from_file(Path::new(
+        "./tests/test_files/vcalendar/two_components.ics",
+    ))
+    .unwrap();
+
+    let uids = vcal_object.uids();
+    assert_eq!(uids.len(), 2);
+    assert!(uids.contains(&"20070313T123432Z-456553@example.com"));
+    assert!(uids.contains(&"19970610T172345Z-AF23B2@example.com"));
+
+    assert!(VCalendar::new_empty().uids().is_empty());
+}
+
+#[test]
+fn swap_geo() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   GEO:-122.08;37.38\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+    // Refused unless asked for
+    assert_eq!(
+        VCalendar::from_str(content).unwrap_err(),
+        ICSError::PropertyConditionNotRespected("GEO".to_string())
+    );
+
+    let options = ParseOptions {
+        swap_geo: true,
+        ..Default::default()
+    };
+    let vcal_object = VCalendar::from_reader_with_options(content.as_bytes(), &options).unwrap();
+    assert_eq!(vcal_object.events()[0].geo, Some((37.38, -122.08)));
+    assert_eq!(
+        vcal_object.warnings(),
+        ["GEO of 19970610T172345Z-AF23B2@example.com had its latitude and longitude swapped"]
+    );
+}
+
+#[test]
+fn next_alarm_after() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   DTSTART:20240101T090000Z\r\n\
+                   RRULE:FREQ=DAILY\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:AUDIO\r\n\
+                   TRIGGER:-PT15M\r\n\
+                   END:VALARM\r\n\
+                   END:VEVENT\r\n\
+                   BEGIN:VTODO\r\n\
+                   UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   DUE:20240103T120000Z\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:DISPLAY\r\n\
+                   DESCRIPTION:Due in an hour\r\n\
+                   TRIGGER;RELATED=END:-PT1H\r\n\
+                   END:VALARM\r\n\
+                   END:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+    let vcal_object = VCalendar::from_str(content).unwrap();
+    let utc = FixedOffset::east_opt(0).unwrap();
+
+    // The next occurrence of the event goes off before the to-do
+    let (time, alarm) = vcal_object
+        .next_alarm_after(utc.with_ymd_and_hms(2024, 1, 2, 10, 0, 0).unwrap())
+        .unwrap();
+    assert_eq!(time, utc.with_ymd_and_hms(2024, 1, 3, 8, 45, 0).unwrap());
+    assert_eq!(alarm.action, Action::Audio);
+
+    let (time, alarm) = vcal_object
+        .next_alarm_after(utc.with_ymd_and_hms(2024, 1, 3, 9, 0, 0).unwrap())
+        .unwrap();
+    assert_eq!(time, utc.with_ymd_and_hms(2024, 1, 3, 11, 0, 0).unwrap());
+    assert_eq!(alarm.action, Action::Display);
+}
+
+#[test]
+fn default_timezone() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   X-WR-CALNAME:Work\r\n\
+                   X-WR-TIMEZONE:America/New_York\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+    let vcal_object = VCalendar::from_str(content).unwrap();
+    assert_eq!(vcal_object.default_timezone(), Some("America/New_York"));
+    assert_eq!(vcal_object.x_properties().len(), 2);
+
+    // The properties are written back
+    let written = vcal_object.write();
+    assert!(written.contains("\r\nX-WR-CALNAME:Work\r\nX-WR-TIMEZONE:America/New_York\r\n"));
+    assert_eq!(
+        VCalendar::from_str(&written).unwrap().default_timezone(),
+        Some("America/New_York")
+    );
+}
+
+#[test]
+fn iana_properties() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   COLOR:turquoise\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   CONFERENCE;VALUE=URI:https://chat.example.com/audio?id=123456\r\n\
+                   END:VEVENT\r\n\
+                   END:VCALENDAR\r\n";
+
+    // A validator still refuses them
+    assert_eq!(
+        VCalendar::from_str(content).unwrap_err(),
+        ICSError::UknownProperty("COLOR".to_string())
+    );
+
+    let vcal_object =
+        VCalendar::from_reader_with_options(content.as_bytes(), &ParseOptions::lenient()).unwrap();
+    assert_eq!(vcal_object.iana_properties()[0].name, "COLOR");
+    let conference = &vcal_object.events()[0].iana_properties[0];
+    assert_eq!(conference.parameters.get("VALUE"), Some("URI"));
+    assert_eq!(conference.value, "https://chat.example.com/audio?id=123456");
+
+    // They are written back as they were read
+    let written = vcal_object.write();
+    assert!(written.contains("\r\nCOLOR:turquoise\r\n"));
+    assert!(
+        written.contains("\r\nCONFERENCE;VALUE=URI:https://chat.example.com/audio?id=123456\r\n")
+    );
+}
+
+#[test]
+fn missing_end() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   BEGIN:VEVENT\r\n\
+                   UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   END:VEVENT\r\n";
+
+    assert_eq!(
+        VCalendar::from_str(content).unwrap_err(),
+        ICSError::BeginWithoutEnd
+    );
+
+    let vcal_object =
+        VCalendar::from_reader_with_options(content.as_bytes(), &ParseOptions::lenient()).unwrap();
+    assert_eq!(vcal_object.events().len(), 1);
+    assert_eq!(vcal_object.warnings(), ["END:VCALENDAR is missing"]);
+
+    // A truncated component is still refused
+    let truncated = content.replace("END:VEVENT\r\n", "");
+    assert!(
+        VCalendar::from_reader_with_options(truncated.as_bytes(), &ParseOptions::lenient())
+            .is_err()
+    );
+}
+
+#[test]
+fn todos_by_status() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vcal_object = VCalendar::new_empty();
+    for (uid, status) in [
+        ("first@example.com", Some(Status::NeedsAction)),
+        ("second@example.com", Some(Status::Completed)),
+        ("third@example.com", Some(Status::NeedsAction)),
+        ("fourth@example.com", None),
+    ] {
+        let mut vtodo = VTodo::new_empty(dtstamp, uid.to_string());
+        vtodo.status = status;
+        vcal_object.vtodos.push(vtodo);
+    }
+
+    let buckets = vcal_object.todos_by_status();
+    assert_eq!(buckets.len(), 2);
+    let uids = |status: Status| -> Vec<&str> {
+        buckets[&status]
+            .iter()
+            .map(|vtodo| vtodo.uid.as_str())
+            .collect()
+    };
+    assert_eq!(
+        uids(Status::NeedsAction),
+        ["first@example.com", "third@example.com"]
+    );
+    assert_eq!(uids(Status::Completed), ["second@example.com"]);
+}
+
+#[test]
+fn lenient_calendar() {
+    // Lone LF line endings, no VERSION and an unknown property
+    let content = "BEGIN:VCALENDAR\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\n\
+                   BEGIN:VEVENT\n\
+                   UID:19970610T172345Z-AF23B2@example.com\n\
+                   DTSTAMP:19970610T172345Z\n\
+                   COLOR:turquoise\n\
+                   END:VEVENT\n\
+                   END:VCALENDAR\n";
+
+    assert_eq!(
+        VCalendar::from_str(content).unwrap_err(),
+        ICSError::UknownProperty("COLOR".to_string())
+    );
+    assert_eq!(
+        VCalendar::from_str(&content.replace("COLOR:turquoise\n", "")).unwrap_err(),
+        ICSError::MissingNecessaryProperty("VERSION".to_string())
+    );
+
+    let vcal_object =
+        VCalendar::from_reader_with_options(content.as_bytes(), &ParseOptions::lenient()).unwrap();
+    assert_eq!(vcal_object.events()[0].iana_properties[0].name, "COLOR");
+    assert_eq!(
+        vcal_object.warnings(),
+        ["VERSION is missing, 2.0 is assumed"]
+    );
+}
+
+#[test]
+fn load_with_diagnostics() {
+    let (vcal_object, diagnostics) =
+        VCalendar::load_with_diagnostics(Path::new("tests/test_files/vcalendar/diagnostics.ics"));
+
+    // Everything that could be read is kept
+    let vcal_object = vcal_object.unwrap();
+    assert_eq!(vcal_object.todos().len(), 1);
+    assert_eq!(
+        vcal_object.todos()[0].summary,
+        Some("Submit Quebec Income Tax Return".to_string())
+    );
+    assert!(vcal_object.events().is_empty());
+
+    let lines: Vec<usize> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.line)
+        .collect();
+    assert_eq!(lines, [4, 8, 11, 13]);
+    assert_eq!(
+        diagnostics[0].error,
+        ICSError::UnexpectedProperty("SUMMARY:Not a calendar property".to_string())
+    );
+    assert_eq!(
+        diagnostics[2].error,
+        ICSError::DuplicateUniqueProperty("SUMMARY:Second summary".to_string())
+    );
+    assert_eq!(
+        diagnostics[3].to_string(),
+        "line 13: missing required property: UID"
+    );
+
+    // A file that cannot be read at all
+    let (vcal_object, diagnostics) =
+        VCalendar::load_with_diagnostics(Path::new("tests/test_files/vcalendar/missing.ics"));
+    assert!(vcal_object.is_none());
+    assert_eq!(diagnostics[0].line, 0);
+}
+
+#[test]
+fn dtstart_date_time_with_tzid() {
+    let content = "BEGIN:VCALENDAR\r\n\
+                   VERSION:2.0\r\n\
+                   PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n\
+                   BEGIN:VTIMEZONE\r\n\
+                   TZID:Europe/Paris\r\n\
+                   BEGIN:STANDARD\r\n\
+                   DTSTART:19701025T030000\r\n\
+                   RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU\r\n\
+                   TZOFFSETFROM:+0200\r\n\
+                   TZOFFSETTO:+0100\r\n\
+                   END:STANDARD\r\n\
+                   BEGIN:DAYLIGHT\r\n\
+                   DTSTART:19700329T020000\r\n\
+                   RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU\r\n\
+                   TZOFFSETFROM:+0100\r\n\
+                   TZOFFSETTO:+0200\r\n\
+                   END:DAYLIGHT\r\n\
+                   END:VTIMEZONE\r\n\
+                   BEGIN:VTODO\r\n\
+                   UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   DTSTART;TZID=Europe/Paris;VALUE=DATE-TIME:20070313T123432\r\n\
+                   DUE;VALUE=DATE-TIME;TZID=Europe/Paris:20070713T123432\r\n\
+                   END:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+
+    let vcal_object = VCalendar::from_str(content).unwrap();
+    let vtodo = &vcal_object.todos()[0];
+    let utc = FixedOffset::east_opt(0).unwrap();
+
+    // Winter time of the VTIMEZONE, then summer time
+    let dtstart = vtodo.dtstart.unwrap();
+    assert_eq!(dtstart.offset().local_minus_utc(), 3600);
+    assert_eq!(
+        dtstart,
+        utc.with_ymd_and_hms(2007, 3, 13, 11, 34, 32).unwrap()
+    );
+    let due = vtodo.due.unwrap();
+    assert_eq!(due.offset().local_minus_utc(), 7200);
+    assert_eq!(due, utc.with_ymd_and_hms(2007, 7, 13, 10, 34, 32).unwrap());
+
+    // Both parameters are written back along with the local time
+    assert!(vcal_object
+        .write()
+        .contains("DTSTART;TZID=Europe/Paris;VALUE=DATE-TIME:20070313T123432\r\n"));
+}
+
+#[test]
+fn vcalendar_builder() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let builder = || {
+        VCalendarBuilder::new()
+            .prodid("-//ABC Corporation//NONSGML My Product//EN".to_string())
+            .method("PUBLISH".to_string())
+    };
+
+    // A calendar needs a component
+    assert_eq!(
+        builder().build().unwrap_err(),
+        ICSError::MissingNecessaryProperty("VEVENT, VTODO, VJOURNAL, VFREEBUSY".to_string())
+    );
+
+    let vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    let vcal_object = builder().add_todo(vtodo.clone()).build().unwrap();
+    let written = vcal_object.write();
+    assert!(written.contains("PRODID:-//ABC Corporation//NONSGML My Product//EN\r\n"));
+    assert!(written.contains("METHOD:PUBLISH\r\n"));
+
+    // The written calendar is read back
+    assert_eq!(VCalendar::from_str(&written).unwrap().todos().len(), 1);
+
+    // Two VTODO cannot share a UID
+    assert!(builder()
+        .add_todo(vtodo.clone())
+        .add_todo(vtodo)
+        .build()
+        .is_err());
+}
+
+#[cfg(feature = "jcal")]
+#[test]
+fn to_jcal() {
+    use serde_json::json;
+
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vjournal/example1.ics"))
+            .unwrap();
+    let jcal = vcal_object.to_jcal();
+    assert_eq!(jcal[0], "vcalendar");
+    assert_eq!(
+        jcal[1],
+        json!([
+            [
+                "prodid",
+                {},
+                "text",
+                "-//ABC Corporation//NONSGML My Product//EN"
+            ],
+            ["version", {}, "text", "2.0"]
+        ])
+    );
+    let vjournal = &jcal[2][0];
+    assert_eq!(vjournal[0], "vjournal");
+    assert_eq!(
+        vjournal[1][0],
+        json!(["dtstamp", {}, "date-time", "1997-09-01T13:00:00Z"])
+    );
+    assert!(vjournal[1].as_array().unwrap().contains(&json!([
+        "dtstart",
+        {},
+        "date",
+        "1997-03-17"
+    ])));
+    assert_eq!(vjournal[2], json!([]));
+
+    // The time zones come first, and the times keep their TZID
+    let vcal_object =
+        VCalendar::load_vcal_from_file(Path::new("./tests/test_files/vevent/example3.ics"))
+            .unwrap();
+    let jcal = vcal_object.to_jcal();
+    let vtimezone = &jcal[2][0];
+    assert_eq!(vtimezone[0], "vtimezone");
+    assert_eq!(vtimezone[2][0][0], "standard");
+    assert!(vtimezone[2][0][1].as_array().unwrap().contains(&json!([
+        "tzoffsetto",
+        {},
+        "utc-offset",
+        "-05:00"
+    ])));
+    assert!(vtimezone[2][0][1].as_array().unwrap().contains(&json!([
+        "rrule",
+        {},
+        "recur",
+        {"freq": "YEARLY", "byday": "1SU", "bymonth": 11}
+    ])));
+
+    let vevent = jcal[2][1][1].as_array().unwrap();
+    assert_eq!(jcal[2][1][0], "vevent");
+    assert!(vevent.contains(&json!([
+        "dtstart",
+        {"tzid": "America/Montreal"},
+        "date-time",
+        "2022-09-14T16:00:00"
+    ])));
+    assert!(vevent.contains(&json!([
+        "organizer",
+        {"cn": "Organiser Person", "partstat": "ACCEPTED", "rsvp": "TRUE"},
+        "cal-address",
+        "mailto:organiser@organising.com"
+    ])));
+}
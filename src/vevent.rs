@@ -1,11 +1,971 @@
+/*
+       eventc     = "BEGIN" ":" "VEVENT" CRLF
+                    eventprop *alarmc
+                    "END" ":" "VEVENT" CRLF
+
+       eventprop  = *(
+                  ;
+                  ; The following are REQUIRED,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  dtstamp / uid /
+                  ;
+                  ; The following is REQUIRED if the component
+                  ; appears in an iCalendar object that doesn't
+                  ; specify the "METHOD" property; otherwise, it
+                  ; is OPTIONAL; in any case, it MUST NOT occur
+                  ; more than once.
+                  ;
+                  dtstart /
+                  ;
+                  ; The following are OPTIONAL,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  class / created / description / geo /
+                  last-mod / location / organizer / priority /
+                  seq / status / summary / transp /
+                  url / recurid /
+                  ;
+                  ; The following is OPTIONAL,
+                  ; but SHOULD NOT occur more than once.
+                  ;
+                  rrule /
+                  ;
+                  ; Either 'dtend' or 'duration' MAY appear in
+                  ; a 'eventprop', but 'dtend' and 'duration'
+                  ; MUST NOT occur in the same 'eventprop'.
+                  ;
+                  dtend / duration /
+                  ;
+                  ; The following are OPTIONAL,
+                  ; and MAY occur more than once.
+                  ;
+                  attach / attendee / categories / comment /
+                  contact / exdate / rstatus / related /
+                  resources / rdate / x-prop / iana-prop
+                  ;
+                  )
+
+   Description:  A "VEVENT" calendar component is a grouping of
+      component properties, possibly including "VALARM" calendar
+      components, that represents a scheduled amount of time on a
+      calendar.  For example, it can be an activity; such as a one-hour
+      long, department meeting from 8:00 AM to 9:00 AM, tomorrow.
+*/
+
 use crate::ics_error::ICSError;
+use crate::parse_options::ParseOptions;
+use crate::properties::attachment::Attachment;
+use crate::properties::class::Class;
+use crate::properties::date_time_value::DateTimeValue;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::recur::Recur;
+use crate::properties::related_to::RelatedTo;
+use crate::properties::transp::Transp;
+use crate::properties::uri::Uri;
+use crate::properties::x_prop::XProp;
+use crate::properties::{cal_adress::CalAdress, status::Status};
+use crate::properties::{Parameters, Property};
+use crate::utils;
+use crate::valarm::VAlarm;
+use crate::vtimezone::{resolve_local_time, VTimezone};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use std::io::{BufRead, Lines};
+
+#[cfg(test)]
+use chrono::TimeZone;
+#[cfg(test)]
+use std::fs::File;
+#[cfg(test)]
+use std::io::{BufReader, Cursor};
+
+#[derive(Debug, Clone)]
+pub struct VEvent {
+    // Necessary variables
+    pub dtstamp: DateTime<FixedOffset>,
+    pub uid: String,
+
+    // Optional and unique
+    pub dtstart: Option<DateTime<FixedOffset>>,
+    pub class: Option<Class>,
+    pub created: Option<DateTime<FixedOffset>>,
+    pub description: Option<String>,
+    pub geo: Option<(f32, f32)>,
+    pub last_modified: Option<DateTime<FixedOffset>>,
+    pub location: Option<String>,
+    pub organizer: Option<CalAdress>,
+    pub priority: Option<usize>,
+    pub sequence: Option<usize>,
+    pub status: Option<Status>,
+    pub summary: Option<String>,
+    pub transp: Option<Transp>,
+    pub url: Option<Uri>,
+    pub recurrence_id: Option<DateTime<FixedOffset>>,
+
+    // Optional and should not occur more than once
+    pub rrule: Option<Recur>,
+
+    // Optional and conditional
+    pub dtend: Option<DateTime<FixedOffset>>,
+    pub duration: Option<Duration>,
+
+    // Optional and several
+    pub attach: Vec<Attachment>,
+    pub attendee: Vec<CalAdress>,
+    pub categories: Vec<String>,
+    pub comment: Vec<String>,
+    pub contact: Vec<String>,
+    pub exdate: Vec<DateTime<FixedOffset>>,
+    pub related_to: Vec<RelatedTo>,
+    pub resources: Vec<String>,
+    pub rdate: Vec<DateTime<FixedOffset>>,
+
+    // Alarms nested in the VEVENT
+    pub alarms: Vec<VAlarm>,
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
+
+    // Values fixed while parsing, such as a GEO swapped with ParseOptions::swap_geo
+    pub warnings: Vec<String>,
+}
 
-#[derive(Debug)]
-pub struct VEvent {}
 impl VEvent {
-    pub fn parse_from_bufreader(
-        _line_reader: &mut std::io::Lines<std::io::BufReader<std::fs::File>>,
+    pub fn new_empty(dtstamp: DateTime<FixedOffset>, uid: String) -> VEvent {
+        VEvent {
+            dtstamp,
+            uid,
+            dtstart: None,
+            class: None,
+            created: None,
+            description: None,
+            geo: None,
+            last_modified: None,
+            location: None,
+            organizer: None,
+            priority: None,
+            sequence: None,
+            status: None,
+            summary: None,
+            transp: None,
+            url: None,
+            recurrence_id: None,
+            rrule: None,
+            dtend: None,
+            duration: None,
+            attach: Vec::new(),
+            attendee: Vec::new(),
+            categories: Vec::new(),
+            comment: Vec::new(),
+            contact: Vec::new(),
+            exdate: Vec::new(),
+            related_to: Vec::new(),
+            resources: Vec::new(),
+            rdate: Vec::new(),
+            alarms: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns the DTEND of the VEVENT, or its end computed from DTSTART and DURATION.
+    pub fn effective_end(&self) -> Option<DateTime<FixedOffset>> {
+        match (self.dtend, self.dtstart, self.duration) {
+            (Some(dtend), _, _) => Some(dtend),
+            (None, Some(dtstart), Some(duration)) => Some(dtstart + duration),
+            _ => None,
+        }
+    }
+
+    /// Returns the categories of the VEVENT separated by commas, such as "FAMILY, FINANCE".
+    pub fn categories_display(&self) -> String {
+        self.categories.join(", ")
+    }
+
+    /// Removes the RDATE occurrences that fall entirely outside of [from, to].
+    /// Returns false if no occurrence of the VEVENT, RRULE expanded, is left in the range,
+    /// true if it has no time.
+    pub fn clip_to_range(
+        &mut self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> bool {
+        let start = match self.dtstart {
+            Some(start) => start,
+            None => return true,
+        };
+        let length = self
+            .effective_end()
+            .map_or(Duration::zero(), |end| end - start);
+        let overlaps = |start: DateTime<FixedOffset>| start <= to && start + length >= from;
+
+        self.rdate.retain(|rdate| overlaps(*rdate));
+        overlaps(start)
+            || !self.rdate.is_empty()
+            || utils::rule_overlaps(
+                self.rrule.as_ref(),
+                self.dtstart,
+                &self.exdate,
+                to,
+                overlaps,
+            )
+    }
+
+    /// Lists the time zones referenced by a TZID parameter in this VEVENT.
+    pub fn tzids(&self) -> Vec<&str> {
+        let mut tzids: Vec<&str> = Vec::new();
+        for (_, parameters) in &self.parameters {
+            if let Some(tzid) = parameters.get("TZID") {
+                if !tzids.contains(&tzid) {
+                    tzids.push(tzid);
+                }
+            }
+        }
+        tzids
+    }
+
+    /// Returns a time property of the VEVENT along with the form it was given in.
+    pub fn time_value(&self, property: Property) -> Option<DateTimeValue> {
+        let value = match property {
+            Property::DTStamp => Some(self.dtstamp),
+            Property::Created => self.created,
+            Property::DTStart => self.dtstart,
+            Property::LastModified => self.last_modified,
+            Property::RecurrenceID => self.recurrence_id,
+            Property::DTEnd => self.dtend,
+            _ => None,
+        }?;
+        Some(DateTimeValue::new(
+            &value,
+            utils::find_parameters(&self.parameters, property),
+        ))
+    }
+
+    /// Places the times given with a TZID in their zone, using the VTIMEZONE of the calendar.
+    pub fn resolve_timezones(&mut self, vtimezones: &[VTimezone]) -> Result<(), ICSError> {
+        if let Some(dtstart) = &mut self.dtstart {
+            let parameters = utils::find_parameters(&self.parameters, Property::DTStart);
+            resolve_local_time(dtstart, parameters, vtimezones)?;
+        }
+        if let Some(dtend) = &mut self.dtend {
+            let parameters = utils::find_parameters(&self.parameters, Property::DTEnd);
+            resolve_local_time(dtend, parameters, vtimezones)?;
+        }
+        if let Some(recurrence_id) = &mut self.recurrence_id {
+            let parameters = utils::find_parameters(&self.parameters, Property::RecurrenceID);
+            resolve_local_time(recurrence_id, parameters, vtimezones)?;
+        }
+        for (index, exdate) in self.exdate.iter_mut().enumerate() {
+            let parameters = utils::find_nth_parameters(&self.parameters, Property::ExDate, index);
+            resolve_local_time(exdate, parameters, vtimezones)?;
+        }
+        for (index, rdate) in self.rdate.iter_mut().enumerate() {
+            let parameters = utils::find_nth_parameters(&self.parameters, Property::RDate, index);
+            resolve_local_time(rdate, parameters, vtimezones)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the content of a VEVENT object. The buffer passed should already have consumed the BEGIN:VEVENT.
+    pub fn parse_from_bufreader(line_reader: &mut Lines<impl BufRead>) -> Result<VEvent, ICSError> {
+        VEvent::parse_from_bufreader_with_options(line_reader, &ParseOptions::default())
+    }
+
+    /// Reads the content of a VEVENT object with the given options. The buffer passed should already have consumed the BEGIN:VEVENT.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
     ) -> Result<VEvent, ICSError> {
-        todo!()
+        let mut vevent: VEvent = VEvent::new_empty(
+            DateTime::from_utc(
+                Utc::now().naive_utc(),
+                FixedOffset::east_opt(0).expect("FixedOffset::east out of bounds"),
+            ),
+            "".to_string(),
+        );
+        let mut has_uid = false;
+        let mut has_dtstamp = false;
+
+        let mut current_line: Option<Result<String, std::io::Error>> = line_reader.next();
+
+        loop {
+            let line = current_line;
+            let processed_line: String;
+            match line {
+                Some(line) => {
+                    // Read line
+                    processed_line = line?;
+                    // End the process if we have arrived at the end.
+                    if processed_line.starts_with("END:VEVENT") {
+                        break;
+                    }
+                }
+                None => return Err(ICSError::BeginWithoutEnd),
+            }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+
+            if processed_line.starts_with("BEGIN:VALARM") {
+                vevent
+                    .alarms
+                    .push(VAlarm::parse_from_bufreader_with_options(
+                        line_reader,
+                        options,
+                    )?);
+
+                // Consume next line as we have finished the VALARM
+                current_line = line_reader.next();
+                continue;
+            }
+            // No other component can be nested in a VEVENT
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                return Err(ICSError::UnexpectedComponent(component.to_string()));
+            }
+
+            // Here we need to be able to process multi line arguments.
+            let property_string: String;
+            (property_string, current_line) =
+                utils::process_multi_line_property(processed_line, line_reader);
+
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+            if options.preserve_raw {
+                vevent
+                    .raw_properties
+                    .push((property, property_string.clone()));
+            }
+
+            match property {
+                Property::DTStamp => {
+                    if has_dtstamp {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_dtstamp = true;
+                    vevent.dtstamp = value.into();
+                }
+                Property::UID => {
+                    if has_uid {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_uid = true;
+                    vevent.uid = value.into();
+                }
+                Property::DTStart => {
+                    utils::apply_unique_property(&mut vevent.dtstart, value, property_string)?
+                }
+                Property::Class => {
+                    utils::apply_unique_property(&mut vevent.class, value, property_string)?
+                }
+                Property::Created => {
+                    utils::apply_unique_property(&mut vevent.created, value, property_string)?
+                }
+                Property::Description => {
+                    utils::apply_unique_property(&mut vevent.description, value, property_string)?
+                }
+                Property::Geo => {
+                    utils::apply_unique_property(&mut vevent.geo, value, property_string)?
+                }
+                Property::LastModified => {
+                    utils::apply_unique_property(&mut vevent.last_modified, value, property_string)?
+                }
+                Property::Location => {
+                    utils::apply_unique_property(&mut vevent.location, value, property_string)?
+                }
+                Property::Organizer => {
+                    utils::apply_unique_property(&mut vevent.organizer, value, property_string)?
+                }
+                Property::Priority => {
+                    utils::apply_unique_property(&mut vevent.priority, value, property_string)?
+                }
+                Property::Sequence => {
+                    utils::apply_unique_property(&mut vevent.sequence, value, property_string)?
+                }
+                Property::Status => {
+                    if vevent.status.is_some() {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    let status: Status = value.into();
+                    if !status.validate_vevent() {
+                        return Err(ICSError::PropertyConditionNotRespected(property_string));
+                    }
+                    vevent.status = Some(status);
+                }
+                Property::Summary => {
+                    utils::apply_unique_property(&mut vevent.summary, value, property_string)?
+                }
+                Property::Transp => {
+                    utils::apply_unique_property(&mut vevent.transp, value, property_string)?
+                }
+                Property::URL => {
+                    utils::apply_unique_property(&mut vevent.url, value, property_string)?
+                }
+                Property::RecurrenceID => {
+                    utils::apply_unique_property(&mut vevent.recurrence_id, value, property_string)?
+                }
+                Property::RRule => {
+                    utils::apply_unique_property(&mut vevent.rrule, value, property_string)?
+                }
+                Property::DTEnd => {
+                    utils::apply_unique_property(&mut vevent.dtend, value, property_string)?
+                }
+                Property::Duration => {
+                    utils::apply_unique_property(&mut vevent.duration, value, property_string)?
+                }
+                Property::Attach => vevent.attach.push(value.into()),
+                Property::Attendee => vevent.attendee.push(value.into()),
+                Property::Categories => {
+                    let mut string_vect: Vec<String> = value.into();
+                    vevent.categories.append(&mut string_vect);
+                }
+                Property::Comment => vevent.comment.push(value.into()),
+                Property::Contact => vevent.contact.push(value.into()),
+                Property::ExDate => vevent.exdate.push(value.into()),
+                Property::RelatedTo => vevent
+                    .related_to
+                    .push(RelatedTo::new(value.into(), &parameters)),
+                Property::Resources => vevent.resources.push(value.into()),
+                Property::RDate => vevent.rdate.push(value.into()),
+                Property::XProp => vevent.x_properties.push(value.into()),
+                Property::IanaProp => vevent.iana_properties.push(value.into()),
+                _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
+            }
+            // Kept even when empty so that repeated properties can be matched by position
+            vevent.parameters.push((property, parameters));
+        }
+
+        if !has_uid {
+            return Err(ICSError::MissingNecessaryProperty("UID".to_string()));
+        }
+        if !has_dtstamp {
+            return Err(ICSError::MissingNecessaryProperty("DTSTAMP".to_string()));
+        }
+
+        vevent.validate_consistency()?;
+        if options.swap_geo {
+            vevent
+                .warnings
+                .extend(utils::swap_geo(&mut vevent.geo, &vevent.uid));
+        }
+
+        Ok(vevent)
+    }
+
+    /// Verifies the constraints linking several properties of the VEVENT together.
+    pub fn validate_consistency(&self) -> Result<(), ICSError> {
+        // The end is given either by DTEND or by DURATION
+        if self.dtend.is_some() && self.duration.is_some() {
+            return Err(ICSError::PropertyConditionNotRespected(
+                "DURATION".to_string(),
+            ));
+        }
+        // An event cannot end before it starts
+        if let (Some(dtstart), Some(dtend)) = (self.dtstart, self.dtend) {
+            if dtend < dtstart {
+                return Err(ICSError::PropertyConditionNotRespected("DTEND".to_string()));
+            }
+        }
+
+        // The exceptions and additions are of the same type as the start
+        if self.dtstart.is_some() {
+            utils::validate_date_types(&self.parameters, self.exdate.len(), self.rdate.len())?;
+        }
+
+        Ok(())
     }
+
+    /// Writes the VEVENT in the ics format, from BEGIN:VEVENT to END:VEVENT, alarms included.
+    pub fn write(&self) -> String {
+        let mut output = String::from("BEGIN:VEVENT\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_date_time(&mut output, Property::DTStamp, &self.dtstamp);
+        self.write_property(&mut output, Property::UID, &self.uid);
+
+        if let Some(dtstart) = &self.dtstart {
+            self.write_date_time(&mut output, Property::DTStart, dtstart);
+        }
+        if let Some(class) = &self.class {
+            self.write_property(&mut output, Property::Class, &String::from(class.clone()));
+        }
+        if let Some(created) = &self.created {
+            self.write_date_time(&mut output, Property::Created, created);
+        }
+        if let Some(description) = &self.description {
+            self.write_property(
+                &mut output,
+                Property::Description,
+                &utils::escape_text(description),
+            );
+        }
+        if let Some((lat, long)) = &self.geo {
+            self.write_property(&mut output, Property::Geo, &format!("{lat};{long}"));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            self.write_date_time(&mut output, Property::LastModified, last_modified);
+        }
+        if let Some(location) = &self.location {
+            self.write_property(
+                &mut output,
+                Property::Location,
+                &utils::escape_text(location),
+            );
+        }
+        if let Some(organizer) = &self.organizer {
+            self.write_cal_address(&mut output, Property::Organizer, organizer);
+        }
+        if let Some(priority) = &self.priority {
+            self.write_property(&mut output, Property::Priority, &priority.to_string());
+        }
+        if let Some(sequence) = &self.sequence {
+            self.write_property(&mut output, Property::Sequence, &sequence.to_string());
+        }
+        if let Some(status) = &self.status {
+            self.write_property(&mut output, Property::Status, &String::from(status.clone()));
+        }
+        if let Some(summary) = &self.summary {
+            self.write_property(&mut output, Property::Summary, &utils::escape_text(summary));
+        }
+        if let Some(transp) = &self.transp {
+            self.write_property(&mut output, Property::Transp, &String::from(transp.clone()));
+        }
+        if let Some(url) = &self.url {
+            self.write_property(&mut output, Property::URL, &url.to_string());
+        }
+        if let Some(recurrence_id) = &self.recurrence_id {
+            self.write_date_time(&mut output, Property::RecurrenceID, recurrence_id);
+        }
+        if let Some(rrule) = &self.rrule {
+            self.write_property(&mut output, Property::RRule, &rrule.to_string());
+        }
+        if let Some(dtend) = &self.dtend {
+            self.write_date_time(&mut output, Property::DTEnd, dtend);
+        }
+        if let Some(duration) = &self.duration {
+            self.write_property(
+                &mut output,
+                Property::Duration,
+                &utils::format_duration(duration),
+            );
+        }
+        utils::write_attachments(
+            &mut output,
+            &self.attach,
+            &self.parameters,
+            &self.raw_properties,
+        );
+        for attendee in &self.attendee {
+            self.write_cal_address(&mut output, Property::Attendee, attendee);
+        }
+        if !self.categories.is_empty() {
+            self.write_property(
+                &mut output,
+                Property::Categories,
+                &self
+                    .categories
+                    .iter()
+                    .map(|category| utils::escape_text(category))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        for comment in &self.comment {
+            self.write_property(&mut output, Property::Comment, &utils::escape_text(comment));
+        }
+        for (index, contact) in self.contact.iter().enumerate() {
+            self.write_repeated_property(
+                &mut output,
+                Property::Contact,
+                index,
+                &utils::escape_text(contact),
+            );
+        }
+        for (index, exdate) in self.exdate.iter().enumerate() {
+            self.write_repeated_date_time(&mut output, Property::ExDate, index, exdate);
+        }
+        if !utils::has_raw_property(&self.raw_properties, Property::RelatedTo) {
+            // Each relationship has its own RELTYPE
+            for related_to in &self.related_to {
+                utils::write_property(
+                    &mut output,
+                    Property::RelatedTo,
+                    Some(&related_to.parameters()),
+                    &related_to.value,
+                );
+            }
+        }
+        for (index, resources) in self.resources.iter().enumerate() {
+            self.write_repeated_property(&mut output, Property::Resources, index, resources);
+        }
+        for (index, rdate) in self.rdate.iter().enumerate() {
+            self.write_repeated_date_time(&mut output, Property::RDate, index, rdate);
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        for alarm in &self.alarms {
+            output.push_str(&alarm.write());
+        }
+
+        output.push_str("END:VEVENT\r\n");
+        output
+    }
+
+    fn write_repeated_property(
+        &self,
+        output: &mut String,
+        property: Property,
+        index: usize,
+        value: &str,
+    ) {
+        // The original lines have already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_nth_parameters(&self.parameters, property, index),
+            value,
+        );
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
+    }
+
+    fn write_cal_address(&self, output: &mut String, property: Property, value: &CalAdress) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        // Each address has its own parameters
+        utils::write_property(
+            output,
+            property,
+            Some(&value.parameters()),
+            &value.address.to_string(),
+        );
+    }
+
+    fn write_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_parameters(&self.parameters, property);
+        self.write_property(
+            output,
+            property,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+
+    // Each EXDATE or RDATE line has its own VALUE and TZID
+    fn write_repeated_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        index: usize,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_nth_parameters(&self.parameters, property, index);
+        self.write_repeated_property(
+            output,
+            property,
+            index,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+}
+
+#[cfg(test)]
+fn read_vevent_fixture(path: &str) -> Result<VEvent, ICSError> {
+    let f = File::open(path).unwrap();
+    let mut lines = BufReader::new(f).lines();
+
+    // Skip the calendar up to the BEGIN:VEVENT line
+    for line in lines.by_ref() {
+        if line.unwrap() == "BEGIN:VEVENT" {
+            break;
+        }
+    }
+    VEvent::parse_from_bufreader(&mut lines)
+}
+
+#[test]
+fn vevent_read_example_2() {
+    let vevent = read_vevent_fixture("./tests/test_files/vevent/example2.ics").unwrap();
+
+    assert_eq!(vevent.uid, "283D0EC1-F320-4AC5-B490-6814232F3E77");
+    assert_eq!(
+        vevent.summary,
+        Some("Made using my instance of GO".to_string())
+    );
+    assert_eq!(vevent.status, Some(Status::Confirmed));
+    let local = FixedOffset::east_opt(0).unwrap();
+    assert_eq!(
+        vevent.dtstart,
+        Some(local.with_ymd_and_hms(2022, 7, 22, 8, 0, 0).unwrap())
+    );
+    assert_eq!(
+        vevent.dtend,
+        Some(local.with_ymd_and_hms(2022, 7, 22, 12, 0, 0).unwrap())
+    );
+    assert_eq!(vevent.tzids(), vec!["America/Montreal"]);
+
+    // Local times are written back with their TZID
+    let output = vevent.write();
+    assert!(output.contains("DTEND;TZID=America/Montreal:20220722T120000\r\n"));
+}
+
+#[test]
+fn vevent_read_example_3() {
+    let vevent = read_vevent_fixture("./tests/test_files/vevent/example3.ics").unwrap();
+
+    assert_eq!(vevent.attendee.len(), 2);
+    assert_eq!(
+        vevent.attendee[1].address.to_string(),
+        "mailto:JEEJ@email.com"
+    );
+    assert_eq!(
+        vevent.organizer.as_ref().unwrap().common_name,
+        Some("Organiser Person".to_string())
+    );
+}
+
+#[test]
+fn vevent_missing_properties() {
+    // Neither UID nor DTSTAMP are given
+    assert_eq!(
+        read_vevent_fixture("./tests/test_files/vevent/example4.ics").unwrap_err(),
+        ICSError::MissingNecessaryProperty("UID".to_string())
+    );
+}
+
+#[test]
+fn vevent_end_constraints() {
+    let parse = |content: &str| {
+        VEvent::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+    };
+
+    assert_eq!(
+        parse(
+            "UID:19970610T172345Z-AF23B2@example.com\r\n\
+             DTSTAMP:19970610T172345Z\r\n\
+             DTSTART:19970714T170000Z\r\n\
+             DTEND:19970715T040000Z\r\n\
+             DURATION:PT1H\r\n\
+             END:VEVENT\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    assert_eq!(
+        parse(
+            "UID:19970610T172345Z-AF23B2@example.com\r\n\
+             DTSTAMP:19970610T172345Z\r\n\
+             DTSTART:19970714T170000Z\r\n\
+             DTEND:19970714T160000Z\r\n\
+             END:VEVENT\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DTEND".to_string())
+    );
+    // A VEVENT cannot have the status of a VTODO
+    assert_eq!(
+        parse(
+            "UID:19970610T172345Z-AF23B2@example.com\r\n\
+             DTSTAMP:19970610T172345Z\r\n\
+             STATUS:NEEDS-ACTION\r\n\
+             END:VEVENT\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("STATUS:NEEDS-ACTION".to_string())
+    );
+
+    let vevent = parse(
+        "UID:19970610T172345Z-AF23B2@example.com\r\n\
+         DTSTAMP:19970610T172345Z\r\n\
+         DTSTART:19970714T170000Z\r\n\
+         DURATION:PT1H\r\n\
+         TRANSP:TRANSPARENT\r\n\
+         END:VEVENT\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        vevent.effective_end(),
+        Some(
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .with_ymd_and_hms(1997, 7, 14, 18, 0, 0)
+                .unwrap()
+        )
+    );
+    assert_eq!(vevent.transp, Some(Transp::Transparent));
+}
+
+#[test]
+fn vevent_date_types() {
+    let parse = |exdate: &str| {
+        VEvent::parse_from_bufreader(
+            &mut Cursor::new(
+                format!(
+                    "UID:19970610T172345Z-AF23B2@example.com\r\n\
+                     DTSTAMP:19970610T172345Z\r\n\
+                     DTSTART;VALUE=DATE:19970714\r\n\
+                     RRULE:FREQ=DAILY;COUNT=5\r\n\
+                     {exdate}\r\n\
+                     END:VEVENT\r\n"
+                )
+                .into_bytes(),
+            )
+            .lines(),
+        )
+    };
+
+    assert_eq!(
+        parse("EXDATE:19970715T000000Z").unwrap_err(),
+        ICSError::PropertyConditionNotRespected("EXDATE".to_string())
+    );
+    assert_eq!(
+        parse("RDATE;VALUE=DATE-TIME:19970720T000000Z").unwrap_err(),
+        ICSError::PropertyConditionNotRespected("RDATE".to_string())
+    );
+    assert!(parse("EXDATE;VALUE=DATE:19970715").is_ok());
+    assert!(parse("RDATE;VALUE=DATE:19970720").is_ok());
+}
+
+#[test]
+fn vevent_floating_time() {
+    let content = "UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   DTSTART:19970714T170000\r\n\
+                   DTEND;TZID=America/New_York:19970714T180000\r\n\
+                   END:VEVENT\r\n";
+    let vevent =
+        VEvent::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap();
+
+    let local = |hour| {
+        chrono::NaiveDate::from_ymd_opt(1997, 7, 14)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    };
+    assert_eq!(
+        vevent.time_value(Property::DTStart),
+        Some(DateTimeValue::Local(local(17)))
+    );
+    assert_eq!(
+        vevent.time_value(Property::DTEnd),
+        Some(DateTimeValue::Zoned(
+            "America/New_York".to_string(),
+            local(18)
+        ))
+    );
+    assert!(matches!(
+        vevent.time_value(Property::DTStamp),
+        Some(DateTimeValue::Utc(_))
+    ));
+    assert_eq!(vevent.time_value(Property::Due), None);
+
+    // The floating time is written back without Z
+    assert!(vevent.write().contains("DTSTART:19970714T170000\r\n"));
+}
+
+#[test]
+fn vevent_contact() {
+    let content = "UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   DTSTART:19970714T170000Z\r\n\
+                   CONTACT:Jim Dolittle\\, ABC Industries\\, +1-919-555-1234\r\n\
+                   CONTACT;ALTREP=\"http://example.com/pdi/jdoe.vcf\":John Doe\r\n\
+                   END:VEVENT\r\n";
+    let vevent =
+        VEvent::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap();
+    assert_eq!(
+        vevent.contact,
+        ["Jim Dolittle, ABC Industries, +1-919-555-1234", "John Doe"]
+    );
+
+    // The text is escaped again and each contact keeps its parameters
+    let output = vevent.write();
+    assert!(output.contains("CONTACT:Jim Dolittle\\, ABC Industries\\, +1-919-555-1234\r\n"));
+    assert!(output.contains("CONTACT;ALTREP=\"http://example.com/pdi/jdoe.vcf\":John Doe\r\n"));
+}
+
+#[test]
+fn vevent_write_repeated_parameters() {
+    let content = "UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   DTSTART:19970714T170000Z\r\n\
+                   RDATE;TZID=America/New_York:19970721T130000\r\n\
+                   RDATE:19970728T170000Z\r\n\
+                   EXDATE:19970804T170000Z\r\n\
+                   EXDATE;TZID=America/New_York:19970811T130000\r\n\
+                   RESOURCES;LANGUAGE=fr:PROJECTEUR\r\n\
+                   RESOURCES:EASEL\r\n\
+                   END:VEVENT\r\n";
+    let vevent =
+        VEvent::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap();
+
+    // Each line keeps its own parameters
+    let output = vevent.write();
+    for line in content.lines().skip(3).take(6) {
+        assert!(output.contains(&format!("{line}\r\n")), "{line}");
+    }
+}
+
+#[test]
+fn vevent_swap_geo() {
+    let content = "UID:19970610T172345Z-AF23B2@example.com\r\n\
+                   DTSTAMP:19970610T172345Z\r\n\
+                   GEO:-122.08;37.38\r\n\
+                   END:VEVENT\r\n";
+    let options = ParseOptions {
+        swap_geo: true,
+        ..Default::default()
+    };
+
+    // The swap does not depend on the calendar
+    let vevent = VEvent::parse_from_bufreader_with_options(
+        &mut Cursor::new(content.as_bytes().to_vec()).lines(),
+        &options,
+    )
+    .unwrap();
+    assert_eq!(vevent.geo, Some((37.38, -122.08)));
+    assert_eq!(
+        vevent.warnings,
+        ["GEO of 19970610T172345Z-AF23B2@example.com had its latitude and longitude swapped"]
+    );
 }
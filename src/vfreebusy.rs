@@ -0,0 +1,415 @@
+/*
+       freebusyc  = "BEGIN" ":" "VFREEBUSY" CRLF
+                    fbprop
+                    "END" ":" "VFREEBUSY" CRLF
+
+       fbprop     = *(
+                  ;
+                  ; The following are REQUIRED,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  dtstamp / uid /
+                  ;
+                  ; The following are OPTIONAL,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  contact / dtstart / dtend /
+                  organizer / url /
+                  ;
+                  ; The following are OPTIONAL,
+                  ; and MAY occur more than once.
+                  ;
+                  attendee / comment / freebusy / rstatus / x-prop /
+                  iana-prop
+                  ;
+                  )
+
+   Description:  A "VFREEBUSY" calendar component is a grouping of
+      component properties that represents either a request for free or
+      busy time information, a reply to a request for free or busy time
+      information, or a published set of busy time information.
+*/
+
+use crate::ics_error::ICSError;
+use crate::parse_options::ParseOptions;
+use crate::properties::cal_adress::CalAdress;
+use crate::properties::free_busy::FreeBusy;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::uri::Uri;
+use crate::properties::x_prop::XProp;
+use crate::properties::{Parameters, Property};
+use crate::utils;
+use chrono::{DateTime, FixedOffset, Utc};
+use std::io::{BufRead, Lines};
+
+#[cfg(test)]
+use crate::properties::free_busy::FBType;
+#[cfg(test)]
+use chrono::{Duration, TimeZone};
+#[cfg(test)]
+use std::fs::File;
+#[cfg(test)]
+use std::io::{BufReader, Cursor};
+
+#[derive(Debug, Clone)]
+pub struct VFreeBusy {
+    // Necessary variables
+    pub dtstamp: DateTime<FixedOffset>,
+    pub uid: String,
+
+    // Optional and unique
+    pub contact: Option<String>,
+    pub dtstart: Option<DateTime<FixedOffset>>,
+    pub dtend: Option<DateTime<FixedOffset>>,
+    pub organizer: Option<CalAdress>,
+    pub url: Option<Uri>,
+
+    // Optional and several
+    pub attendee: Vec<CalAdress>,
+    pub comment: Vec<String>,
+    pub freebusy: Vec<FreeBusy>,
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
+}
+
+impl VFreeBusy {
+    pub fn new_empty(dtstamp: DateTime<FixedOffset>, uid: String) -> VFreeBusy {
+        VFreeBusy {
+            dtstamp,
+            uid,
+            contact: None,
+            dtstart: None,
+            dtend: None,
+            organizer: None,
+            url: None,
+            attendee: Vec::new(),
+            comment: Vec::new(),
+            freebusy: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
+        }
+    }
+
+    /// Reads the content of a VFREEBUSY object. The buffer passed should already have consumed the BEGIN:VFREEBUSY.
+    pub fn parse_from_bufreader(
+        line_reader: &mut Lines<impl BufRead>,
+    ) -> Result<VFreeBusy, ICSError> {
+        VFreeBusy::parse_from_bufreader_with_options(line_reader, &ParseOptions::default())
+    }
+
+    /// Reads the content of a VFREEBUSY object with the given options. The buffer passed should already have consumed the BEGIN:VFREEBUSY.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
+    ) -> Result<VFreeBusy, ICSError> {
+        let mut vfreebusy: VFreeBusy = VFreeBusy::new_empty(
+            DateTime::from_utc(
+                Utc::now().naive_utc(),
+                FixedOffset::east_opt(0).expect("FixedOffset::east out of bounds"),
+            ),
+            "".to_string(),
+        );
+        let mut has_uid = false;
+        let mut has_dtstamp = false;
+
+        let mut current_line: Option<Result<String, std::io::Error>> = line_reader.next();
+
+        loop {
+            let line = current_line;
+            let processed_line: String;
+            match line {
+                Some(line) => {
+                    // Read line
+                    processed_line = line?;
+                    // End the process if we have arrived at the end.
+                    if processed_line.starts_with("END:VFREEBUSY") {
+                        break;
+                    }
+                }
+                None => return Err(ICSError::BeginWithoutEnd),
+            }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+
+            // No component can be nested in a VFREEBUSY
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                return Err(ICSError::UnexpectedComponent(component.to_string()));
+            }
+
+            // Here we need to be able to process multi line arguments.
+            let property_string: String;
+            (property_string, current_line) =
+                utils::process_multi_line_property(processed_line, line_reader);
+
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+            if options.preserve_raw {
+                vfreebusy
+                    .raw_properties
+                    .push((property, property_string.clone()));
+            }
+
+            match property {
+                Property::DTStamp => {
+                    if has_dtstamp {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_dtstamp = true;
+                    vfreebusy.dtstamp = value.into();
+                }
+                Property::UID => {
+                    if has_uid {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_uid = true;
+                    vfreebusy.uid = value.into();
+                }
+                Property::Contact => {
+                    utils::apply_unique_property(&mut vfreebusy.contact, value, property_string)?
+                }
+                Property::DTStart => {
+                    utils::apply_unique_property(&mut vfreebusy.dtstart, value, property_string)?
+                }
+                Property::DTEnd => {
+                    utils::apply_unique_property(&mut vfreebusy.dtend, value, property_string)?
+                }
+                Property::Organizer => {
+                    utils::apply_unique_property(&mut vfreebusy.organizer, value, property_string)?
+                }
+                Property::URL => {
+                    utils::apply_unique_property(&mut vfreebusy.url, value, property_string)?
+                }
+                Property::Attendee => vfreebusy.attendee.push(value.into()),
+                Property::Comment => vfreebusy.comment.push(value.into()),
+                Property::FreeBusy => vfreebusy.freebusy.push(value.into()),
+                Property::XProp => vfreebusy.x_properties.push(value.into()),
+                Property::IanaProp => vfreebusy.iana_properties.push(value.into()),
+                _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
+            }
+            // Kept even when empty so that repeated properties can be matched by position
+            vfreebusy.parameters.push((property, parameters));
+        }
+
+        if !has_uid {
+            return Err(ICSError::MissingNecessaryProperty("UID".to_string()));
+        }
+        if !has_dtstamp {
+            return Err(ICSError::MissingNecessaryProperty("DTSTAMP".to_string()));
+        }
+
+        Ok(vfreebusy)
+    }
+
+    /// Writes the VFREEBUSY in the ics format, from BEGIN:VFREEBUSY to END:VFREEBUSY.
+    pub fn write(&self) -> String {
+        let mut output = String::from("BEGIN:VFREEBUSY\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_date_time(&mut output, Property::DTStamp, &self.dtstamp);
+        self.write_property(&mut output, Property::UID, &self.uid);
+
+        if let Some(contact) = &self.contact {
+            self.write_property(&mut output, Property::Contact, &utils::escape_text(contact));
+        }
+        if let Some(dtstart) = &self.dtstart {
+            self.write_date_time(&mut output, Property::DTStart, dtstart);
+        }
+        if let Some(dtend) = &self.dtend {
+            self.write_date_time(&mut output, Property::DTEnd, dtend);
+        }
+        if let Some(organizer) = &self.organizer {
+            self.write_cal_address(&mut output, Property::Organizer, organizer);
+        }
+        if let Some(url) = &self.url {
+            self.write_property(&mut output, Property::URL, &url.to_string());
+        }
+        for attendee in &self.attendee {
+            self.write_cal_address(&mut output, Property::Attendee, attendee);
+        }
+        for comment in &self.comment {
+            self.write_property(&mut output, Property::Comment, &utils::escape_text(comment));
+        }
+        if !utils::has_raw_property(&self.raw_properties, Property::FreeBusy) {
+            // Each line has its own FBTYPE
+            for freebusy in &self.freebusy {
+                utils::write_property(
+                    &mut output,
+                    Property::FreeBusy,
+                    Some(&freebusy.parameters()),
+                    &freebusy.value(),
+                );
+            }
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        output.push_str("END:VFREEBUSY\r\n");
+        output
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
+    }
+
+    fn write_cal_address(&self, output: &mut String, property: Property, value: &CalAdress) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        // Each address has its own parameters
+        utils::write_property(
+            output,
+            property,
+            Some(&value.parameters()),
+            &value.address.to_string(),
+        );
+    }
+
+    fn write_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_parameters(&self.parameters, property);
+        self.write_property(
+            output,
+            property,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+}
+
+#[cfg(test)]
+fn read_vfreebusy_fixture(path: &str) -> Result<VFreeBusy, ICSError> {
+    let f = File::open(path).unwrap();
+    let mut lines = BufReader::new(f).lines();
+
+    // Skip the calendar up to the BEGIN:VFREEBUSY line
+    for line in lines.by_ref() {
+        if line.unwrap() == "BEGIN:VFREEBUSY" {
+            break;
+        }
+    }
+    VFreeBusy::parse_from_bufreader(&mut lines)
+}
+
+#[test]
+fn vfreebusy_read_example_1() {
+    let vfreebusy = read_vfreebusy_fixture("./tests/test_files/vfreebusy/example1.ics").unwrap();
+
+    let utc = FixedOffset::east_opt(0).unwrap();
+    assert_eq!(vfreebusy.uid, "19970901T115957Z-76A912@example.com");
+    assert_eq!(
+        vfreebusy.dtstart,
+        Some(utc.with_ymd_and_hms(1997, 9, 1, 8, 0, 0).unwrap())
+    );
+    assert_eq!(
+        vfreebusy.organizer.as_ref().unwrap().address.to_string(),
+        "mailto:jsmith@example.com"
+    );
+
+    assert_eq!(vfreebusy.freebusy.len(), 3);
+    assert_eq!(vfreebusy.freebusy[0].fbtype, FBType::Busy);
+    assert_eq!(vfreebusy.freebusy[1].fbtype, FBType::Free);
+    assert_eq!(vfreebusy.freebusy[2].periods.len(), 2);
+    let period = &vfreebusy.freebusy[0].periods[0];
+    assert_eq!(
+        period.start(),
+        utc.with_ymd_and_hms(1997, 9, 1, 9, 0, 0).unwrap()
+    );
+    assert_eq!(period.end() - period.start(), Duration::hours(1));
+
+    let output = vfreebusy.write();
+    assert!(output.contains("FREEBUSY;FBTYPE=FREE:19970901T113000Z/PT30M\r\n"));
+}
+
+#[test]
+fn vfreebusy_invalid_periods() {
+    let parse = |content: &str| {
+        VFreeBusy::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+    };
+
+    // Free busy times are always in UTC
+    assert_eq!(
+        parse(
+            "UID:19970901T115957Z-76A912@example.com\r\n\
+             DTSTAMP:19970901T120000Z\r\n\
+             FREEBUSY:19970308T160000/PT8H30M\r\n\
+             END:VFREEBUSY\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("FREEBUSY".to_string())
+    );
+    assert_eq!(
+        parse(
+            "UID:19970901T115957Z-76A912@example.com\r\n\
+             DTSTAMP:19970901T120000Z\r\n\
+             FREEBUSY:19970308T160000Z\r\n\
+             END:VFREEBUSY\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("FREEBUSY".to_string())
+    );
+}
+
+#[test]
+fn vfreebusy_contact() {
+    let parse = |content: &str| {
+        VFreeBusy::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+    };
+
+    let vfreebusy = parse(
+        "UID:19970901T115957Z-76A912@example.com\r\n\
+         DTSTAMP:19970901T120000Z\r\n\
+         CONTACT:Jim Dolittle\\, ABC Industries\r\n\
+         END:VFREEBUSY\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        vfreebusy.contact,
+        Some("Jim Dolittle, ABC Industries".to_string())
+    );
+    assert!(vfreebusy
+        .write()
+        .contains("CONTACT:Jim Dolittle\\, ABC Industries\r\n"));
+
+    // A VFREEBUSY has a single CONTACT
+    assert!(matches!(
+        parse(
+            "UID:19970901T115957Z-76A912@example.com\r\n\
+             DTSTAMP:19970901T120000Z\r\n\
+             CONTACT:Jim Dolittle\r\n\
+             CONTACT:John Doe\r\n\
+             END:VFREEBUSY\r\n"
+        ),
+        Err(ICSError::DuplicateUniqueProperty(_))
+    ));
+}
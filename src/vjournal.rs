@@ -1,11 +1,699 @@
+/*
+       journalc   = "BEGIN" ":" "VJOURNAL" CRLF
+                    jourprop
+                    "END" ":" "VJOURNAL" CRLF
+
+       jourprop   = *(
+                  ;
+                  ; The following are REQUIRED,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  dtstamp / uid /
+                  ;
+                  ; The following are OPTIONAL,
+                  ; but MUST NOT occur more than once.
+                  ;
+                  class / created / dtstart /
+                  last-mod / organizer / recurid / seq /
+                  status / summary / url /
+                  ;
+                  ; The following is OPTIONAL,
+                  ; but SHOULD NOT occur more than once.
+                  ;
+                  rrule /
+                  ;
+                  ; The following are OPTIONAL,
+                  ; and MAY occur more than once.
+                  ;
+                  attach / attendee / categories / comment /
+                  contact / description / exdate / related / rdate /
+                  rstatus / x-prop / iana-prop
+                  ;
+                  )
+
+   Description:  A "VJOURNAL" calendar component is a grouping of
+      component properties that represent one or more descriptive text
+      notes associated with a particular calendar date.  The "DTSTART"
+      property is used to specify the calendar date with which the
+      journal entry is associated.
+*/
+
 use crate::ics_error::ICSError;
+use crate::parse_options::ParseOptions;
+use crate::properties::attachment::Attachment;
+use crate::properties::class::Class;
+use crate::properties::date_time_value::DateTimeValue;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::recur::Recur;
+use crate::properties::related_to::RelatedTo;
+use crate::properties::uri::Uri;
+use crate::properties::x_prop::XProp;
+use crate::properties::{cal_adress::CalAdress, status::Status};
+use crate::properties::{Parameters, Property};
+use crate::utils;
+use crate::vtimezone::{resolve_local_time, VTimezone};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::io::{BufRead, Lines};
+
+#[cfg(test)]
+use chrono::TimeZone;
+#[cfg(test)]
+use std::fs::File;
+#[cfg(test)]
+use std::io::{BufReader, Cursor};
+
+#[derive(Debug, Clone)]
+pub struct VJournal {
+    // Necessary variables
+    pub dtstamp: DateTime<FixedOffset>,
+    pub uid: String,
+
+    // Optional and unique
+    pub class: Option<Class>,
+    pub created: Option<DateTime<FixedOffset>>,
+    pub dtstart: Option<DateTime<FixedOffset>>,
+    pub last_modified: Option<DateTime<FixedOffset>>,
+    pub organizer: Option<CalAdress>,
+    pub recurrence_id: Option<DateTime<FixedOffset>>,
+    pub sequence: Option<usize>,
+    pub status: Option<Status>,
+    pub summary: Option<String>,
+    pub url: Option<Uri>,
+
+    // Optional and should not occur more than once
+    pub rrule: Option<Recur>,
+
+    // Optional and several
+    pub attach: Vec<Attachment>,
+    pub attendee: Vec<CalAdress>,
+    pub categories: Vec<String>,
+    pub comment: Vec<String>,
+    pub contact: Vec<String>,
+    pub description: Vec<String>,
+    pub exdate: Vec<DateTime<FixedOffset>>,
+    pub related_to: Vec<RelatedTo>,
+    pub rdate: Vec<DateTime<FixedOffset>>,
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
+}
 
-#[derive(Debug)]
-pub struct VJournal {}
 impl VJournal {
+    pub fn new_empty(dtstamp: DateTime<FixedOffset>, uid: String) -> VJournal {
+        VJournal {
+            dtstamp,
+            uid,
+            class: None,
+            created: None,
+            dtstart: None,
+            last_modified: None,
+            organizer: None,
+            recurrence_id: None,
+            sequence: None,
+            status: None,
+            summary: None,
+            url: None,
+            rrule: None,
+            attach: Vec::new(),
+            attendee: Vec::new(),
+            categories: Vec::new(),
+            comment: Vec::new(),
+            contact: Vec::new(),
+            description: Vec::new(),
+            exdate: Vec::new(),
+            related_to: Vec::new(),
+            rdate: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
+        }
+    }
+
+    /// Returns the categories of the VJOURNAL separated by commas, such as "FAMILY, FINANCE".
+    pub fn categories_display(&self) -> String {
+        self.categories.join(", ")
+    }
+
+    /// Removes the RDATE occurrences that fall outside of [from, to].
+    /// Returns false if no occurrence of the VJOURNAL, RRULE expanded, is left in the range,
+    /// true if it has no time.
+    pub fn clip_to_range(
+        &mut self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> bool {
+        let start = match self.dtstart {
+            Some(start) => start,
+            None => return true,
+        };
+        let in_range = |date: DateTime<FixedOffset>| date >= from && date <= to;
+
+        self.rdate.retain(|rdate| in_range(*rdate));
+        in_range(start)
+            || !self.rdate.is_empty()
+            || utils::rule_overlaps(
+                self.rrule.as_ref(),
+                self.dtstart,
+                &self.exdate,
+                to,
+                in_range,
+            )
+    }
+
+    /// Lists the time zones referenced by a TZID parameter in this VJOURNAL.
+    pub fn tzids(&self) -> Vec<&str> {
+        let mut tzids: Vec<&str> = Vec::new();
+        for (_, parameters) in &self.parameters {
+            if let Some(tzid) = parameters.get("TZID") {
+                if !tzids.contains(&tzid) {
+                    tzids.push(tzid);
+                }
+            }
+        }
+        tzids
+    }
+
+    /// Returns a time property of the VJOURNAL along with the form it was given in.
+    pub fn time_value(&self, property: Property) -> Option<DateTimeValue> {
+        let value = match property {
+            Property::DTStamp => Some(self.dtstamp),
+            Property::Created => self.created,
+            Property::DTStart => self.dtstart,
+            Property::LastModified => self.last_modified,
+            Property::RecurrenceID => self.recurrence_id,
+            _ => None,
+        }?;
+        Some(DateTimeValue::new(
+            &value,
+            utils::find_parameters(&self.parameters, property),
+        ))
+    }
+
+    /// Places the times given with a TZID in their zone, using the VTIMEZONE of the calendar.
+    pub fn resolve_timezones(&mut self, vtimezones: &[VTimezone]) -> Result<(), ICSError> {
+        if let Some(dtstart) = &mut self.dtstart {
+            let parameters = utils::find_parameters(&self.parameters, Property::DTStart);
+            resolve_local_time(dtstart, parameters, vtimezones)?;
+        }
+        if let Some(recurrence_id) = &mut self.recurrence_id {
+            let parameters = utils::find_parameters(&self.parameters, Property::RecurrenceID);
+            resolve_local_time(recurrence_id, parameters, vtimezones)?;
+        }
+        for (index, exdate) in self.exdate.iter_mut().enumerate() {
+            let parameters = utils::find_nth_parameters(&self.parameters, Property::ExDate, index);
+            resolve_local_time(exdate, parameters, vtimezones)?;
+        }
+        for (index, rdate) in self.rdate.iter_mut().enumerate() {
+            let parameters = utils::find_nth_parameters(&self.parameters, Property::RDate, index);
+            resolve_local_time(rdate, parameters, vtimezones)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the content of a VJOURNAL object. The buffer passed should already have consumed the BEGIN:VJOURNAL.
     pub fn parse_from_bufreader(
-        _line_reader: &mut std::io::Lines<std::io::BufReader<std::fs::File>>,
+        line_reader: &mut Lines<impl BufRead>,
+    ) -> Result<VJournal, ICSError> {
+        VJournal::parse_from_bufreader_with_options(line_reader, &ParseOptions::default())
+    }
+
+    /// Reads the content of a VJOURNAL object with the given options. The buffer passed should already have consumed the BEGIN:VJOURNAL.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
     ) -> Result<VJournal, ICSError> {
-        todo!()
+        let mut vjournal: VJournal = VJournal::new_empty(
+            DateTime::from_utc(
+                Utc::now().naive_utc(),
+                FixedOffset::east_opt(0).expect("FixedOffset::east out of bounds"),
+            ),
+            "".to_string(),
+        );
+        let mut has_uid = false;
+        let mut has_dtstamp = false;
+
+        let mut current_line: Option<Result<String, std::io::Error>> = line_reader.next();
+
+        loop {
+            let line = current_line;
+            let processed_line: String;
+            match line {
+                Some(line) => {
+                    // Read line
+                    processed_line = line?;
+                    // End the process if we have arrived at the end.
+                    if processed_line.starts_with("END:VJOURNAL") {
+                        break;
+                    }
+                }
+                None => return Err(ICSError::BeginWithoutEnd),
+            }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+
+            // No component can be nested in a VJOURNAL
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                return Err(ICSError::UnexpectedComponent(component.to_string()));
+            }
+
+            // Here we need to be able to process multi line arguments.
+            let property_string: String;
+            (property_string, current_line) =
+                utils::process_multi_line_property(processed_line, line_reader);
+
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+            if options.preserve_raw {
+                vjournal
+                    .raw_properties
+                    .push((property, property_string.clone()));
+            }
+
+            match property {
+                Property::DTStamp => {
+                    if has_dtstamp {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_dtstamp = true;
+                    vjournal.dtstamp = value.into();
+                }
+                Property::UID => {
+                    if has_uid {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_uid = true;
+                    vjournal.uid = value.into();
+                }
+                Property::Class => {
+                    utils::apply_unique_property(&mut vjournal.class, value, property_string)?
+                }
+                Property::Created => {
+                    utils::apply_unique_property(&mut vjournal.created, value, property_string)?
+                }
+                Property::DTStart => {
+                    utils::apply_unique_property(&mut vjournal.dtstart, value, property_string)?
+                }
+                Property::LastModified => utils::apply_unique_property(
+                    &mut vjournal.last_modified,
+                    value,
+                    property_string,
+                )?,
+                Property::Organizer => {
+                    utils::apply_unique_property(&mut vjournal.organizer, value, property_string)?
+                }
+                Property::RecurrenceID => utils::apply_unique_property(
+                    &mut vjournal.recurrence_id,
+                    value,
+                    property_string,
+                )?,
+                Property::Sequence => {
+                    utils::apply_unique_property(&mut vjournal.sequence, value, property_string)?
+                }
+                Property::Status => {
+                    if vjournal.status.is_some() {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    let status: Status = value.into();
+                    if !status.validate_vjournal() {
+                        return Err(ICSError::PropertyConditionNotRespected(property_string));
+                    }
+                    vjournal.status = Some(status);
+                }
+                Property::Summary => {
+                    utils::apply_unique_property(&mut vjournal.summary, value, property_string)?
+                }
+                Property::URL => {
+                    utils::apply_unique_property(&mut vjournal.url, value, property_string)?
+                }
+                Property::RRule => {
+                    utils::apply_unique_property(&mut vjournal.rrule, value, property_string)?
+                }
+                Property::Attach => vjournal.attach.push(value.into()),
+                Property::Attendee => vjournal.attendee.push(value.into()),
+                Property::Categories => {
+                    let mut string_vect: Vec<String> = value.into();
+                    vjournal.categories.append(&mut string_vect);
+                }
+                Property::Comment => vjournal.comment.push(value.into()),
+                Property::Contact => vjournal.contact.push(value.into()),
+                Property::Description => vjournal.description.push(value.into()),
+                Property::ExDate => vjournal.exdate.push(value.into()),
+                Property::RelatedTo => vjournal
+                    .related_to
+                    .push(RelatedTo::new(value.into(), &parameters)),
+                Property::RDate => vjournal.rdate.push(value.into()),
+                Property::XProp => vjournal.x_properties.push(value.into()),
+                Property::IanaProp => vjournal.iana_properties.push(value.into()),
+                _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
+            }
+            // Kept even when empty so that repeated properties can be matched by position
+            vjournal.parameters.push((property, parameters));
+        }
+
+        if !has_uid {
+            return Err(ICSError::MissingNecessaryProperty("UID".to_string()));
+        }
+        if !has_dtstamp {
+            return Err(ICSError::MissingNecessaryProperty("DTSTAMP".to_string()));
+        }
+
+        Ok(vjournal)
+    }
+
+    /// Writes the VJOURNAL in the ics format, from BEGIN:VJOURNAL to END:VJOURNAL.
+    pub fn write(&self) -> String {
+        let mut output = String::from("BEGIN:VJOURNAL\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_date_time(&mut output, Property::DTStamp, &self.dtstamp);
+        self.write_property(&mut output, Property::UID, &self.uid);
+
+        if let Some(class) = &self.class {
+            self.write_property(&mut output, Property::Class, &String::from(class.clone()));
+        }
+        if let Some(created) = &self.created {
+            self.write_date_time(&mut output, Property::Created, created);
+        }
+        if let Some(dtstart) = &self.dtstart {
+            self.write_date_time(&mut output, Property::DTStart, dtstart);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            self.write_date_time(&mut output, Property::LastModified, last_modified);
+        }
+        if let Some(organizer) = &self.organizer {
+            self.write_cal_address(&mut output, Property::Organizer, organizer);
+        }
+        if let Some(recurrence_id) = &self.recurrence_id {
+            self.write_date_time(&mut output, Property::RecurrenceID, recurrence_id);
+        }
+        if let Some(sequence) = &self.sequence {
+            self.write_property(&mut output, Property::Sequence, &sequence.to_string());
+        }
+        if let Some(status) = &self.status {
+            self.write_property(&mut output, Property::Status, &String::from(status.clone()));
+        }
+        if let Some(summary) = &self.summary {
+            self.write_property(&mut output, Property::Summary, &utils::escape_text(summary));
+        }
+        if let Some(url) = &self.url {
+            self.write_property(&mut output, Property::URL, &url.to_string());
+        }
+        if let Some(rrule) = &self.rrule {
+            self.write_property(&mut output, Property::RRule, &rrule.to_string());
+        }
+        utils::write_attachments(
+            &mut output,
+            &self.attach,
+            &self.parameters,
+            &self.raw_properties,
+        );
+        for attendee in &self.attendee {
+            self.write_cal_address(&mut output, Property::Attendee, attendee);
+        }
+        if !self.categories.is_empty() {
+            self.write_property(
+                &mut output,
+                Property::Categories,
+                &self
+                    .categories
+                    .iter()
+                    .map(|category| utils::escape_text(category))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        for comment in &self.comment {
+            self.write_property(&mut output, Property::Comment, &utils::escape_text(comment));
+        }
+        for (index, contact) in self.contact.iter().enumerate() {
+            self.write_repeated_property(
+                &mut output,
+                Property::Contact,
+                index,
+                &utils::escape_text(contact),
+            );
+        }
+        for (index, description) in self.description.iter().enumerate() {
+            self.write_repeated_property(
+                &mut output,
+                Property::Description,
+                index,
+                &utils::escape_text(description),
+            );
+        }
+        for (index, exdate) in self.exdate.iter().enumerate() {
+            self.write_repeated_date_time(&mut output, Property::ExDate, index, exdate);
+        }
+        if !utils::has_raw_property(&self.raw_properties, Property::RelatedTo) {
+            // Each relationship has its own RELTYPE
+            for related_to in &self.related_to {
+                utils::write_property(
+                    &mut output,
+                    Property::RelatedTo,
+                    Some(&related_to.parameters()),
+                    &related_to.value,
+                );
+            }
+        }
+        for (index, rdate) in self.rdate.iter().enumerate() {
+            self.write_repeated_date_time(&mut output, Property::RDate, index, rdate);
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        output.push_str("END:VJOURNAL\r\n");
+        output
+    }
+
+    fn write_repeated_property(
+        &self,
+        output: &mut String,
+        property: Property,
+        index: usize,
+        value: &str,
+    ) {
+        // The original lines have already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_nth_parameters(&self.parameters, property, index),
+            value,
+        );
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
+    }
+
+    fn write_cal_address(&self, output: &mut String, property: Property, value: &CalAdress) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        // Each address has its own parameters
+        utils::write_property(
+            output,
+            property,
+            Some(&value.parameters()),
+            &value.address.to_string(),
+        );
+    }
+
+    fn write_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_parameters(&self.parameters, property);
+        self.write_property(
+            output,
+            property,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+
+    // Each EXDATE or RDATE line has its own VALUE and TZID
+    fn write_repeated_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        index: usize,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_nth_parameters(&self.parameters, property, index);
+        self.write_repeated_property(
+            output,
+            property,
+            index,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+}
+
+#[cfg(test)]
+fn read_vjournal_fixture(path: &str) -> Result<VJournal, ICSError> {
+    let f = File::open(path).unwrap();
+    let mut lines = BufReader::new(f).lines();
+
+    // Skip the calendar up to the BEGIN:VJOURNAL line
+    for line in lines.by_ref() {
+        if line.unwrap() == "BEGIN:VJOURNAL" {
+            break;
+        }
+    }
+    VJournal::parse_from_bufreader(&mut lines)
+}
+
+#[test]
+fn vjournal_read_example_1() {
+    let vjournal = read_vjournal_fixture("./tests/test_files/vjournal/example1.ics").unwrap();
+
+    assert_eq!(vjournal.uid, "19970901T130000Z-123405@example.com");
+    assert_eq!(
+        vjournal.dtstamp,
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(1997, 9, 1, 13, 0, 0)
+            .unwrap()
+    );
+    assert_eq!(vjournal.summary, Some("Staff meeting minutes".to_string()));
+    assert_eq!(vjournal.description.len(), 1);
+    assert!(vjournal.description[0].starts_with("1. Staff meeting: Participants include Joe"));
+}
+
+#[test]
+fn vjournal_read_example_2() {
+    let vjournal = read_vjournal_fixture("./tests/test_files/vjournal/example2.ics").unwrap();
+
+    // Each DESCRIPTION is kept along with its own parameters
+    assert_eq!(
+        vjournal.description,
+        vec![
+            "Project review notes".to_string(),
+            "Notes de revue du projet".to_string()
+        ]
+    );
+    assert_eq!(vjournal.status, Some(Status::Final));
+    assert_eq!(vjournal.categories, vec!["Project Report", "XYZ"]);
+
+    let output = vjournal.write();
+    assert!(output.contains("DESCRIPTION:Project review notes\r\n"));
+    assert!(output.contains("DESCRIPTION;LANGUAGE=fr:Notes de revue du projet\r\n"));
+}
+
+#[test]
+fn vjournal_constraints() {
+    let parse = |content: &str| {
+        VJournal::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+    };
+
+    assert_eq!(
+        parse(
+            "DTSTAMP:19970901T130000Z\r\n\
+             DESCRIPTION:No identifier\r\n\
+             END:VJOURNAL\r\n"
+        )
+        .unwrap_err(),
+        ICSError::MissingNecessaryProperty("UID".to_string())
+    );
+    // A VJOURNAL cannot have the status of a VEVENT
+    assert_eq!(
+        parse(
+            "UID:19970901T130000Z-123405@example.com\r\n\
+             DTSTAMP:19970901T130000Z\r\n\
+             STATUS:CONFIRMED\r\n\
+             END:VJOURNAL\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("STATUS:CONFIRMED".to_string())
+    );
+    assert_eq!(
+        parse(
+            "UID:19970901T130000Z-123405@example.com\r\n\
+             DTSTAMP:19970901T130000Z\r\n\
+             SUMMARY:First\r\n\
+             SUMMARY:Second\r\n\
+             END:VJOURNAL\r\n"
+        )
+        .unwrap_err(),
+        ICSError::DuplicateUniqueProperty("SUMMARY:Second".to_string())
+    );
+    // Journals cannot have alarms
+    assert_eq!(
+        parse(
+            "UID:19970901T130000Z-123405@example.com\r\n\
+             DTSTAMP:19970901T130000Z\r\n\
+             BEGIN:VALARM\r\n"
+        )
+        .unwrap_err(),
+        ICSError::UnexpectedComponent("VALARM".to_string())
+    );
+}
+
+#[test]
+fn vjournal_contact() {
+    let content = "UID:19970901T130000Z-123405@example.com\r\n\
+                   DTSTAMP:19970901T130000Z\r\n\
+                   CONTACT:Jim Dolittle\\, ABC Industries\\, +1-919-555-1234\r\n\
+                   END:VJOURNAL\r\n";
+    let vjournal =
+        VJournal::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap();
+    assert_eq!(
+        vjournal.contact,
+        ["Jim Dolittle, ABC Industries, +1-919-555-1234"]
+    );
+    assert!(vjournal
+        .write()
+        .contains("CONTACT:Jim Dolittle\\, ABC Industries\\, +1-919-555-1234\r\n"));
+}
+
+#[test]
+fn vjournal_write_repeated_parameters() {
+    let content = "UID:19970901T130000Z-123405@example.com\r\n\
+                   DTSTAMP:19970901T130000Z\r\n\
+                   DTSTART:19970317T120000Z\r\n\
+                   RDATE;TZID=America/New_York:19970324T080000\r\n\
+                   RDATE:19970331T120000Z\r\n\
+                   EXDATE:19970407T120000Z\r\n\
+                   EXDATE;TZID=America/New_York:19970414T080000\r\n\
+                   END:VJOURNAL\r\n";
+    let vjournal =
+        VJournal::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap();
+
+    // Each line keeps its own parameters
+    let output = vjournal.write();
+    for line in content.lines().skip(3).take(4) {
+        assert!(output.contains(&format!("{line}\r\n")), "{line}");
     }
 }
@@ -0,0 +1,741 @@
+/*
+       timezonec  = "BEGIN" ":" "VTIMEZONE" CRLF
+                    *(
+                    ;
+                    ; 'tzid' is REQUIRED, but MUST NOT occur more
+                    ; than once.
+                    ;
+                    tzid /
+                    ;
+                    ; 'last-mod' and 'tzurl' are OPTIONAL,
+                    ; but MUST NOT occur more than once.
+                    ;
+                    last-mod / tzurl /
+                    ;
+                    ; One of 'standardc' or 'daylightc' MUST occur
+                    ; and each MAY occur more than once.
+                    ;
+                    standardc / daylightc /
+                    ;
+                    ; The following are OPTIONAL,
+                    ; and MAY occur more than once.
+                    ;
+                    x-prop / iana-prop
+                    ;
+                    )
+                    "END" ":" "VTIMEZONE" CRLF
+
+       standardc  = "BEGIN" ":" "STANDARD" CRLF
+                    tzprop
+                    "END" ":" "STANDARD" CRLF
+
+       daylightc  = "BEGIN" ":" "DAYLIGHT" CRLF
+                    tzprop
+                    "END" ":" "DAYLIGHT" CRLF
+
+       tzprop     = *(
+                    ;
+                    ; The following are REQUIRED,
+                    ; but MUST NOT occur more than once.
+                    ;
+                    dtstart / tzoffsetto / tzoffsetfrom /
+                    ;
+                    ; The following is OPTIONAL,
+                    ; but SHOULD NOT occur more than once.
+                    ;
+                    rrule /
+                    ;
+                    ; The following are OPTIONAL,
+                    ; and MAY occur more than once.
+                    ;
+                    comment / rdate / tzname / x-prop / iana-prop
+                    ;
+                    )
+
+   Description:  A time zone is unambiguously defined by the set of time
+      measurement rules determined by the governing body for a given
+      geographic area.  These rules describe, at a minimum, the base
+      offset from UTC for the time zone, often referred to as the
+      Standard Time offset.  Many locations adjust their Standard Time
+      forward or backward by one hour, in order to accommodate seasonal
+      changes in number of daylight hours, often referred to as Daylight
+      Saving Time.
+*/
+
+use crate::ics_error::ICSError;
+use crate::parse_options::ParseOptions;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::recur::Recur;
+use crate::properties::uri::Uri;
+use crate::properties::utc_offset::format_utc_offset;
+use crate::properties::x_prop::XProp;
+use crate::properties::{Parameters, Property};
+use crate::utils;
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use std::io::{BufRead, Lines};
+
+#[cfg(test)]
+use chrono::NaiveDate;
+#[cfg(test)]
+use std::fs::File;
+#[cfg(test)]
+use std::io::{BufReader, Cursor};
+
+/// A period of a time zone with the same offset, given by a STANDARD or a DAYLIGHT component.
+#[derive(Debug, Clone)]
+pub struct Observance {
+    // Necessary variables
+    // The first onset of the observance, a local time in the offset that precedes it
+    pub dtstart: NaiveDateTime,
+    pub tzoffsetfrom: FixedOffset,
+    pub tzoffsetto: FixedOffset,
+
+    // Optional and should not occur more than once
+    pub rrule: Option<Recur>,
+
+    // Optional and several
+    pub comment: Vec<String>,
+    pub rdate: Vec<NaiveDateTime>,
+    pub tzname: Vec<String>,
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VTimezone {
+    // Necessary variables
+    pub tzid: String,
+
+    // Optional and unique
+    pub last_modified: Option<DateTime<FixedOffset>>,
+    pub tzurl: Option<Uri>,
+
+    // At least one observance, of either kind
+    pub standard: Vec<Observance>,
+    pub daylight: Vec<Observance>,
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
+}
+
+impl Observance {
+    pub fn new(
+        dtstart: NaiveDateTime,
+        tzoffsetfrom: FixedOffset,
+        tzoffsetto: FixedOffset,
+    ) -> Observance {
+        Observance {
+            dtstart,
+            tzoffsetfrom,
+            tzoffsetto,
+            rrule: None,
+            comment: Vec::new(),
+            rdate: Vec::new(),
+            tzname: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
+        }
+    }
+
+    /// Returns the last onset of the observance at or before a local time, if it has started.
+    pub fn last_onset(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.dtstart > local {
+            return None;
+        }
+        // The rule is expanded on the local times, written as if they were UTC
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let from_rule = match &self.rrule {
+            Some(rrule) => rrule
+                .occurrences(utc.from_utc_datetime(&self.dtstart))
+                .map(|onset| onset.naive_utc())
+                .take_while(|onset| *onset <= local)
+                .last(),
+            None => Some(self.dtstart),
+        };
+        self.rdate
+            .iter()
+            .copied()
+            .filter(|onset| *onset <= local)
+            .chain(from_rule)
+            .max()
+    }
+
+    /// Reads the content of a STANDARD or DAYLIGHT component, given as `name`, with the given options.
+    /// The buffer passed should already have consumed the BEGIN line.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
+        name: &str,
+    ) -> Result<Observance, ICSError> {
+        let end_line = format!("END:{name}");
+        let mut observance = Observance::new(
+            NaiveDateTime::default(),
+            FixedOffset::east_opt(0).expect("FixedOffset::east out of bounds"),
+            FixedOffset::east_opt(0).expect("FixedOffset::east out of bounds"),
+        );
+        let mut has_dtstart = false;
+        let mut has_tzoffsetfrom = false;
+        let mut has_tzoffsetto = false;
+
+        let mut current_line: Option<Result<String, std::io::Error>> = line_reader.next();
+
+        loop {
+            let line = current_line;
+            let processed_line: String;
+            match line {
+                Some(line) => {
+                    // Read line
+                    processed_line = line?;
+                    // End the process if we have arrived at the end.
+                    if processed_line.starts_with(&end_line) {
+                        break;
+                    }
+                }
+                None => return Err(ICSError::BeginWithoutEnd),
+            }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+
+            // No component can be nested in an observance
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                return Err(ICSError::UnexpectedComponent(component.to_string()));
+            }
+
+            // Here we need to be able to process multi line arguments.
+            let property_string: String;
+            (property_string, current_line) =
+                utils::process_multi_line_property(processed_line, line_reader);
+
+            // DTSTART and RDATE are local times, which the time properties parser does not read
+            if let Some(value) = property_string.strip_prefix("DTSTART:") {
+                if has_dtstart {
+                    return Err(ICSError::DuplicateUniqueProperty(property_string));
+                }
+                has_dtstart = true;
+                observance.dtstart = parse_local_time(value, &property_string)?;
+                observance.keep_raw(Property::DTStart, property_string, options);
+                continue;
+            }
+            if let Some(values) = property_string.strip_prefix("RDATE:") {
+                for value in values.split(',') {
+                    observance
+                        .rdate
+                        .push(parse_local_time(value, &property_string)?);
+                }
+                observance.keep_raw(Property::RDate, property_string, options);
+                continue;
+            }
+
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+
+            match property {
+                Property::TZOffsetFrom => {
+                    if has_tzoffsetfrom {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_tzoffsetfrom = true;
+                    observance.tzoffsetfrom = value.into();
+                }
+                Property::TZOffsetTo => {
+                    if has_tzoffsetto {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_tzoffsetto = true;
+                    observance.tzoffsetto = value.into();
+                }
+                Property::RRule => utils::apply_unique_property(
+                    &mut observance.rrule,
+                    value,
+                    property_string.clone(),
+                )?,
+                Property::Comment => observance.comment.push(value.into()),
+                Property::TZName => observance.tzname.push(value.into()),
+                Property::XProp => observance.x_properties.push(value.into()),
+                Property::IanaProp => observance.iana_properties.push(value.into()),
+                _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
+            }
+            observance.keep_raw(property, property_string, options);
+            // Kept even when empty so that repeated properties can be matched by position
+            observance.parameters.push((property, parameters));
+        }
+
+        if !has_dtstart {
+            return Err(ICSError::MissingNecessaryProperty("DTSTART".to_string()));
+        }
+        if !has_tzoffsetfrom {
+            return Err(ICSError::MissingNecessaryProperty(
+                "TZOFFSETFROM".to_string(),
+            ));
+        }
+        if !has_tzoffsetto {
+            return Err(ICSError::MissingNecessaryProperty("TZOFFSETTO".to_string()));
+        }
+
+        Ok(observance)
+    }
+
+    fn keep_raw(&mut self, property: Property, property_string: String, options: &ParseOptions) {
+        if options.preserve_raw {
+            self.raw_properties.push((property, property_string));
+        }
+    }
+
+    /// Writes the observance in the ics format, from BEGIN to END with the given component name.
+    pub fn write(&self, name: &str) -> String {
+        let mut output = format!("BEGIN:{name}\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_property(
+            &mut output,
+            Property::DTStart,
+            &self.dtstart.format("%Y%m%dT%H%M%S").to_string(),
+        );
+        self.write_property(
+            &mut output,
+            Property::TZOffsetFrom,
+            &format_utc_offset(&self.tzoffsetfrom),
+        );
+        self.write_property(
+            &mut output,
+            Property::TZOffsetTo,
+            &format_utc_offset(&self.tzoffsetto),
+        );
+        if let Some(rrule) = &self.rrule {
+            self.write_property(&mut output, Property::RRule, &rrule.to_string());
+        }
+        for comment in &self.comment {
+            self.write_property(&mut output, Property::Comment, &utils::escape_text(comment));
+        }
+        for rdate in &self.rdate {
+            self.write_property(
+                &mut output,
+                Property::RDate,
+                &rdate.format("%Y%m%dT%H%M%S").to_string(),
+            );
+        }
+        for (index, tzname) in self.tzname.iter().enumerate() {
+            // The original lines have already been written
+            if !utils::has_raw_property(&self.raw_properties, Property::TZName) {
+                utils::write_property(
+                    &mut output,
+                    Property::TZName,
+                    utils::find_nth_parameters(&self.parameters, Property::TZName, index),
+                    &utils::escape_text(tzname),
+                );
+            }
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        output.push_str(&format!("END:{name}\r\n"));
+        output
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
+    }
+}
+
+impl VTimezone {
+    pub fn new_empty(tzid: String) -> VTimezone {
+        VTimezone {
+            tzid,
+            last_modified: None,
+            tzurl: None,
+            standard: Vec::new(),
+            daylight: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
+        }
+    }
+
+    /// Returns the UTC offset in effect at a local time of the zone.
+    /// Before the first onset, the offset that precedes the earliest observance is used.
+    pub fn offset_at(&self, local: NaiveDateTime) -> Option<FixedOffset> {
+        let observances = || self.standard.iter().chain(self.daylight.iter());
+        observances()
+            .filter_map(|observance| {
+                observance
+                    .last_onset(local)
+                    .map(|onset| (onset, observance.tzoffsetto))
+            })
+            .max_by_key(|(onset, _)| *onset)
+            .map(|(_, offset)| offset)
+            .or_else(|| {
+                observances()
+                    .min_by_key(|observance| observance.dtstart)
+                    .map(|observance| observance.tzoffsetfrom)
+            })
+    }
+
+    /// Reads the content of a VTIMEZONE object. The buffer passed should already have consumed the BEGIN:VTIMEZONE.
+    pub fn parse_from_bufreader(
+        line_reader: &mut Lines<impl BufRead>,
+    ) -> Result<VTimezone, ICSError> {
+        VTimezone::parse_from_bufreader_with_options(line_reader, &ParseOptions::default())
+    }
+
+    /// Reads the content of a VTIMEZONE object with the given options. The buffer passed should already have consumed the BEGIN:VTIMEZONE.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
+    ) -> Result<VTimezone, ICSError> {
+        let mut vtimezone = VTimezone::new_empty("".to_string());
+        let mut has_tzid = false;
+
+        let mut current_line: Option<Result<String, std::io::Error>> = line_reader.next();
+
+        loop {
+            let line = current_line;
+            let processed_line: String;
+            match line {
+                Some(line) => {
+                    // Read line
+                    processed_line = line?;
+                    // End the process if we have arrived at the end.
+                    if processed_line.starts_with("END:VTIMEZONE") {
+                        break;
+                    }
+                }
+                None => return Err(ICSError::BeginWithoutEnd),
+            }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                match component {
+                    "STANDARD" => {
+                        vtimezone
+                            .standard
+                            .push(Observance::parse_from_bufreader_with_options(
+                                line_reader,
+                                options,
+                                component,
+                            )?)
+                    }
+                    "DAYLIGHT" => {
+                        vtimezone
+                            .daylight
+                            .push(Observance::parse_from_bufreader_with_options(
+                                line_reader,
+                                options,
+                                component,
+                            )?)
+                    }
+                    _ => return Err(ICSError::UnexpectedComponent(component.to_string())),
+                }
+
+                // Consume next line as we have finished the observance
+                current_line = line_reader.next();
+                continue;
+            }
+
+            // Here we need to be able to process multi line arguments.
+            let property_string: String;
+            (property_string, current_line) =
+                utils::process_multi_line_property(processed_line, line_reader);
+
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+            if options.preserve_raw {
+                vtimezone
+                    .raw_properties
+                    .push((property, property_string.clone()));
+            }
+
+            match property {
+                Property::TZID => {
+                    if has_tzid {
+                        return Err(ICSError::DuplicateUniqueProperty(property_string));
+                    }
+                    has_tzid = true;
+                    vtimezone.tzid = value.into();
+                }
+                Property::LastModified => utils::apply_unique_property(
+                    &mut vtimezone.last_modified,
+                    value,
+                    property_string,
+                )?,
+                Property::TZURL => {
+                    utils::apply_unique_property(&mut vtimezone.tzurl, value, property_string)?
+                }
+                Property::XProp => vtimezone.x_properties.push(value.into()),
+                Property::IanaProp => vtimezone.iana_properties.push(value.into()),
+                _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
+            }
+            vtimezone.parameters.push((property, parameters));
+        }
+
+        if !has_tzid {
+            return Err(ICSError::MissingNecessaryProperty("TZID".to_string()));
+        }
+        if vtimezone.standard.is_empty() && vtimezone.daylight.is_empty() {
+            return Err(ICSError::MissingNecessaryProperty(
+                "STANDARD, DAYLIGHT".to_string(),
+            ));
+        }
+
+        Ok(vtimezone)
+    }
+
+    /// Writes the VTIMEZONE in the ics format, from BEGIN:VTIMEZONE to END:VTIMEZONE.
+    pub fn write(&self) -> String {
+        let mut output = String::from("BEGIN:VTIMEZONE\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_property(&mut output, Property::TZID, &self.tzid);
+        if let Some(last_modified) = &self.last_modified {
+            let parameters = utils::find_parameters(&self.parameters, Property::LastModified);
+            self.write_property(
+                &mut output,
+                Property::LastModified,
+                &utils::format_date_time(last_modified, parameters),
+            );
+        }
+        if let Some(tzurl) = &self.tzurl {
+            self.write_property(&mut output, Property::TZURL, &tzurl.to_string());
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        for standard in &self.standard {
+            output.push_str(&standard.write("STANDARD"));
+        }
+        for daylight in &self.daylight {
+            output.push_str(&daylight.write("DAYLIGHT"));
+        }
+
+        output.push_str("END:VTIMEZONE\r\n");
+        output
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
+    }
+}
+
+/// Parses a local date-time, without a trailing Z, as used by the onsets of an observance.
+fn parse_local_time(value: &str, property_string: &str) -> Result<NaiveDateTime, ICSError> {
+    match NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        Ok(date_time) => Ok(date_time),
+        Err(_) => Err(ICSError::PropertyConditionNotRespected(
+            property_string.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+fn read_vtimezone_fixture(path: &str) -> Result<VTimezone, ICSError> {
+    let f = File::open(path).unwrap();
+    let mut lines = BufReader::new(f).lines();
+
+    // Skip the calendar up to the BEGIN:VTIMEZONE line
+    for line in lines.by_ref() {
+        if line.unwrap() == "BEGIN:VTIMEZONE" {
+            break;
+        }
+    }
+    VTimezone::parse_from_bufreader(&mut lines)
+}
+
+/// Places a time read with a TZID parameter in its zone, the time was read as a local time at +00:00.
+/// The zone is looked up in the VTIMEZONE of the calendar first, then in the IANA time zone names.
+pub fn resolve_local_time(
+    value: &mut DateTime<FixedOffset>,
+    parameters: Option<&Parameters>,
+    vtimezones: &[VTimezone],
+) -> Result<(), ICSError> {
+    let tzid = match parameters.and_then(|parameters| parameters.get("TZID")) {
+        Some(tzid) => tzid,
+        None => return Ok(()),
+    };
+    // A date has no time to place in a zone
+    if parameters.and_then(|parameters| parameters.get("VALUE")) == Some("DATE") {
+        return Ok(());
+    }
+
+    let local = value.naive_local();
+    let offset = match vtimezones.iter().find(|vtimezone| vtimezone.tzid == tzid) {
+        Some(vtimezone) => vtimezone.offset_at(local),
+        None => tzid.parse::<Tz>().ok().and_then(|timezone| {
+            timezone
+                .offset_from_local_datetime(&local)
+                .earliest()
+                .map(|offset| offset.fix())
+        }),
+    };
+    match offset.and_then(|offset| offset.from_local_datetime(&local).single()) {
+        Some(resolved) => {
+            *value = resolved;
+            Ok(())
+        }
+        None => Err(ICSError::UnknownTimezone(tzid.to_string())),
+    }
+}
+
+#[test]
+fn vtimezone_read_example() {
+    let vtimezone = read_vtimezone_fixture("./tests/test_files/vevent/example3.ics").unwrap();
+
+    assert_eq!(vtimezone.tzid, "America/Montreal");
+    assert_eq!(vtimezone.standard.len(), 1);
+    assert_eq!(vtimezone.daylight.len(), 1);
+
+    let standard = &vtimezone.standard[0];
+    assert_eq!(
+        standard.dtstart,
+        NaiveDate::from_ymd_opt(1970, 11, 1)
+            .unwrap()
+            .and_hms_opt(4, 0, 0)
+            .unwrap()
+    );
+    assert_eq!(
+        standard.tzoffsetfrom,
+        FixedOffset::west_opt(4 * 3600).unwrap()
+    );
+    assert_eq!(
+        standard.tzoffsetto,
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    );
+    assert_eq!(
+        standard.rrule.as_ref().unwrap().to_string(),
+        "FREQ=YEARLY;BYDAY=1SU;BYMONTH=11"
+    );
+
+    let output = vtimezone.write();
+    assert!(output.contains(
+        "BEGIN:DAYLIGHT\r\n\
+         DTSTART:19700308T050000\r\n\
+         TZOFFSETFROM:-0500\r\n\
+         TZOFFSETTO:-0400\r\n"
+    ));
+}
+
+#[test]
+fn vtimezone_missing_properties() {
+    let parse = |content: &str| {
+        VTimezone::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+    };
+
+    assert_eq!(
+        parse(
+            "TZID:Europe/Paris\r\n\
+             END:VTIMEZONE\r\n"
+        )
+        .unwrap_err(),
+        ICSError::MissingNecessaryProperty("STANDARD, DAYLIGHT".to_string())
+    );
+    assert_eq!(
+        parse(
+            "TZID:Europe/Paris\r\n\
+             BEGIN:STANDARD\r\n\
+             DTSTART:19961027T030000\r\n\
+             TZOFFSETFROM:+0200\r\n\
+             END:STANDARD\r\n\
+             END:VTIMEZONE\r\n"
+        )
+        .unwrap_err(),
+        ICSError::MissingNecessaryProperty("TZOFFSETTO".to_string())
+    );
+    // The onset of an observance is a local time
+    assert_eq!(
+        parse(
+            "TZID:Europe/Paris\r\n\
+             BEGIN:STANDARD\r\n\
+             DTSTART:19961027T030000Z\r\n"
+        )
+        .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DTSTART:19961027T030000Z".to_string())
+    );
+    assert_eq!(
+        parse(
+            "TZID:Europe/Paris\r\n\
+             BEGIN:VEVENT\r\n"
+        )
+        .unwrap_err(),
+        ICSError::UnexpectedComponent("VEVENT".to_string())
+    );
+}
+
+#[test]
+fn vtimezone_offset_at() {
+    let vtimezone = read_vtimezone_fixture("./tests/test_files/vevent/example3.ics").unwrap();
+    let local = |year, month, day, hour| {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    };
+    let offset = |hours| FixedOffset::east_opt(hours * 3600).unwrap();
+
+    assert_eq!(
+        vtimezone.offset_at(local(2022, 9, 14, 16)),
+        Some(offset(-4))
+    );
+    assert_eq!(vtimezone.offset_at(local(2022, 12, 1, 9)), Some(offset(-5)));
+    // The second sunday of march 2022 is the 13th
+    assert_eq!(vtimezone.offset_at(local(2022, 3, 13, 4)), Some(offset(-5)));
+    assert_eq!(vtimezone.offset_at(local(2022, 3, 13, 6)), Some(offset(-4)));
+    // Before the first onset, the offset preceding it is used
+    assert_eq!(vtimezone.offset_at(local(1960, 1, 1, 0)), Some(offset(-5)));
+
+    assert_eq!(
+        VTimezone::new_empty("Empty".to_string()).offset_at(local(2022, 1, 1, 0)),
+        None
+    );
+}
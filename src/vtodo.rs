@@ -63,22 +63,44 @@ Purpose:  Provide a grouping of calendar properties that describe a
       with each successive calendar date, until it is completed.
 */
 
+use crate::component::{Component, FieldChange};
 use crate::ics_error::ICSError;
+use crate::parse_options::ParseOptions;
+use crate::properties::action::Action;
+use crate::properties::attachment::Attachment;
 use crate::properties::class::Class;
+use crate::properties::date_time_value::DateTimeValue;
+use crate::properties::iana_prop::IanaProp;
+use crate::properties::recur::Recur;
+use crate::properties::related_to::RelatedTo;
+use crate::properties::trigger::Trigger;
 use crate::properties::uri::Uri;
-use crate::properties::Property;
+use crate::properties::x_prop::XProp;
 use crate::properties::{cal_adress::CalAdress, status::Status};
+use crate::properties::{validate_integer, Parameters, Property};
 use crate::utils;
+use crate::valarm::VAlarm;
+use crate::vcalendar::VCalendar;
+use crate::vtimezone::{resolve_local_time, VTimezone};
 use chrono::{DateTime, Duration, FixedOffset, Utc};
-use std::fs::File;
-use std::io::{BufReader, Lines};
+use std::io::{BufRead, Lines};
 
+#[cfg(test)]
+use crate::properties::cal_adress::PartStat;
+#[cfg(test)]
+use crate::properties::recur::Frequency;
+#[cfg(test)]
+use crate::properties::trigger::Related;
 #[cfg(test)]
 use chrono::TimeZone;
 #[cfg(test)]
-use std::io::BufRead;
+use std::fs;
+#[cfg(test)]
+use std::fs::File;
+#[cfg(test)]
+use std::io::{BufReader, Cursor};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VTodo {
     // Necessary variables
     pub dtstamp: DateTime<FixedOffset>,
@@ -102,23 +124,42 @@ pub struct VTodo {
     pub summary: Option<String>,
     pub url: Option<Uri>,
 
+    // Optional and should not occur more than once
+    pub rrule: Option<Recur>,
+
     // Optional and conditional
     pub due: Option<DateTime<FixedOffset>>,
     pub duration: Option<Duration>,
 
     // Optional and several
-    pub attach: Vec<Uri>,
+    pub attach: Vec<Attachment>,
     pub attendee: Vec<CalAdress>,
     pub categories: Vec<String>,
     pub comment: Vec<String>,
-    pub contact: Vec<CalAdress>,
+    pub contact: Vec<String>,
     pub exdate: Vec<DateTime<FixedOffset>>,
     // rstatus: Vec<String> // Seems to be a request answer so I wont be putting it in for now.
-    pub related_to: Vec<String>,
+    pub related_to: Vec<RelatedTo>,
     pub resources: Vec<String>,
     pub rdate: Vec<DateTime<FixedOffset>>,
-    // x_prop: Will be implemented later
-    // iana_prop: Will be implemented later
+
+    // Alarms nested in the VTODO
+    pub alarms: Vec<VAlarm>,
+
+    // Non-standard properties, such as X-MOZ-GENERATION
+    pub x_properties: Vec<XProp>,
+    // Registered properties the crate does not know, only kept when the parse is not strict
+    pub iana_properties: Vec<IanaProp>,
+
+    // Parameters given to the properties above, in the order they were read
+    pub parameters: Vec<(Property, Parameters)>,
+
+    // Original lines of the properties when parsed with ParseOptions::preserve_raw.
+    // They are written instead of the values of the matching properties.
+    pub raw_properties: Vec<(Property, String)>,
+
+    // Values fixed while parsing, such as a GEO swapped with ParseOptions::swap_geo
+    pub warnings: Vec<String>,
 }
 
 impl VTodo {
@@ -142,6 +183,7 @@ impl VTodo {
             status: None,
             summary: None,
             url: None,
+            rrule: None,
             due: None,
             duration: None,
             attach: Vec::new(),
@@ -153,12 +195,435 @@ impl VTodo {
             related_to: Vec::new(),
             resources: Vec::new(),
             rdate: Vec::new(),
+            alarms: Vec::new(),
+            x_properties: Vec::new(),
+            iana_properties: Vec::new(),
+            parameters: Vec::new(),
+            raw_properties: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
+    /// Adds an alarm to the VTODO.
+    pub fn with_alarm(mut self, alarm: VAlarm) -> VTodo {
+        self.alarms.push(alarm);
+        self
+    }
+
+    /// Adds a DISPLAY alarm at the given trigger, the SUMMARY of the VTODO is used as its text.
+    pub fn display_alarm(self, trigger: Trigger) -> VTodo {
+        let mut alarm = VAlarm::new_empty(Action::Display, trigger);
+        // A DISPLAY alarm requires a DESCRIPTION
+        alarm.description = Some(
+            self.summary
+                .clone()
+                .unwrap_or_else(|| "Reminder".to_string()),
+        );
+        self.with_alarm(alarm)
+    }
+
+    /// Returns the DUE of the VTODO, or the end of its DURATION from DTSTART.
+    pub fn effective_due(&self) -> Option<DateTime<FixedOffset>> {
+        match (self.due, self.dtstart, self.duration) {
+            (Some(due), _, _) => Some(due),
+            (None, Some(dtstart), Some(duration)) => Some(dtstart + duration),
+            _ => None,
+        }
+    }
+
+    /// Returns the DURATION of the VTODO, or the time between its DTSTART and DUE.
+    pub fn effective_duration(&self) -> Option<Duration> {
+        match (self.duration, self.dtstart, self.due) {
+            (Some(duration), _, _) => Some(duration),
+            (None, Some(dtstart), Some(due)) => Some(due - dtstart),
+            _ => None,
+        }
+    }
+
+    /// Returns the PERCENT-COMPLETE of the VTODO. Without one, a completed VTODO is at 100
+    /// and any other at 0.
+    pub fn effective_percent(&self) -> u8 {
+        match (self.percent, &self.status) {
+            (Some(percent), _) => percent.min(100) as u8,
+            (None, Some(Status::Completed)) => 100,
+            (None, _) => 0,
+        }
+    }
+
+    /// Lists the properties that differ in the other version of the VTODO.
+    /// DTSTAMP is left out as it changes every time the VTODO is exported, and so are the alarms.
+    pub fn diff(&self, other: &VTodo) -> Vec<FieldChange> {
+        [
+            FieldChange::compare(Property::UID, Some(&self.uid), Some(&other.uid)),
+            FieldChange::compare(Property::Class, self.class.as_ref(), other.class.as_ref()),
+            FieldChange::compare(
+                Property::Completed,
+                self.completed.as_ref(),
+                other.completed.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Created,
+                self.created.as_ref(),
+                other.created.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Description,
+                self.description.as_ref(),
+                other.description.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::DTStart,
+                self.dtstart.as_ref(),
+                other.dtstart.as_ref(),
+            ),
+            FieldChange::compare(Property::Geo, self.geo.as_ref(), other.geo.as_ref()),
+            FieldChange::compare(
+                Property::LastModified,
+                self.last_modified.as_ref(),
+                other.last_modified.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Location,
+                self.location.as_ref(),
+                other.location.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Organizer,
+                self.organizer.as_ref(),
+                other.organizer.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::PercentComplete,
+                self.percent.as_ref(),
+                other.percent.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Priority,
+                self.priority.as_ref(),
+                other.priority.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::RecurrenceID,
+                self.recurrence_id.as_ref(),
+                other.recurrence_id.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Sequence,
+                self.sequence.as_ref(),
+                other.sequence.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Status,
+                self.status.as_ref(),
+                other.status.as_ref(),
+            ),
+            FieldChange::compare(
+                Property::Summary,
+                self.summary.as_ref(),
+                other.summary.as_ref(),
+            ),
+            FieldChange::compare(Property::URL, self.url.as_ref(), other.url.as_ref()),
+            FieldChange::compare(Property::RRule, self.rrule.as_ref(), other.rrule.as_ref()),
+            FieldChange::compare(Property::Due, self.due.as_ref(), other.due.as_ref()),
+            FieldChange::compare(
+                Property::Duration,
+                self.duration.as_ref(),
+                other.duration.as_ref(),
+            ),
+            FieldChange::compare_list(Property::Attach, &self.attach, &other.attach),
+            FieldChange::compare_list(Property::Attendee, &self.attendee, &other.attendee),
+            FieldChange::compare_list(Property::Categories, &self.categories, &other.categories),
+            FieldChange::compare_list(Property::Comment, &self.comment, &other.comment),
+            FieldChange::compare_list(Property::Contact, &self.contact, &other.contact),
+            FieldChange::compare_list(Property::ExDate, &self.exdate, &other.exdate),
+            FieldChange::compare_list(Property::RelatedTo, &self.related_to, &other.related_to),
+            FieldChange::compare_list(Property::Resources, &self.resources, &other.resources),
+            FieldChange::compare_list(Property::RDate, &self.rdate, &other.rdate),
+            FieldChange::compare_list(Property::XProp, &self.x_properties, &other.x_properties),
+            FieldChange::compare_list(
+                Property::IanaProp,
+                &self.iana_properties,
+                &other.iana_properties,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Returns the categories of the VTODO separated by commas, such as "FAMILY, FINANCE".
+    pub fn categories_display(&self) -> String {
+        self.categories.join(", ")
+    }
+
+    /// Sorts the categories gathered from all the CATEGORIES lines and removes the duplicates.
+    /// When case_insensitive is set, "Work" and "WORK" are the same and the first one is kept.
+    pub fn normalize_categories(&mut self, case_insensitive: bool) {
+        let key = |category: &String| {
+            if case_insensitive {
+                category.to_lowercase()
+            } else {
+                category.clone()
+            }
+        };
+        let mut seen = std::collections::HashSet::new();
+        self.categories
+            .retain(|category| seen.insert(key(category)));
+        self.categories.sort_by_key(key);
+    }
+
+    /// Removes the RDATE occurrences that fall entirely outside of [from, to].
+    /// Returns false if no occurrence of the VTODO, RRULE expanded, is left in the range,
+    /// true if it has no time.
+    pub fn clip_to_range(
+        &mut self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> bool {
+        let start = match self.dtstart.or(self.effective_due()) {
+            Some(start) => start,
+            None => return true,
+        };
+        let length = self
+            .effective_due()
+            .map_or(Duration::zero(), |due| due - start);
+        let overlaps = |start: DateTime<FixedOffset>| start <= to && start + length >= from;
+
+        self.rdate.retain(|rdate| overlaps(*rdate));
+        overlaps(start)
+            || !self.rdate.is_empty()
+            || utils::rule_overlaps(
+                self.rrule.as_ref(),
+                self.dtstart,
+                &self.exdate,
+                to,
+                overlaps,
+            )
+    }
+
+    /// Lists the first `limit` start times of the VTODO at or after `after`, in chronological order.
+    /// They come from DTSTART expanded by RRULE and from RDATE, the EXDATE are skipped.
+    pub fn occurrences(
+        &self,
+        after: DateTime<FixedOffset>,
+        limit: usize,
+    ) -> Vec<DateTime<FixedOffset>> {
+        let mut occurrences: Vec<DateTime<FixedOffset>> = self
+            .rdate
+            .iter()
+            .copied()
+            .filter(|occurrence| *occurrence >= after && !self.exdate.contains(occurrence))
+            .collect();
+
+        let keep = |occurrence: &DateTime<FixedOffset>| {
+            *occurrence >= after
+                && !self.exdate.contains(occurrence)
+                && !self.rdate.contains(occurrence)
+        };
+        let from_rule: Vec<DateTime<FixedOffset>> = match (&self.rrule, self.dtstart) {
+            (Some(rrule), Some(dtstart)) => rrule
+                .occurrences(dtstart)
+                .filter(keep)
+                .take(limit)
+                .collect(),
+            (None, Some(dtstart)) => std::iter::once(dtstart).filter(keep).collect(),
+            (_, None) => Vec::new(),
+        };
+
+        occurrences.extend(from_rule);
+        occurrences.sort();
+        occurrences.dedup();
+        occurrences.truncate(limit);
+        occurrences
+    }
+
+    /// Lists the concrete instances of the VTODO overlapping [from, to], in chronological order.
+    /// The occurrences come from DTSTART, RRULE and RDATE minus EXDATE, an override with the matching
+    /// RECURRENCE-ID replaces its occurrence, even one moved into the range from after it.
+    /// A VTODO without time is its only instance.
+    pub fn materialize(
+        &self,
+        overrides: &[VTodo],
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Vec<VTodo> {
+        let start = match self.dtstart.or(self.effective_due()) {
+            Some(start) => start,
+            None => return vec![self.clone()],
+        };
+
+        let mut occurrences = match (&self.rrule, self.dtstart) {
+            (Some(rrule), Some(dtstart)) => rrule
+                .occurrences(dtstart)
+                .take_while(|occurrence| *occurrence <= to)
+                .collect(),
+            _ => vec![start],
+        };
+        occurrences.extend(self.rdate.iter().copied());
+        occurrences.retain(|occurrence| !self.exdate.contains(occurrence));
+        occurrences.sort();
+        occurrences.dedup();
+
+        let mut instances = Vec::new();
+        for occurrence in &occurrences {
+            let mut instance = match overrides.iter().find(|instance| {
+                instance.uid == self.uid && instance.recurrence_id == Some(*occurrence)
+            }) {
+                Some(instance) => instance.clone(),
+                None => self.instance_at(*occurrence - start, *occurrence),
+            };
+            if instance.clip_to_range(from, to) {
+                instances.push(instance);
+            }
+        }
+
+        // An occurrence after the range can be moved into it by its override
+        for instance in overrides {
+            let recurrence_id = match instance.recurrence_id {
+                Some(recurrence_id) if instance.uid == self.uid => recurrence_id,
+                _ => continue,
+            };
+            if recurrence_id <= to
+                || occurrences.contains(&recurrence_id)
+                || self.exdate.contains(&recurrence_id)
+            {
+                continue;
+            }
+            let is_occurrence = match (&self.rrule, self.dtstart) {
+                (Some(rrule), Some(dtstart)) => rrule
+                    .occurrences(dtstart)
+                    .take_while(|occurrence| *occurrence <= recurrence_id)
+                    .any(|occurrence| occurrence == recurrence_id),
+                _ => false,
+            };
+            let mut instance = instance.clone();
+            if is_occurrence && instance.clip_to_range(from, to) {
+                instances.push(instance);
+            }
+        }
+        instances.sort_by_key(|instance| instance.dtstart.or(instance.effective_due()));
+        instances
+    }
+
+    // Copy of the VTODO moved by the offset, standing for a single occurrence
+    fn instance_at(&self, offset: Duration, occurrence: DateTime<FixedOffset>) -> VTodo {
+        let mut instance = self.clone();
+        instance.dtstart = self.dtstart.map(|dtstart| dtstart + offset);
+        instance.due = self.due.map(|due| due + offset);
+        instance.recurrence_id = Some(occurrence);
+        instance.rrule = None;
+        instance.rdate.clear();
+        instance.exdate.clear();
+        // The original lines of the moved properties no longer match their values
+        instance.raw_properties.retain(|(property, _)| {
+            !matches!(
+                property,
+                Property::DTStart
+                    | Property::Due
+                    | Property::RecurrenceID
+                    | Property::RRule
+                    | Property::RDate
+                    | Property::ExDate
+            )
+        });
+        instance
+    }
+
+    /// Lists the time zones referenced by a TZID parameter in this VTODO.
+    pub fn tzids(&self) -> Vec<&str> {
+        let mut tzids: Vec<&str> = Vec::new();
+        for (_, parameters) in &self.parameters {
+            if let Some(tzid) = parameters.get("TZID") {
+                if !tzids.contains(&tzid) {
+                    tzids.push(tzid);
+                }
+            }
+        }
+        tzids
+    }
+
+    /// Returns a time property of the VTODO along with the form it was given in.
+    pub fn time_value(&self, property: Property) -> Option<DateTimeValue> {
+        let value = match property {
+            Property::DTStamp => Some(self.dtstamp),
+            Property::Completed => self.completed,
+            Property::Created => self.created,
+            Property::DTStart => self.dtstart,
+            Property::LastModified => self.last_modified,
+            Property::RecurrenceID => self.recurrence_id,
+            Property::Due => self.due,
+            _ => None,
+        }?;
+        Some(DateTimeValue::new(
+            &value,
+            utils::find_parameters(&self.parameters, property),
+        ))
+    }
+
+    /// Sets DTSTART or DUE in any of its forms, such as a whole day with DateTimeValue::Date.
+    pub fn set_time_value(
+        &mut self,
+        property: Property,
+        value: DateTimeValue,
+    ) -> Result<(), ICSError> {
+        match property {
+            Property::DTStart => self.dtstart = Some(value.to_date_time()),
+            Property::Due => self.due = Some(value.to_date_time()),
+            _ => {
+                return Err(ICSError::UnexpectedProperty(
+                    property.get_identier().to_string(),
+                ))
+            }
+        }
+        match self
+            .parameters
+            .iter_mut()
+            .find(|(param_property, _)| *param_property == property)
+        {
+            Some((_, parameters)) => value.apply_to(parameters),
+            None => {
+                let mut parameters = Parameters::new();
+                value.apply_to(&mut parameters);
+                self.parameters.push((property, parameters));
+            }
+        }
+        Ok(())
+    }
+
+    /// Places the times given with a TZID in their zone, using the VTIMEZONE of the calendar.
+    pub fn resolve_timezones(&mut self, vtimezones: &[VTimezone]) -> Result<(), ICSError> {
+        if let Some(dtstart) = &mut self.dtstart {
+            let parameters = utils::find_parameters(&self.parameters, Property::DTStart);
+            resolve_local_time(dtstart, parameters, vtimezones)?;
+        }
+        if let Some(due) = &mut self.due {
+            let parameters = utils::find_parameters(&self.parameters, Property::Due);
+            resolve_local_time(due, parameters, vtimezones)?;
+        }
+        if let Some(recurrence_id) = &mut self.recurrence_id {
+            let parameters = utils::find_parameters(&self.parameters, Property::RecurrenceID);
+            resolve_local_time(recurrence_id, parameters, vtimezones)?;
+        }
+        for (index, exdate) in self.exdate.iter_mut().enumerate() {
+            let parameters = utils::find_nth_parameters(&self.parameters, Property::ExDate, index);
+            resolve_local_time(exdate, parameters, vtimezones)?;
+        }
+        for (index, rdate) in self.rdate.iter_mut().enumerate() {
+            let parameters = utils::find_nth_parameters(&self.parameters, Property::RDate, index);
+            resolve_local_time(rdate, parameters, vtimezones)?;
+        }
+        Ok(())
+    }
+
     /// Reads the content of a VTODO object. The buffer passed should already have consumed the BEGIN:VTODO.
-    pub fn parse_from_bufreader(
-        line_reader: &mut Lines<BufReader<File>>,
+    pub fn parse_from_bufreader(line_reader: &mut Lines<impl BufRead>) -> Result<VTodo, ICSError> {
+        VTodo::parse_from_bufreader_with_options(line_reader, &ParseOptions::default())
+    }
+
+    /// Reads the content of a VTODO object with the given options. The buffer passed should already have consumed the BEGIN:VTODO.
+    pub fn parse_from_bufreader_with_options(
+        line_reader: &mut Lines<impl BufRead>,
+        options: &ParseOptions,
     ) -> Result<VTodo, ICSError> {
         let mut vtodo: VTodo = VTodo::new_empty(
             DateTime::from_utc(
@@ -178,10 +643,7 @@ impl VTodo {
             match line {
                 Some(line) => {
                     // Read line
-                    processed_line = match line {
-                        Ok(val) => val,
-                        Err(_) => return Err(ICSError::ReadError),
-                    };
+                    processed_line = line?;
                     // End the process if we have arrived at the end.
                     if processed_line.starts_with("END:VTODO") {
                         break;
@@ -189,6 +651,26 @@ impl VTodo {
                 }
                 None => return Err(ICSError::BeginWithoutEnd),
             }
+            // Some exports separate the components with blank lines
+            if processed_line.trim().is_empty() {
+                current_line = line_reader.next();
+                continue;
+            }
+
+            if processed_line.starts_with("BEGIN:VALARM") {
+                vtodo.alarms.push(VAlarm::parse_from_bufreader_with_options(
+                    line_reader,
+                    options,
+                )?);
+
+                // Consume next line as we have finished the VALARM
+                current_line = line_reader.next();
+                continue;
+            }
+            // No other component can be nested in a VTODO
+            if let Some(component) = processed_line.strip_prefix("BEGIN:") {
+                return Err(ICSError::UnexpectedComponent(component.to_string()));
+            }
 
             // Here we need to be able to process multi line arguments.
             let property_string: String;
@@ -197,7 +679,13 @@ impl VTodo {
 
             // I clone the line here to avoid borrowing it as I might give it to an error.
             // This is probably slow but let's leave that problem for future smarter me.
-            let (property, value) = Property::parse_property(property_string.clone())?;
+            let (property, parameters, value) =
+                Property::parse_property_with_options(property_string.clone(), options)?;
+            if options.preserve_raw {
+                vtodo
+                    .raw_properties
+                    .push((property, property_string.clone()));
+            }
 
             match property {
                 Property::DTStamp => {
@@ -205,7 +693,7 @@ impl VTodo {
                         return Err(ICSError::DuplicateUniqueProperty(property_string));
                     }
                     has_dtstamp = true;
-                    vtodo.dtstamp = value.try_into().unwrap();
+                    vtodo.dtstamp = value.into();
                 }
                 Property::Completed => {
                     utils::apply_unique_property(&mut vtodo.completed, value, property_string)?
@@ -219,19 +707,26 @@ impl VTodo {
                 Property::LastModified => {
                     utils::apply_unique_property(&mut vtodo.last_modified, value, property_string)?
                 }
-                Property::RecurrenceID => todo!(),
-                Property::ExDate => vtodo.exdate.push(value.try_into().unwrap()),
-                Property::RDate => vtodo.rdate.push(value.try_into().unwrap()),
+                Property::RecurrenceID => {
+                    utils::apply_unique_property(&mut vtodo.recurrence_id, value, property_string)?
+                }
+                Property::ExDate => vtodo.exdate.push(value.into()),
+                Property::RDate => vtodo.rdate.push(value.into()),
                 Property::Due => {
                     utils::apply_unique_property(&mut vtodo.due, value, property_string)?
                 }
-                Property::Duration => todo!(),
+                Property::Duration => {
+                    utils::apply_unique_property(&mut vtodo.duration, value, property_string)?
+                }
+                Property::RRule => {
+                    utils::apply_unique_property(&mut vtodo.rrule, value, property_string)?
+                }
                 Property::UID => {
                     if has_uid {
                         return Err(ICSError::DuplicateUniqueProperty(property_string));
                     }
                     has_uid = true;
-                    vtodo.uid = value.try_into().unwrap();
+                    vtodo.uid = value.into();
                 }
                 Property::Description => {
                     utils::apply_unique_property(&mut vtodo.description, value, property_string)?
@@ -242,16 +737,20 @@ impl VTodo {
                 Property::Summary => {
                     utils::apply_unique_property(&mut vtodo.summary, value, property_string)?
                 }
-                Property::Comment => vtodo.comment.push(value.try_into().unwrap()),
-                Property::RelatedTo => vtodo.related_to.push(value.try_into().unwrap()),
-                Property::Resources => vtodo.resources.push(value.try_into().unwrap()),
+                Property::Comment => vtodo.comment.push(value.into()),
+                Property::RelatedTo => vtodo
+                    .related_to
+                    .push(RelatedTo::new(value.into(), &parameters)),
+                Property::Resources => vtodo.resources.push(value.into()),
                 Property::Categories => {
-                    let mut string_vect: Vec<String> = value.try_into().unwrap();
+                    let mut string_vect: Vec<String> = value.into();
                     vtodo.categories.append(&mut string_vect);
                 }
-                Property::Organizer => todo!(),
-                Property::Attendee => todo!(),
-                Property::Contact => todo!(),
+                Property::Organizer => {
+                    utils::apply_unique_property(&mut vtodo.organizer, value, property_string)?
+                }
+                Property::Attendee => vtodo.attendee.push(value.into()),
+                Property::Contact => vtodo.contact.push(value.into()),
                 Property::PercentComplete => {
                     utils::apply_unique_property(&mut vtodo.percent, value, property_string)?
                 }
@@ -265,22 +764,28 @@ impl VTodo {
                     if vtodo.status.is_some() {
                         return Err(ICSError::DuplicateUniqueProperty(property_string));
                     }
-                    let status: Status = value.try_into().unwrap();
+                    let status: Status = value.into();
                     if !status.validate_vtodo() {
                         return Err(ICSError::PropertyConditionNotRespected(property_string));
                     }
                     vtodo.status = Some(status);
                 }
-                Property::URL => todo!(),
-                Property::Attach => todo!(),
+                Property::URL => {
+                    utils::apply_unique_property(&mut vtodo.url, value, property_string)?
+                }
+                Property::Attach => vtodo.attach.push(value.into()),
                 Property::Geo => {
                     utils::apply_unique_property(&mut vtodo.geo, value, property_string)?
                 }
                 Property::Class => {
                     utils::apply_unique_property(&mut vtodo.class, value, property_string)?
                 }
+                Property::XProp => vtodo.x_properties.push(value.into()),
+                Property::IanaProp => vtodo.iana_properties.push(value.into()),
                 _ => return Err(ICSError::UnexpectedProperty(property_string)), // Other properties are not used
             }
+            // Kept even when empty so that repeated properties can be matched by position
+            vtodo.parameters.push((property, parameters));
         }
 
         if !has_uid {
@@ -290,6 +795,365 @@ impl VTodo {
             return Err(ICSError::MissingNecessaryProperty("DTSTAMP".to_string()));
         }
 
+        vtodo.validate_consistency()?;
+        if options.swap_geo {
+            vtodo
+                .warnings
+                .extend(utils::swap_geo(&mut vtodo.geo, &vtodo.uid));
+        }
+
+        Ok(vtodo)
+    }
+
+    /// Writes the VTODO in the ics format, from BEGIN:VTODO to END:VTODO, alarms included.
+    pub fn write(&self) -> String {
+        let mut output = String::from("BEGIN:VTODO\r\n");
+
+        for (_, raw_line) in &self.raw_properties {
+            utils::write_line(&mut output, raw_line);
+        }
+
+        self.write_date_time(&mut output, Property::DTStamp, &self.dtstamp);
+        self.write_property(&mut output, Property::UID, &self.uid);
+
+        if let Some(class) = &self.class {
+            self.write_property(&mut output, Property::Class, &String::from(class.clone()));
+        }
+        if let Some(completed) = &self.completed {
+            self.write_date_time(&mut output, Property::Completed, completed);
+        }
+        if let Some(created) = &self.created {
+            self.write_date_time(&mut output, Property::Created, created);
+        }
+        if let Some(description) = &self.description {
+            self.write_property(
+                &mut output,
+                Property::Description,
+                &utils::escape_text(description),
+            );
+        }
+        if let Some(dtstart) = &self.dtstart {
+            self.write_date_time(&mut output, Property::DTStart, dtstart);
+        }
+        if let Some((lat, long)) = &self.geo {
+            self.write_property(&mut output, Property::Geo, &format!("{lat};{long}"));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            self.write_date_time(&mut output, Property::LastModified, last_modified);
+        }
+        if let Some(location) = &self.location {
+            self.write_property(
+                &mut output,
+                Property::Location,
+                &utils::escape_text(location),
+            );
+        }
+        if let Some(percent) = &self.percent {
+            self.write_property(&mut output, Property::PercentComplete, &percent.to_string());
+        }
+        if let Some(priority) = &self.priority {
+            self.write_property(&mut output, Property::Priority, &priority.to_string());
+        }
+        if let Some(recurrence_id) = &self.recurrence_id {
+            self.write_date_time(&mut output, Property::RecurrenceID, recurrence_id);
+        }
+        if let Some(sequence) = &self.sequence {
+            self.write_property(&mut output, Property::Sequence, &sequence.to_string());
+        }
+        if let Some(status) = &self.status {
+            self.write_property(&mut output, Property::Status, &String::from(status.clone()));
+        }
+        if let Some(summary) = &self.summary {
+            self.write_property(&mut output, Property::Summary, &utils::escape_text(summary));
+        }
+        if let Some(due) = &self.due {
+            self.write_date_time(&mut output, Property::Due, due);
+        }
+        if let Some(duration) = &self.duration {
+            self.write_property(
+                &mut output,
+                Property::Duration,
+                &utils::format_duration(duration),
+            );
+        }
+        if let Some(rrule) = &self.rrule {
+            self.write_property(&mut output, Property::RRule, &rrule.to_string());
+        }
+        if let Some(url) = &self.url {
+            self.write_property(&mut output, Property::URL, &url.to_string());
+        }
+        if let Some(organizer) = &self.organizer {
+            self.write_cal_address(&mut output, Property::Organizer, organizer);
+        }
+        utils::write_attachments(
+            &mut output,
+            &self.attach,
+            &self.parameters,
+            &self.raw_properties,
+        );
+        for attendee in &self.attendee {
+            self.write_cal_address(&mut output, Property::Attendee, attendee);
+        }
+        if !self.categories.is_empty() {
+            self.write_property(
+                &mut output,
+                Property::Categories,
+                &self
+                    .categories
+                    .iter()
+                    .map(|category| utils::escape_text(category))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        for comment in &self.comment {
+            self.write_property(&mut output, Property::Comment, &utils::escape_text(comment));
+        }
+        for (index, contact) in self.contact.iter().enumerate() {
+            self.write_repeated_property(
+                &mut output,
+                Property::Contact,
+                index,
+                &utils::escape_text(contact),
+            );
+        }
+        for (index, exdate) in self.exdate.iter().enumerate() {
+            self.write_repeated_date_time(&mut output, Property::ExDate, index, exdate);
+        }
+        if !utils::has_raw_property(&self.raw_properties, Property::RelatedTo) {
+            // Each relationship has its own RELTYPE
+            for related_to in &self.related_to {
+                utils::write_property(
+                    &mut output,
+                    Property::RelatedTo,
+                    Some(&related_to.parameters()),
+                    &related_to.value,
+                );
+            }
+        }
+        for (index, resources) in self.resources.iter().enumerate() {
+            self.write_repeated_property(&mut output, Property::Resources, index, resources);
+        }
+        for (index, rdate) in self.rdate.iter().enumerate() {
+            self.write_repeated_date_time(&mut output, Property::RDate, index, rdate);
+        }
+        utils::write_x_properties(&mut output, &self.x_properties, &self.raw_properties);
+        utils::write_iana_properties(&mut output, &self.iana_properties, &self.raw_properties);
+
+        for alarm in &self.alarms {
+            output.push_str(&alarm.write());
+        }
+
+        output.push_str("END:VTODO\r\n");
+        output
+    }
+
+    /// Writes the VTODO alone in a calendar with the default PRODID and VERSION, as an ics file.
+    pub fn to_ics_string(&self) -> String {
+        VCalendar::new_empty().write_components(&[Component::Todo(self)])
+    }
+
+    fn write_repeated_property(
+        &self,
+        output: &mut String,
+        property: Property,
+        index: usize,
+        value: &str,
+    ) {
+        // The original lines have already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_nth_parameters(&self.parameters, property, index),
+            value,
+        );
+    }
+
+    fn write_property(&self, output: &mut String, property: Property, value: &str) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        utils::write_property(
+            output,
+            property,
+            utils::find_parameters(&self.parameters, property),
+            value,
+        );
+    }
+
+    fn write_cal_address(&self, output: &mut String, property: Property, value: &CalAdress) {
+        // The original line has already been written
+        if utils::has_raw_property(&self.raw_properties, property) {
+            return;
+        }
+        // Each address has its own parameters
+        utils::write_property(
+            output,
+            property,
+            Some(&value.parameters()),
+            &value.address.to_string(),
+        );
+    }
+
+    fn write_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_parameters(&self.parameters, property);
+        self.write_property(
+            output,
+            property,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+
+    // Each EXDATE or RDATE line has its own VALUE and TZID
+    fn write_repeated_date_time(
+        &self,
+        output: &mut String,
+        property: Property,
+        index: usize,
+        value: &DateTime<FixedOffset>,
+    ) {
+        let parameters = utils::find_nth_parameters(&self.parameters, property, index);
+        self.write_repeated_property(
+            output,
+            property,
+            index,
+            &utils::format_date_time(value, parameters),
+        );
+    }
+
+    /// Verifies the constraints linking several properties of the VTODO together.
+    pub fn validate_consistency(&self) -> Result<(), ICSError> {
+        // A to-do cannot be completed before it has started.
+        if let (Some(dtstart), Some(completed)) = (self.dtstart, self.completed) {
+            if completed < dtstart {
+                return Err(ICSError::PropertyConditionNotRespected(
+                    "COMPLETED".to_string(),
+                ));
+            }
+        }
+
+        // A duration is counted from the start and replaces the due date.
+        if self.duration.is_some() && (self.dtstart.is_none() || self.due.is_some()) {
+            return Err(ICSError::PropertyConditionNotRespected(
+                "DURATION".to_string(),
+            ));
+        }
+
+        // The exceptions and additions are of the same type as the start
+        if self.dtstart.is_some() {
+            utils::validate_date_types(&self.parameters, self.exdate.len(), self.rdate.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds a VTODO one property at a time, the rules of the RFC 5545 are verified by build.
+#[derive(Debug, Clone)]
+pub struct VTodoBuilder {
+    vtodo: VTodo,
+}
+
+impl VTodoBuilder {
+    /// Starts a VTODO with its required DTSTAMP and UID.
+    pub fn new(dtstamp: DateTime<FixedOffset>, uid: String) -> VTodoBuilder {
+        VTodoBuilder {
+            vtodo: VTodo::new_empty(dtstamp, uid),
+        }
+    }
+
+    pub fn summary(mut self, summary: String) -> VTodoBuilder {
+        self.vtodo.summary = Some(summary);
+        self
+    }
+
+    pub fn description(mut self, description: String) -> VTodoBuilder {
+        self.vtodo.description = Some(description);
+        self
+    }
+
+    pub fn location(mut self, location: String) -> VTodoBuilder {
+        self.vtodo.location = Some(location);
+        self
+    }
+
+    pub fn dtstart(mut self, dtstart: DateTime<FixedOffset>) -> VTodoBuilder {
+        self.vtodo.dtstart = Some(dtstart);
+        self
+    }
+
+    pub fn due(mut self, due: DateTime<FixedOffset>) -> VTodoBuilder {
+        self.vtodo.due = Some(due);
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> VTodoBuilder {
+        self.vtodo.duration = Some(duration);
+        self
+    }
+
+    pub fn priority(mut self, priority: usize) -> VTodoBuilder {
+        self.vtodo.priority = Some(priority);
+        self
+    }
+
+    pub fn percent(mut self, percent: usize) -> VTodoBuilder {
+        self.vtodo.percent = Some(percent);
+        self
+    }
+
+    pub fn status(mut self, status: Status) -> VTodoBuilder {
+        self.vtodo.status = Some(status);
+        self
+    }
+
+    pub fn class(mut self, class: Class) -> VTodoBuilder {
+        self.vtodo.class = Some(class);
+        self
+    }
+
+    pub fn add_category(mut self, category: String) -> VTodoBuilder {
+        self.vtodo.categories.push(category);
+        self
+    }
+
+    pub fn add_alarm(mut self, alarm: VAlarm) -> VTodoBuilder {
+        self.vtodo.alarms.push(alarm);
+        self
+    }
+
+    /// Returns the VTODO, or the first rule it does not respect, as parsing it would.
+    pub fn build(self) -> Result<VTodo, ICSError> {
+        let vtodo = self.vtodo;
+        if let Some(priority) = vtodo.priority {
+            validate_integer(Property::Priority, priority as i64)?;
+        }
+        if let Some(percent) = vtodo.percent {
+            validate_integer(Property::PercentComplete, percent as i64)?;
+        }
+        if vtodo
+            .status
+            .as_ref()
+            .is_some_and(|status| !status.validate_vtodo())
+        {
+            return Err(ICSError::PropertyConditionNotRespected(
+                "STATUS".to_string(),
+            ));
+        }
+        // DUE and DURATION cannot be given together
+        vtodo.validate_consistency()?;
+        for alarm in &vtodo.alarms {
+            alarm.validate()?;
+        }
         Ok(vtodo)
     }
 }
@@ -433,3 +1297,890 @@ fn vtodo_duplicate_variable() {
         }
     }
 }
+
+#[test]
+fn vtodo_consistency_completed_before_start() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "uid@example.com".to_string());
+
+    vtodo.dtstart = Some(
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2007, 5, 14, 11, 0, 0)
+            .unwrap(),
+    );
+    vtodo.completed = Some(
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2007, 5, 13, 11, 0, 0)
+            .unwrap(),
+    );
+    assert_eq!(
+        vtodo.validate_consistency().unwrap_err(),
+        ICSError::PropertyConditionNotRespected("COMPLETED".to_string())
+    );
+
+    // Completing it at the exact start time or afterwards is fine
+    vtodo.completed = vtodo.dtstart;
+    assert!(vtodo.validate_consistency().is_ok());
+    vtodo.completed = Some(
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2007, 7, 7, 10, 0, 0)
+            .unwrap(),
+    );
+    assert!(vtodo.validate_consistency().is_ok());
+
+    // Only one of the two is not checked
+    vtodo.dtstart = None;
+    assert!(vtodo.validate_consistency().is_ok());
+}
+
+#[test]
+fn vtodo_tzid_references() {
+    let f = File::open("./tests/test_files/vtodo/example_vtodo_tzid").unwrap();
+    let buf_reader = BufReader::new(f);
+
+    // Consume the first VTODO line
+    let mut lines = buf_reader.lines();
+    lines.next().unwrap().unwrap();
+
+    let vtodo = VTodo::parse_from_bufreader(&mut lines).unwrap();
+
+    assert_eq!(vtodo.tzids(), vec!["America/New_York"]);
+    let expected_date = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 3, 13, 12, 34, 32)
+        .unwrap();
+    assert_eq!(vtodo.dtstart.unwrap(), expected_date);
+}
+
+#[test]
+fn vtodo_write_repeated_parameters() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   DTSTART:20070402T130000Z\r\n\
+                   RDATE;TZID=America/New_York:20070409T090000\r\n\
+                   RDATE:20070416T130000Z\r\n\
+                   EXDATE:20070423T130000Z\r\n\
+                   EXDATE;TZID=America/New_York:20070430T090000\r\n\
+                   RESOURCES;LANGUAGE=fr:PROJECTEUR\r\n\
+                   RESOURCES:EASEL\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    let output = vtodo.write();
+    for line in content.lines().skip(3).take(6) {
+        assert!(output.contains(&format!("{line}\r\n")), "{line}");
+    }
+
+    // Each line is read back with its own parameters
+    let mut lines = Cursor::new(output.into_bytes()).lines();
+    lines.next().unwrap().unwrap();
+    let parsed = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(parsed.rdate, vtodo.rdate);
+    assert_eq!(parsed.exdate, vtodo.exdate);
+    assert_eq!(parsed.resources, vtodo.resources);
+}
+
+#[test]
+fn vtodo_write_with_alarm() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.summary = Some("Submit Revised Internet-Draft".to_string());
+
+    let mut valarm = VAlarm::new_empty(
+        Action::Display,
+        Trigger::Relative(Duration::minutes(-30), Related::Start),
+    );
+    valarm.description = Some("Breakfast meeting with executive team".to_string());
+    valarm.duration = Some(Duration::minutes(15));
+    valarm.repeat = Some(2);
+    vtodo.alarms.push(valarm);
+
+    let output = vtodo.write();
+    assert!(output.ends_with("END:VALARM\r\nEND:VTODO\r\n"));
+    assert!(output.contains("BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT30M\r\n"));
+
+    // Read it back
+    let mut lines = Cursor::new(output.into_bytes()).lines();
+    lines.next().unwrap().unwrap();
+
+    let parsed = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(parsed.uid, vtodo.uid);
+    assert_eq!(parsed.dtstamp, vtodo.dtstamp);
+    assert_eq!(parsed.summary, vtodo.summary);
+    assert_eq!(parsed.alarms.len(), 1);
+
+    let alarm = &parsed.alarms[0];
+    assert_eq!(alarm.action, Action::Display);
+    assert_eq!(
+        alarm.trigger,
+        Trigger::Relative(Duration::minutes(-30), Related::Start)
+    );
+    assert_eq!(
+        alarm.description.as_ref().unwrap(),
+        "Breakfast meeting with executive team"
+    );
+    assert_eq!(alarm.duration.unwrap(), Duration::minutes(15));
+    assert_eq!(alarm.repeat.unwrap(), 2);
+    assert_eq!(alarm.write(), vtodo.alarms[0].write());
+}
+
+#[test]
+fn vtodo_effective_due() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "uid@example.com".to_string());
+    assert_eq!(vtodo.effective_due(), None);
+
+    vtodo.dtstart = Some(dtstamp);
+    assert_eq!(vtodo.effective_due(), None);
+
+    vtodo.duration = Some(Duration::hours(2));
+    assert_eq!(vtodo.effective_due(), Some(dtstamp + Duration::hours(2)));
+
+    vtodo.duration = None;
+    vtodo.due = Some(dtstamp + Duration::days(1));
+    assert_eq!(vtodo.effective_due(), Some(dtstamp + Duration::days(1)));
+}
+
+#[test]
+fn vtodo_preserve_raw() {
+    let expected = fs::read_to_string("./tests/test_files/vtodo/example_vtodo_raw").unwrap();
+    let options = ParseOptions {
+        preserve_raw: true,
+        ..Default::default()
+    };
+
+    let mut lines =
+        BufReader::new(File::open("./tests/test_files/vtodo/example_vtodo_raw").unwrap()).lines();
+    lines.next().unwrap().unwrap();
+    let vtodo = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert!(vtodo.raw_properties.is_empty());
+    assert_ne!(vtodo.write(), expected);
+
+    let mut lines =
+        BufReader::new(File::open("./tests/test_files/vtodo/example_vtodo_raw").unwrap()).lines();
+    lines.next().unwrap().unwrap();
+    let mut vtodo = VTodo::parse_from_bufreader_with_options(&mut lines, &options).unwrap();
+    assert_eq!(vtodo.raw_properties.len(), 9);
+    assert_eq!(vtodo.write(), expected);
+
+    // A changed field is only written once its raw line is removed
+    vtodo.summary = Some("Submit Quebec Income Tax Return for 2007".to_string());
+    assert_eq!(vtodo.write(), expected);
+    vtodo
+        .raw_properties
+        .retain(|(property, _)| *property != Property::Summary);
+    assert!(vtodo
+        .write()
+        .contains("SUMMARY;LANGUAGE=en-CA:Submit Quebec Income Tax Return for 2007\r\n"));
+
+    // Without the raw lines the properties are written from their values
+    vtodo.raw_properties.clear();
+    assert!(vtodo.write().contains("CLASS:CONFIDENTIAL\r\n"));
+}
+
+#[test]
+fn vtodo_display_alarm() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.summary = Some("Submit Revised Internet-Draft".to_string());
+
+    let vtodo = vtodo
+        .display_alarm(Trigger::Relative(Duration::minutes(-30), Related::Start))
+        .with_alarm(VAlarm::new_empty(
+            Action::Audio,
+            Trigger::Relative(Duration::minutes(-5), Related::Start),
+        ));
+    assert_eq!(vtodo.alarms.len(), 2);
+    assert_eq!(vtodo.alarms[0].action, Action::Display);
+    assert_eq!(
+        vtodo.alarms[0].description.as_deref(),
+        Some("Submit Revised Internet-Draft")
+    );
+
+    let output = vtodo.write();
+    assert!(output.contains(
+        "BEGIN:VALARM\r\nACTION:DISPLAY\r\nTRIGGER:-PT30M\r\nDESCRIPTION:Submit Revised Internet-Draft\r\nEND:VALARM\r\n"
+    ));
+    assert!(output.contains("BEGIN:VALARM\r\nACTION:AUDIO\r\nTRIGGER:-PT5M\r\nEND:VALARM\r\n"));
+}
+
+#[test]
+fn vtodo_read_from_memory() {
+    let content = "BEGIN:VTODO\r\n\
+                   UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   SUMMARY:Submit Quebec Income Tax Return for 2006\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:DISPLAY\r\n\
+                   TRIGGER:-PT30M\r\n\
+                   DESCRIPTION:Income tax\r\n\
+                   END:VALARM\r\n\
+                   END:VTODO\r\n";
+
+    let mut lines = Cursor::new(content.as_bytes().to_vec()).lines();
+    lines.next().unwrap().unwrap();
+
+    let vtodo = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(vtodo.uid, "20070313T123432Z-456553@example.com");
+    assert_eq!(
+        vtodo.summary.as_deref(),
+        Some("Submit Quebec Income Tax Return for 2006")
+    );
+    assert_eq!(vtodo.alarms.len(), 1);
+}
+
+#[test]
+fn vtodo_write_example_1() {
+    let expected = fs::read_to_string("./tests/test_files/vtodo/example_vtodo_1").unwrap();
+
+    let mut lines =
+        BufReader::new(File::open("./tests/test_files/vtodo/example_vtodo_1").unwrap()).lines();
+    lines.next().unwrap().unwrap();
+    let output = VTodo::parse_from_bufreader(&mut lines).unwrap().write();
+
+    // Same lines, the order of the properties is free
+    assert!(output.starts_with("BEGIN:VTODO\r\n"));
+    assert!(output.ends_with("END:VTODO\r\n"));
+    let mut output_lines: Vec<&str> = output.split_terminator("\r\n").collect();
+    let mut expected_lines: Vec<&str> = expected.lines().collect();
+    output_lines.sort_unstable();
+    expected_lines.sort_unstable();
+    assert_eq!(output_lines, expected_lines);
+}
+
+#[test]
+fn vtodo_read_duration() {
+    let parse = |properties: &str| {
+        let content = format!(
+            "UID:20070313T123432Z-456553@example.com\r\n\
+             DTSTAMP:20070313T123432Z\r\n\
+             {properties}\
+             END:VTODO\r\n"
+        );
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.into_bytes()).lines())
+    };
+
+    let vtodo = parse("DTSTART:20070313T123432Z\r\nDURATION:PT1H30M\r\n").unwrap();
+    assert_eq!(vtodo.duration, Some(Duration::minutes(90)));
+
+    // DURATION needs a DTSTART and cannot be used with DUE
+    assert_eq!(
+        parse("DURATION:PT1H30M\r\n").unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    assert_eq!(
+        parse("DTSTART:20070313T123432Z\r\nDUE:20070314T123432Z\r\nDURATION:PT1H30M\r\n")
+            .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    assert_eq!(
+        parse("DTSTART:20070313T123432Z\r\nDURATION:PT1H\r\nDURATION:PT2H\r\n").unwrap_err(),
+        ICSError::DuplicateUniqueProperty("DURATION:PT2H".to_string())
+    );
+}
+
+#[test]
+fn vtodo_read_recurrence_id() {
+    let f = File::open("./tests/test_files/vtodo/example_vtodo_recurrence_id").unwrap();
+    let buf_reader = BufReader::new(f);
+
+    // Consume the first VTODO line
+    let mut lines = buf_reader.lines();
+    lines.next().unwrap().unwrap();
+
+    let vtodo = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    let expected_date = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 3, 20, 12, 0, 0)
+        .unwrap();
+    assert_eq!(vtodo.recurrence_id.unwrap(), expected_date);
+    assert!(Component::Todo(&vtodo).has_recurrence_id());
+}
+
+#[test]
+fn vtodo_read_organizer() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   ORGANIZER;CN=John Smith:MAILTO:jsmith@host.com\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    let organizer = vtodo.organizer.as_ref().unwrap();
+    assert_eq!(organizer.address.to_string(), "MAILTO:jsmith@host.com");
+    assert_eq!(organizer.common_name.as_deref(), Some("John Smith"));
+
+    assert!(vtodo
+        .write()
+        .contains("ORGANIZER;CN=John Smith:MAILTO:jsmith@host.com\r\n"));
+}
+
+#[test]
+fn vtodo_to_ics_string() {
+    let mut lines =
+        BufReader::new(File::open("./tests/test_files/vtodo/example_vtodo_1").unwrap()).lines();
+    lines.next().unwrap().unwrap();
+    let vtodo = VTodo::parse_from_bufreader(&mut lines).unwrap();
+
+    let output = vtodo.to_ics_string();
+    assert!(output.starts_with("BEGIN:VCALENDAR\r\nPRODID:-//ics-rs//"));
+    assert!(output.ends_with("END:VTODO\r\nEND:VCALENDAR\r\n"));
+
+    // The calendar holds the same to-do
+    let vcal_object: VCalendar = output.parse().unwrap();
+    let components = vcal_object.components();
+    assert_eq!(components.len(), 1);
+    match components[0] {
+        Component::Todo(parsed) => assert_eq!(parsed.write(), vtodo.write()),
+        _ => panic!("The component should be a VTODO"),
+    }
+}
+
+#[test]
+fn vtodo_read_attendees() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   ATTENDEE;ROLE=REQ-PARTICIPANT;PARTSTAT=ACCEPTED;CN=Jane Doe:mailto:jdoe@example.com\r\n\
+                   ATTENDEE;RSVP=TRUE;X-CUSTOM=value:mailto:jsmith@example.com\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    assert_eq!(vtodo.attendee.len(), 2);
+    assert_eq!(vtodo.attendee[0].partstat, Some(PartStat::Accepted));
+    assert_eq!(vtodo.attendee[1].rsvp, Some(true));
+
+    // Each attendee is written back with its own parameters, in alphabetical order
+    let output = vtodo.write().replace("\r\n ", "");
+    assert!(output.contains(
+        "ATTENDEE;CN=Jane Doe;PARTSTAT=ACCEPTED;ROLE=REQ-PARTICIPANT:mailto:jdoe@example.com\r\n"
+    ));
+    assert!(output.contains("ATTENDEE;RSVP=TRUE;X-CUSTOM=value:mailto:jsmith@example.com\r\n"));
+}
+
+#[test]
+fn vtodo_read_uris() {
+    let parse = |properties: &str| {
+        let content = format!(
+            "UID:20070313T123432Z-456553@example.com\r\n\
+             DTSTAMP:20070313T123432Z\r\n\
+             {properties}\
+             END:VTODO\r\n"
+        );
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.into_bytes()).lines())
+    };
+
+    let vtodo = parse(
+        "URL:http://example.com/pub/calendars/jsmith/mytime.ics\r\n\
+         ATTACH:CID:jsmith.part3.960817T083000.xyzMail@example.com\r\n\
+         ATTACH;FMTTYPE=application/postscript:ftp://example.com/pub/reports/r-960812.ps\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        vtodo.url.as_ref().unwrap().to_string(),
+        "http://example.com/pub/calendars/jsmith/mytime.ics"
+    );
+    assert_eq!(vtodo.attach.len(), 2);
+    assert_eq!(vtodo.attach[0].uri().unwrap().scheme(), "CID");
+    assert_eq!(vtodo.attach[1].uri().unwrap().scheme(), "ftp");
+
+    // Each attachment keeps its own parameters
+    let output = vtodo.write().replace("\r\n ", "");
+    assert!(output.contains("ATTACH:CID:jsmith.part3.960817T083000.xyzMail@example.com\r\n"));
+    assert!(output.contains(
+        "ATTACH;FMTTYPE=application/postscript:ftp://example.com/pub/reports/r-960812.ps\r\n"
+    ));
+
+    // An inline attachment is decoded and encoded again when written
+    let vtodo =
+        parse("ATTACH;FMTTYPE=text/plain;ENCODING=BASE64;VALUE=BINARY:Zm9vYg==\r\n").unwrap();
+    assert_eq!(vtodo.attach, [Attachment::Binary(b"foob".to_vec())]);
+    assert!(vtodo
+        .write()
+        .contains("ATTACH;ENCODING=BASE64;FMTTYPE=text/plain;VALUE=BINARY:Zm9vYg==\r\n"));
+
+    assert_eq!(
+        parse("URL:not a uri\r\n").unwrap_err(),
+        ICSError::UnableToParseProperty("not a uri".to_string())
+    );
+}
+
+#[test]
+fn vtodo_read_related_to_uri() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   RELATED-TO:http://host/item/1\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    assert_eq!(vtodo.related_to[0].value, "http://host/item/1");
+    assert!(vtodo.related_to[0].is_uri());
+    assert!(vtodo.write().contains("RELATED-TO:http://host/item/1\r\n"));
+}
+
+#[test]
+fn vtodo_read_nested_alarm() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:DISPLAY\r\n\
+                   TRIGGER:-PT30M\r\n\
+                   DESCRIPTION:Reminder\r\n\
+                   END:VALARM\r\n\
+                   SUMMARY:Read after the alarm\r\n\
+                   END:VTODO\r\n\
+                   END:VCALENDAR\r\n";
+
+    let mut lines = Cursor::new(content.as_bytes().to_vec()).lines();
+    let vtodo = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(vtodo.alarms.len(), 1);
+    assert_eq!(vtodo.summary, Some("Read after the alarm".to_string()));
+    // The reader stops right after END:VTODO
+    assert_eq!(lines.next().unwrap().unwrap(), "END:VCALENDAR");
+}
+
+#[test]
+fn vtodo_read_spaces_in_values() {
+    // Only the first space of a folded line is removed, the others belong to the value
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   SUMMARY: Leading space kept\r\n\
+                   DESCRIPTION:Folded\r\n  with a space \r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    assert_eq!(vtodo.summary, Some(" Leading space kept".to_string()));
+    assert_eq!(vtodo.description, Some("Folded with a space ".to_string()));
+    assert!(vtodo.write().contains("SUMMARY: Leading space kept\r\n"));
+}
+
+#[test]
+fn vtodo_categories_display() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 3, 13, 12, 34, 32)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070313T123432Z-456553@example.com".to_string());
+    assert_eq!(vtodo.categories_display(), "");
+
+    vtodo.categories = vec!["FAMILY".to_string(), "FINANCE".to_string()];
+    assert_eq!(vtodo.categories_display(), "FAMILY, FINANCE");
+}
+
+#[test]
+fn vtodo_normalize_categories() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   CATEGORIES:WORK,Family\r\n\
+                   CATEGORIES:FAMILY,Finance,WORK\r\n\
+                   END:VTODO\r\n";
+    let mut vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    assert_eq!(
+        vtodo.categories,
+        ["WORK", "Family", "FAMILY", "Finance", "WORK"]
+    );
+
+    let mut case_sensitive = vtodo.clone();
+    case_sensitive.normalize_categories(false);
+    assert_eq!(
+        case_sensitive.categories,
+        ["FAMILY", "Family", "Finance", "WORK"]
+    );
+
+    vtodo.normalize_categories(true);
+    assert_eq!(vtodo.categories, ["Family", "Finance", "WORK"]);
+}
+
+#[test]
+fn vtodo_materialize() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let date = |day: u32, hour: u32| utc.with_ymd_and_hms(2007, 3, day, hour, 0, 0).unwrap();
+
+    // Weekly on mondays, the third week is excluded
+    let mut vtodo = VTodo::new_empty(date(1, 0), "weekly@example.com".to_string());
+    vtodo.summary = Some("Weekly report".to_string());
+    vtodo.dtstart = Some(date(5, 9));
+    vtodo.due = Some(date(5, 10));
+    vtodo.rdate = vec![date(12, 9), date(19, 9), date(26, 9)];
+    vtodo.exdate = vec![date(19, 9)];
+
+    // The second week is moved to the tuesday
+    let mut moved = VTodo::new_empty(date(1, 0), "weekly@example.com".to_string());
+    moved.summary = Some("Moved report".to_string());
+    moved.recurrence_id = Some(date(12, 9));
+    moved.dtstart = Some(date(13, 9));
+    moved.due = Some(date(13, 10));
+
+    let instances = vtodo.materialize(&[moved], date(1, 0), date(20, 0));
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[0].dtstart, Some(date(5, 9)));
+    assert_eq!(instances[0].due, Some(date(5, 10)));
+    assert_eq!(instances[0].recurrence_id, Some(date(5, 9)));
+    assert!(instances[0].rdate.is_empty());
+    assert_eq!(instances[1].summary, Some("Moved report".to_string()));
+    assert_eq!(instances[1].dtstart, Some(date(13, 9)));
+
+    let instances = vtodo.materialize(&[], date(20, 0), date(31, 0));
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].dtstart, Some(date(26, 9)));
+    assert_eq!(instances[0].due, Some(date(26, 10)));
+}
+
+#[test]
+fn vtodo_read_rrule() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   DTSTART:20070514T110000Z\r\n\
+                   RRULE:FREQ=WEEKLY;COUNT=10;BYDAY=MO\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    let rrule = vtodo.rrule.as_ref().unwrap();
+    assert_eq!(rrule.freq, Frequency::Weekly);
+    assert_eq!(rrule.count, Some(10));
+    assert!(vtodo
+        .write()
+        .contains("RRULE:FREQ=WEEKLY;COUNT=10;BYDAY=MO\r\n"));
+
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   RRULE:FREQ=SOMETIMES\r\n\
+                   END:VTODO\r\n";
+    assert_eq!(
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("RRULE".to_string())
+    );
+}
+
+#[test]
+fn vtodo_nested_component() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   BEGIN:VTODO\r\n\
+                   UID:inner@example.com\r\n\
+                   END:VTODO\r\n\
+                   END:VTODO\r\n";
+
+    assert_eq!(
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines())
+            .unwrap_err(),
+        ICSError::UnexpectedComponent("VTODO".to_string())
+    );
+}
+
+#[test]
+fn vtodo_occurrences() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let date = |day: u32| utc.with_ymd_and_hms(1997, 9, day, 9, 0, 0).unwrap();
+
+    // Monday 1997-09-01, on mondays and wednesdays
+    let mut vtodo = VTodo::new_empty(date(1), "weekly@example.com".to_string());
+    vtodo.dtstart = Some(date(1));
+    vtodo.rrule = Some("FREQ=WEEKLY;BYDAY=MO,WE".parse().unwrap());
+    vtodo.exdate = vec![date(8)];
+    vtodo.rdate = vec![date(5)];
+
+    assert_eq!(
+        vtodo.occurrences(date(2), 4),
+        vec![date(3), date(5), date(10), date(15)]
+    );
+
+    vtodo.rrule = Some("FREQ=WEEKLY;BYDAY=MO,WE;COUNT=3".parse().unwrap());
+    assert_eq!(
+        vtodo.occurrences(date(1), 10),
+        vec![date(1), date(3), date(5)]
+    );
+
+    // Without a rule only DTSTART and RDATE are occurrences
+    vtodo.rrule = None;
+    assert_eq!(vtodo.occurrences(date(1), 10), vec![date(1), date(5)]);
+}
+
+#[test]
+fn vtodo_materialize_rrule() {
+    let utc = FixedOffset::east_opt(0).unwrap();
+    let date = |day: u32, hour: u32| utc.with_ymd_and_hms(2007, 3, day, hour, 0, 0).unwrap();
+
+    let mut vtodo = VTodo::new_empty(date(1, 0), "weekly@example.com".to_string());
+    vtodo.dtstart = Some(date(5, 9));
+    vtodo.due = Some(date(5, 10));
+    vtodo.rrule = Some("FREQ=WEEKLY".parse().unwrap());
+
+    let mut moved = VTodo::new_empty(date(1, 0), "weekly@example.com".to_string());
+    moved.recurrence_id = Some(date(12, 9));
+    moved.dtstart = Some(date(13, 9));
+
+    let instances = vtodo.materialize(&[moved], date(1, 0), date(27, 0));
+    let starts: Vec<_> = instances.iter().map(|instance| instance.dtstart).collect();
+    assert_eq!(
+        starts,
+        vec![
+            Some(date(5, 9)),
+            Some(date(13, 9)),
+            Some(date(19, 9)),
+            Some(date(26, 9))
+        ]
+    );
+    assert!(instances[2].rrule.is_none());
+
+    // The occurrence of the 2nd of April is moved into the range
+    let mut moved = VTodo::new_empty(date(1, 0), "weekly@example.com".to_string());
+    moved.recurrence_id = Some(utc.with_ymd_and_hms(2007, 4, 2, 9, 0, 0).unwrap());
+    moved.dtstart = Some(date(20, 14));
+    let instances = vtodo.materialize(&[moved.clone()], date(15, 0), date(27, 0));
+    let starts: Vec<_> = instances.iter().map(|instance| instance.dtstart).collect();
+    assert_eq!(
+        starts,
+        vec![Some(date(19, 9)), Some(date(20, 14)), Some(date(26, 9))]
+    );
+
+    // An override that does not stand for an occurrence is ignored
+    moved.recurrence_id = Some(utc.with_ymd_and_hms(2007, 4, 3, 9, 0, 0).unwrap());
+    assert_eq!(
+        vtodo.materialize(&[moved], date(15, 0), date(27, 0)).len(),
+        2
+    );
+}
+
+#[test]
+fn vtodo_text_escaping() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   SUMMARY:Budget\\, planning\\; review\r\n\
+                   DESCRIPTION:Line1\\nLine2\r\n\
+                   CATEGORIES:FAMILY,C:\\\\share\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    assert_eq!(vtodo.summary, Some("Budget, planning; review".to_string()));
+    assert_eq!(vtodo.description, Some("Line1\nLine2".to_string()));
+    assert_eq!(vtodo.categories, vec!["FAMILY", "C:\\share"]);
+
+    // The values are escaped again when written
+    let output = vtodo.write();
+    assert!(output.contains("SUMMARY:Budget\\, planning\\; review\r\n"));
+    assert!(output.contains("DESCRIPTION:Line1\\nLine2\r\n"));
+    assert!(output.contains("CATEGORIES:FAMILY,C:\\\\share\r\n"));
+
+    // A category with a comma is read back as a single one
+    let mut vtodo = vtodo;
+    vtodo.categories = vec!["Food, Drink".to_string(), "Travel".to_string()];
+    let mut lines = Cursor::new(vtodo.write().into_bytes()).lines();
+    assert_eq!(lines.next().unwrap().unwrap(), "BEGIN:VTODO");
+    let read_back = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(read_back.categories, vtodo.categories);
+}
+
+#[test]
+fn vtodo_diff() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    vtodo.summary = Some("Submit Revised Internet-Draft".to_string());
+    vtodo.due = Some(dtstamp + Duration::days(2));
+    vtodo.status = Some(Status::NeedsAction);
+
+    let mut other = vtodo.clone();
+    other.dtstamp = dtstamp + Duration::hours(1);
+    assert!(vtodo.diff(&other).is_empty());
+
+    other.due = Some(dtstamp + Duration::days(3));
+    other.status = Some(Status::InProgress);
+    assert_eq!(
+        vtodo.diff(&other),
+        vec![
+            FieldChange::Changed(Property::Status),
+            FieldChange::Changed(Property::Due)
+        ]
+    );
+
+    other.summary = None;
+    other.categories.push("WORK".to_string());
+    let changes = vtodo.diff(&other);
+    assert!(changes.contains(&FieldChange::Removed(Property::Summary)));
+    assert!(changes.contains(&FieldChange::Added(Property::Categories)));
+}
+
+#[test]
+fn vtodo_date_values() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   DUE;VALUE=DATE:20070501\r\n\
+                   END:VTODO\r\n";
+    let mut vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+
+    let date = |day| chrono::NaiveDate::from_ymd_opt(2007, 5, day).unwrap();
+    assert_eq!(
+        vtodo.time_value(Property::Due),
+        Some(DateTimeValue::Date(date(1)))
+    );
+
+    // A whole day start is written as a date
+    vtodo
+        .set_time_value(Property::DTStart, DateTimeValue::Date(date(1)))
+        .unwrap();
+    let output = vtodo.write();
+    assert!(output.contains("DTSTART;VALUE=DATE:20070501\r\n"));
+    assert!(output.contains("DUE;VALUE=DATE:20070501\r\n"));
+
+    // And can be changed to another form
+    vtodo
+        .set_time_value(
+            Property::Due,
+            DateTimeValue::Local(date(2).and_hms_opt(9, 0, 0).unwrap()),
+        )
+        .unwrap();
+    assert!(vtodo.write().contains("DUE:20070502T090000\r\n"));
+
+    assert!(vtodo
+        .set_time_value(Property::Summary, DateTimeValue::Date(date(1)))
+        .is_err());
+}
+
+#[test]
+fn vtodo_x_properties() {
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   X-MOZ-GENERATION:3\r\n\
+                   X-APPLE-SORT-ORDER;X-PARAM=1:12\r\n\
+                   BEGIN:VALARM\r\n\
+                   ACTION:AUDIO\r\n\
+                   TRIGGER:-PT30M\r\n\
+                   X-WR-ALARMUID:1234\r\n\
+                   END:VALARM\r\n\
+                   END:VTODO\r\n";
+
+    let vtodo =
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).unwrap();
+    assert_eq!(vtodo.x_properties.len(), 2);
+    assert_eq!(vtodo.x_properties[1].name, "X-APPLE-SORT-ORDER");
+    assert_eq!(vtodo.alarms[0].x_properties[0].value, "1234");
+
+    // They are written back as they were read
+    let output = vtodo.write();
+    assert!(output.contains("X-MOZ-GENERATION:3\r\nX-APPLE-SORT-ORDER;X-PARAM=1:12\r\n"));
+    assert!(output.contains("X-WR-ALARMUID:1234\r\n"));
+    let mut lines = Cursor::new(output.into_bytes()).lines();
+    lines.next();
+    let read_back = VTodo::parse_from_bufreader(&mut lines).unwrap();
+    assert_eq!(read_back.x_properties, vtodo.x_properties);
+}
+
+#[test]
+fn vtodo_effective_percent() {
+    let dtstamp = FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2007, 5, 14, 10, 32, 11)
+        .unwrap();
+    let mut vtodo = VTodo::new_empty(dtstamp, "20070514T103211Z-123404@example.com".to_string());
+    assert_eq!(vtodo.effective_percent(), 0);
+
+    // Derived from the status
+    vtodo.status = Some(Status::Completed);
+    assert_eq!(vtodo.effective_percent(), 100);
+    vtodo.status = Some(Status::NeedsAction);
+    assert_eq!(vtodo.effective_percent(), 0);
+
+    // The stored value comes first
+    vtodo.status = Some(Status::InProgress);
+    vtodo.percent = Some(40);
+    assert_eq!(vtodo.effective_percent(), 40);
+
+    // A lenient parse clamps the value, a strict one refuses it
+    let content = "UID:20070313T123432Z-456553@example.com\r\n\
+                   DTSTAMP:20070313T123432Z\r\n\
+                   PERCENT-COMPLETE:150\r\n\
+                   END:VTODO\r\n";
+    let vtodo = VTodo::parse_from_bufreader_with_options(
+        &mut Cursor::new(content.as_bytes().to_vec()).lines(),
+        &ParseOptions::lenient(),
+    )
+    .unwrap();
+    assert_eq!(vtodo.percent, Some(100));
+    assert!(
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.as_bytes().to_vec()).lines()).is_err()
+    );
+}
+
+#[test]
+fn vtodo_canonical_parameters() {
+    let read = |attendee: &str| {
+        let content = format!(
+            "UID:20070313T123432Z-456553@example.com\r\n\
+             DTSTAMP:20070313T123432Z\r\n\
+             {attendee}\r\n\
+             END:VTODO\r\n"
+        );
+        VTodo::parse_from_bufreader(&mut Cursor::new(content.into_bytes()).lines()).unwrap()
+    };
+    let first = read("ATTENDEE;ROLE=CHAIR;CN=Jane Doe;X-NOTE=1:mailto:jdoe@example.com");
+    let second = read("ATTENDEE;X-NOTE=1;CN=Jane Doe;ROLE=CHAIR:mailto:jdoe@example.com");
+
+    // The same parameters given in another order are written identically
+    assert_eq!(first.write(), second.write());
+    assert!(first
+        .write()
+        .contains("ATTENDEE;CN=Jane Doe;ROLE=CHAIR;X-NOTE=1:mailto:jdoe@example.com\r\n"));
+}
+
+#[test]
+fn vtodo_builder() {
+    let date = |day| {
+        FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2007, 5, day, 10, 0, 0)
+            .unwrap()
+    };
+    let builder = VTodoBuilder::new(date(1), "20070514T103211Z-123404@example.com".to_string())
+        .summary("Submit Revised Internet-Draft".to_string())
+        .dtstart(date(1))
+        .due(date(14))
+        .add_category("WORK".to_string())
+        .add_category("IETF".to_string())
+        .priority(1);
+
+    let vtodo = builder.clone().build().unwrap();
+    assert_eq!(vtodo.due, Some(date(14)));
+    assert_eq!(vtodo.categories, ["WORK", "IETF"]);
+    assert!(vtodo.write().contains("PRIORITY:1\r\n"));
+
+    // DUE and DURATION are exclusive
+    assert_eq!(
+        builder
+            .clone()
+            .duration(Duration::days(2))
+            .build()
+            .unwrap_err(),
+        ICSError::PropertyConditionNotRespected("DURATION".to_string())
+    );
+    assert_eq!(
+        builder.clone().priority(10).build().unwrap_err(),
+        ICSError::PropertyConditionNotRespected("PRIORITY must be 0..=9".to_string())
+    );
+    assert!(builder.status(Status::Tentative).build().is_err());
+}